@@ -1,8 +1,11 @@
 // Shared type definitions for the application
 
+// Saved rows are text; generated rows carry native numbers, booleans and nulls
+export type Cell = string | number | boolean | null
+
 export interface CsvData {
   headers: string[]
-  rows: string[][]
+  rows: Cell[][]
 }
 
 export interface ApiResponse {
@@ -20,15 +23,36 @@ export interface Message {
 }
 
 // New types for header extraction flow
+export type InferredKind =
+  | 'empty' | 'boolean' | 'uuid' | 'integer' | 'decimal'
+  | 'date' | 'timestamp' | 'email' | 'enum' | 'text'
+
+export interface InferredColumn {
+  name: string
+  kind: InferredKind
+  confidence: number  // Share of values that fit `kind`, 0 to 1
+  column: ColumnSpec
+}
+
 export interface HeadersResponse {
   headers: string[]
+  columns: InferredColumn[]
+  column_types: Record<string, string>  // Inferred type per header
   message: string
 }
 
+export type OutputFormat = 'csv' | 'tsv' | 'json' | 'ndjson' | 'sql' | 'markdown'
+
+// How null cells are written in downloads
+export type NullValue = 'empty' | 'NULL' | '\\N' | 'null'
+
 export interface GenerateRequest {
   row_count?: number
   save?: boolean
   headers?: string[]  // Custom headers for generation
+  seed?: number  // Reproduce a previous run
+  format?: OutputFormat  // Download as a file instead of JSON
+  null_as?: NullValue  // Default: 'empty'
 }
 
 // Dataset management types
@@ -41,6 +65,9 @@ export interface Dataset {
   data_type: 'uploaded' | 'custom' | 'generated'
   has_sample_data: boolean
   column_types?: string  // JSON string of column type mappings
+  seed?: number | null  // Seed that produced a 'generated' dataset
+  columns?: string | null  // JSON column specs from an imported schema file
+  locale?: string | null  // Locale pack of columns that name none
 }
 
 export interface SaveDatasetRequest {
@@ -49,15 +76,54 @@ export interface SaveDatasetRequest {
   data_type: 'uploaded' | 'custom' | 'generated'
   column_types?: Record<string, string>
   sample_data?: string[][]
+  columns?: ColumnSpec[]  // Per-column type parameters, validated on save
+  locale?: string  // Locale pack of every column that names none, e.g. 'de_DE'
+}
+
+// One column's type and generator parameters
+export interface ColumnSpec {
+  name: string
+  type?: string  // Defaults to 'auto'
+  min?: number | string  // Numbers, or YYYY-MM-DD for dates
+  max?: number | string
+  format?: string  // strftime for dates, '#' digit pattern for phones
+  precision?: number
+  currency?: string
+  min_length?: number
+  max_length?: number
+  values?: string[]
+  weights?: number[]  // Relative frequency of each of 'values'
+  from?: string  // Name column an email is built from
+  within?: string  // Country column a city belongs to
+  min_column?: string  // Column whose value in the same row bounds this one
+  max_column?: string
+  expression?: string  // Value of a 'computed' column, e.g. 'price * quantity'
+  strategy?: 'sequence' | 'random' | 'uuid_v4' | 'uuid_v7' | 'ulid' | 'snowflake'  // Of an 'id' column
+  start?: number  // First id of a sequence
+  step?: number
+  prefix?: string  // Text before each id, e.g. 'CUST-'
+  width?: number  // Digits a numeric id is zero-padded to
+  pattern?: string  // Regular expression of a 'regex' column, e.g. 'ORD-[0-9]{6}'
+  dictionary?: string  // Saved dictionary of a 'dictionary' column
+  locale?: string  // Locale pack, overriding the dataset's
+  null_rate?: number
+  unique?: boolean
 }
 
 export interface GenerateFromDatasetRequest {
   row_count: number
+  use_sample_data?: boolean  // Learn from saved sample rows (default: true)
+  seed?: number
+  save?: boolean  // Save the output as a 'generated' dataset
+  format?: OutputFormat
+  null_as?: NullValue
 }
 
 export interface GenerateFromDatasetResponse {
   data: CsvData
+  seed: number
   message: string
+  dataset_id?: number  // Present when save was requested
 }
 
 export interface SaveDatasetResponse {
@@ -69,3 +135,90 @@ export interface ColumnTypeOption {
   value: string
   label: string
 }
+
+// GET /api/types
+export interface ColumnTypeParam {
+  name: string
+  type: 'integer' | 'number' | 'date' | 'string' | 'string_list' | 'column'
+  required: boolean
+  description: string
+}
+
+export interface ColumnTypeInfo {
+  name: string
+  description: string
+  params: ColumnTypeParam[]
+}
+
+// GET /api/locales
+export interface LocaleInfo {
+  code: string  // e.g. 'de_DE'
+  name: string  // The locale's own name, e.g. 'Deutsch (Deutschland)'
+}
+
+// Projects: related datasets generated together
+export interface ProjectTable {
+  name: string
+  dataset_id: number
+  rows?: number
+}
+
+export interface ForeignKey {
+  column: string  // 'table.column' of the child key
+  references: string  // 'table.column' of the parent key
+  children?: { min: number, max: number }  // Child rows per parent row
+  orphan_rate?: number
+}
+
+export interface ProjectSpec {
+  name: string
+  tables: ProjectTable[]
+  foreign_keys?: ForeignKey[]
+}
+
+export interface ProjectSummary {
+  id: number
+  name: string
+  created_at: string
+  table_count: number
+}
+
+export interface GenerateProjectRequest {
+  row_count?: number
+  use_sample_data?: boolean
+  seed?: number
+  format?: 'json' | 'sql' | 'markdown'  // One file with every table
+  null_as?: NullValue
+}
+
+export interface GenerateProjectResponse {
+  tables: { name: string, data: CsvData }[]
+  seed: number
+  message: string
+}
+
+// Dictionaries: saved value lists picked from by 'dictionary' columns
+export interface DictionarySpec {
+  name: string
+  values: string[]
+  weights?: number[]  // Relative frequency of each value
+}
+
+export interface DictionaryEntry {
+  value: string
+  weight?: number
+}
+
+export interface DictionarySummary {
+  id: number
+  name: string
+  created_at: string
+  entry_count: number
+}
+
+export interface Dictionary {
+  id: number
+  name: string
+  created_at: string
+  entries: DictionaryEntry[]
+}
@@ -0,0 +1,4 @@
+-- Add seed to datasets table
+-- seed: RNG seed that produced a 'generated' dataset, so the run can be reproduced
+
+ALTER TABLE datasets ADD COLUMN seed INTEGER DEFAULT NULL;
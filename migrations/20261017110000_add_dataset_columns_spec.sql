@@ -0,0 +1,4 @@
+-- Add column specs to datasets table
+-- columns: JSON array of column specs (type plus generator parameters) from an imported schema file
+
+ALTER TABLE datasets ADD COLUMN columns TEXT DEFAULT NULL;
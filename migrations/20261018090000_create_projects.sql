@@ -0,0 +1,11 @@
+-- Projects group datasets into related tables generated together
+-- tables: JSON array of {name, dataset_id, rows}
+-- foreign_keys: JSON array of {column, references, children, orphan_rate} between the tables
+
+CREATE TABLE projects (
+                          id INTEGER PRIMARY KEY AUTOINCREMENT,
+                          name TEXT NOT NULL,
+                          created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                          tables TEXT NOT NULL,
+                          foreign_keys TEXT NOT NULL
+);
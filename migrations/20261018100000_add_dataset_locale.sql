@@ -0,0 +1,4 @@
+-- Add locale to datasets table
+-- locale: locale pack (e.g. de_DE) of every column that does not name its own
+
+ALTER TABLE datasets ADD COLUMN locale TEXT DEFAULT NULL;
@@ -0,0 +1,9 @@
+-- Dictionaries are named value lists that dictionary columns pick from
+-- entries: JSON array of {value, weight}; weight is left out of unweighted entries
+
+CREATE TABLE dictionaries (
+                              id INTEGER PRIMARY KEY AUTOINCREMENT,
+                              name TEXT NOT NULL UNIQUE,
+                              created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                              entries TEXT NOT NULL
+);
@@ -0,0 +1,402 @@
+//! Safe copies of uploaded CSVs: each column's values are kept, replaced
+//! with generated ones, hashed, masked, redacted or generalized, while the
+//! headers, row count and blank cells stay as they were.
+use crate::csv_parser::CsvData;
+use crate::generators::{DATE_FORMATS, SmartGenerator};
+use crate::schema::ColumnSpec;
+use chrono::{NaiveDate, NaiveDateTime};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+const TIMESTAMP_FORMATS: &[&str] = &["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S"];
+
+/// Hex digits kept of a hashed value's SHA-256 digest.
+const HASH_LENGTH: usize = 16;
+
+/// The policies of an anonymize request:
+///
+/// ```json
+/// {
+///   "columns": {
+///     "email": {"action": "replace"},
+///     "ssn": {"action": "mask", "keep_last": 4},
+///     "birth_date": {"action": "generalize", "to": "year"}
+///   },
+///   "default": {"action": "keep"}
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnonymizeSpec {
+    /// Policy of each header; headers not listed get `default`.
+    #[serde(default)]
+    pub columns: HashMap<String, Policy>,
+    #[serde(default)]
+    pub default: Policy,
+}
+
+/// What happens to a column's values. Blank cells stay blank under every
+/// policy.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Policy {
+    #[default]
+    Keep,
+    /// A generated value of `type`, detected from the header when not given.
+    /// Equal values get equal replacements, so repeated keys still match.
+    Replace {
+        #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+        type_name: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        locale: Option<String>,
+    },
+    /// The first 16 hex digits of the SHA-256 of `salt` followed by the
+    /// value. Without a salt, common values can be recovered by hashing
+    /// guesses.
+    Hash {
+        #[serde(default)]
+        salt: String,
+    },
+    /// Letters and digits become `*`, except the first `keep_first` and last
+    /// `keep_last` characters; punctuation and spaces stay.
+    Mask {
+        #[serde(default)]
+        keep_first: usize,
+        #[serde(default)]
+        keep_last: usize,
+    },
+    /// Every value becomes `with`.
+    Redact {
+        #[serde(default = "redacted")]
+        with: String,
+    },
+    /// Dates and timestamps cut down `to` a year, month or day, or numbers
+    /// rounded down to a multiple of `round_to`. Values that are neither fail
+    /// the request rather than pass through.
+    Generalize {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        to: Option<DatePart>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        round_to: Option<f64>,
+    },
+}
+
+fn redacted() -> String {
+    "[REDACTED]".to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatePart {
+    Year,
+    Month,
+    Day,
+}
+
+impl DatePart {
+    fn format(self) -> &'static str {
+        match self {
+            DatePart::Year => "%Y",
+            DatePart::Month => "%Y-%m",
+            DatePart::Day => "%Y-%m-%d",
+        }
+    }
+}
+
+impl AnonymizeSpec {
+    /// Every column named must be a header, and generalize policies need
+    /// exactly one of `to` and a positive `round_to`.
+    pub fn validate(&self, headers: &[String]) -> Result<(), String> {
+        let mut names: Vec<&String> = self.columns.keys().collect();
+        names.sort();
+        for name in names {
+            if !headers.contains(name) {
+                return Err(format!("No header named '{}'", name));
+            }
+        }
+        let policies = self.columns.iter().map(|(name, p)| (name.as_str(), p));
+        for (name, policy) in policies.chain([("default", &self.default)]) {
+            if let Policy::Generalize { to, round_to } = policy {
+                match (to, round_to) {
+                    (Some(_), None) => {}
+                    (None, Some(step)) if *step > 0.0 && step.is_finite() => {}
+                    (None, Some(step)) => {
+                        return Err(format!(
+                            "{}: round_to must be a number above 0, got {}",
+                            name, step
+                        ));
+                    }
+                    _ => {
+                        return Err(format!(
+                            "{}: generalize needs either 'to' or 'round_to'",
+                            name
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn policy(&self, header: &str) -> &Policy {
+        self.columns.get(header).unwrap_or(&self.default)
+    }
+}
+
+/// Applies `spec` to every cell of `data`. Replacements are drawn from the
+/// column types' generators, seeded with `seed`.
+pub fn anonymize(data: &CsvData, spec: &AnonymizeSpec, seed: u64) -> Result<CsvData, String> {
+    spec.validate(&data.headers)?;
+
+    let columns: Vec<ColumnSpec> = data
+        .headers
+        .iter()
+        .map(|header| match spec.policy(header) {
+            Policy::Replace { type_name, locale } => {
+                let mut column = ColumnSpec::new(header, type_name.as_deref().unwrap_or("auto"));
+                column.locale = locale.clone();
+                column
+            }
+            _ => ColumnSpec::new(header, "auto"),
+        })
+        .collect();
+    let generator = SmartGenerator::from_columns(&columns).map_err(|e| e.to_string())?;
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut replacements: Vec<HashMap<String, String>> = vec![HashMap::new(); columns.len()];
+    let context = vec![None; columns.len()];
+    let mut rows = Vec::with_capacity(data.rows.len());
+    for (row_index, row) in data.rows.iter().enumerate() {
+        let mut anonymized = Vec::with_capacity(row.len());
+        for (column, value) in row.iter().enumerate() {
+            if value.trim().is_empty() {
+                anonymized.push(value.clone());
+                continue;
+            }
+            let Some(header) = data.headers.get(column) else {
+                anonymized.push(value.clone());
+                continue;
+            };
+            let cell = match spec.policy(header) {
+                Policy::Keep => value.clone(),
+                Policy::Replace { .. } => {
+                    let seen = &mut replacements[column];
+                    match seen.get(value) {
+                        Some(replacement) => replacement.clone(),
+                        None => {
+                            let index = seen.len() + 1;
+                            let replacement = generator
+                                .generate_cell(column, index, &context, &mut rng)
+                                .text()
+                                .into_owned();
+                            seen.insert(value.clone(), replacement.clone());
+                            replacement
+                        }
+                    }
+                }
+                Policy::Hash { salt } => hash(salt, value),
+                Policy::Mask {
+                    keep_first,
+                    keep_last,
+                } => mask(value, *keep_first, *keep_last),
+                Policy::Redact { with } => with.clone(),
+                Policy::Generalize { to, round_to } => generalize(value, *to, *round_to)
+                    .ok_or_else(|| {
+                        let expected = if to.is_some() { "a date" } else { "a number" };
+                        format!(
+                            "{} (row {}): '{}' is not {}",
+                            header,
+                            row_index + 1,
+                            value,
+                            expected
+                        )
+                    })?,
+            };
+            anonymized.push(cell);
+        }
+        rows.push(anonymized);
+    }
+
+    Ok(CsvData {
+        headers: data.headers.clone(),
+        rows,
+    })
+}
+
+fn hash(salt: &str, value: &str) -> String {
+    let digest = Sha256::new()
+        .chain_update(salt)
+        .chain_update(value)
+        .finalize();
+    digest
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>()[..HASH_LENGTH]
+        .to_string()
+}
+
+fn mask(value: &str, keep_first: usize, keep_last: usize) -> String {
+    let length = value.chars().count();
+    value
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let kept = i < keep_first || i + keep_last >= length;
+            if kept || !c.is_alphanumeric() { c } else { '*' }
+        })
+        .collect()
+}
+
+fn generalize(value: &str, to: Option<DatePart>, round_to: Option<f64>) -> Option<String> {
+    let value = value.trim();
+    if let Some(part) = to {
+        let date = DATE_FORMATS
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+            .or_else(|| {
+                TIMESTAMP_FORMATS
+                    .iter()
+                    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+                    .map(|time| time.date())
+            })?;
+        return Some(date.format(part.format()).to_string());
+    }
+
+    let step = round_to?;
+    let number = value.parse::<f64>().ok().filter(|n| n.is_finite())?;
+    let places = step
+        .to_string()
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len());
+    Some(format!("{:.*}", places, (number / step).floor() * step))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(headers: &[&str], rows: &[&[&str]]) -> CsvData {
+        CsvData {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: rows
+                .iter()
+                .map(|row| row.iter().map(|v| v.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    fn spec(json: &str) -> AnonymizeSpec {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_value_policies() {
+        assert_eq!(mask("555-12-3456", 0, 4), "***-**-3456");
+        assert_eq!(mask("Anna", 1, 0), "A***");
+        assert_eq!(mask("ab", 3, 3), "ab");
+
+        assert_eq!(hash("", "ann@example.com").len(), HASH_LENGTH);
+        assert_eq!(hash("s", "x"), hash("s", "x"));
+        assert_ne!(hash("s", "x"), hash("t", "x"));
+
+        assert_eq!(
+            generalize("31/01/1985", Some(DatePart::Year), None),
+            Some("1985".into())
+        );
+        assert_eq!(
+            generalize("2024-03-05T10:00:00", Some(DatePart::Month), None),
+            Some("2024-03".into())
+        );
+        assert_eq!(
+            generalize("52340", None, Some(1000.0)),
+            Some("52000".into())
+        );
+        assert_eq!(generalize("-7", None, Some(5.0)), Some("-10".into()));
+        assert_eq!(generalize("12.37", None, Some(0.1)), Some("12.3".into()));
+        assert_eq!(generalize("soon", Some(DatePart::Day), None), None);
+    }
+
+    #[test]
+    fn test_anonymize_keeps_shape() {
+        let data = data(
+            &["customer_id", "email", "ssn", "salary", "notes", "city"],
+            &[
+                &["c-1", "ann@corp.com", "555-12-3456", "52340", "vip", "Oslo"],
+                &["c-2", "", "555-98-7654", "61000", "", "Rome"],
+                &[
+                    "c-1",
+                    "ann@corp.com",
+                    "555-12-3456",
+                    "52340",
+                    "late",
+                    "Oslo",
+                ],
+            ],
+        );
+        let spec = spec(
+            r#"{
+                "columns": {
+                    "customer_id": {"action": "hash", "salt": "pepper"},
+                    "email": {"action": "replace"},
+                    "ssn": {"action": "mask", "keep_last": 4},
+                    "salary": {"action": "generalize", "round_to": 10000},
+                    "notes": {"action": "redact"}
+                }
+            }"#,
+        );
+        let result = anonymize(&data, &spec, 7).unwrap();
+
+        assert_eq!(result.headers, data.headers);
+        assert_eq!(result.rows.len(), 3);
+        let (first, second, third) = (&result.rows[0], &result.rows[1], &result.rows[2]);
+        assert_eq!(first[0], third[0]);
+        assert_ne!(first[0], "c-1");
+        assert!(first[1].contains('@') && first[1] != "ann@corp.com");
+        assert_eq!(first[1], third[1]);
+        assert_eq!(second[1], "");
+        assert_eq!(first[2], "***-**-3456");
+        assert_eq!(second[3], "60000");
+        assert_eq!(first[4], "[REDACTED]");
+        assert_eq!(second[4], "");
+        assert_eq!(first[5], "Oslo");
+
+        assert_eq!(anonymize(&data, &spec, 7).unwrap().rows, result.rows);
+    }
+
+    #[test]
+    fn test_anonymize_errors() {
+        let data = data(&["born", "age"], &[&["1985-01-31", "n/a"]]);
+
+        let unknown = spec(r#"{"columns": {"dob": {"action": "keep"}}}"#);
+        assert_eq!(
+            anonymize(&data, &unknown, 1).unwrap_err(),
+            "No header named 'dob'"
+        );
+
+        let both = spec(r#"{"columns": {"born": {"action": "generalize"}}}"#);
+        assert_eq!(
+            anonymize(&data, &both, 1).unwrap_err(),
+            "born: generalize needs either 'to' or 'round_to'"
+        );
+
+        let not_number = spec(r#"{"columns": {"age": {"action": "generalize", "round_to": 10}}}"#);
+        assert_eq!(
+            anonymize(&data, &not_number, 1).unwrap_err(),
+            "age (row 1): 'n/a' is not a number"
+        );
+
+        let all = spec(r#"{"default": {"action": "replace", "type": "telepathy"}}"#);
+        assert!(anonymize(&data, &all, 1).is_err());
+
+        assert!(
+            serde_json::from_str::<AnonymizeSpec>(r#"{"default": {"action": "shred"}}"#).is_err()
+        );
+        assert!(
+            serde_json::from_str::<AnonymizeSpec>(r#"{"default": {"action": "mask", "keep": 2}}"#)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use super::generate::file_response;
+use crate::anonymize::{self, AnonymizeSpec};
+use crate::csv_parser::parse_csv_from_bytes;
+use crate::generators::resolve_seed;
+use crate::multipart::parse_form;
+use crate::output::{NullValue, OutputFormat};
+use crate::value::{Table, Value};
+use actix_multipart::Multipart;
+use actix_web::{HttpResponse, Responder};
+use log::info;
+
+fn invalid_anonymize_response(message: &str) -> HttpResponse {
+    info!("Anonymize request rejected: {}", message);
+    HttpResponse::BadRequest().json(serde_json::json!({ "error": message }))
+}
+
+/// Returns a copy of an uploaded CSV (`file`) with each column's policy
+/// applied, from the JSON `policies` field. An optional `seed` field makes
+/// replacements reproducible; the seed used comes back in `X-Seed`.
+pub async fn anonymize(payload: Multipart) -> impl Responder {
+    let form = match parse_form(payload).await {
+        Ok(form) => form,
+        Err(e) => return invalid_anonymize_response(&e.to_string()),
+    };
+    let text = |field: &str| {
+        form.get(field)
+            .map(|bytes| String::from_utf8_lossy(bytes).trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    let Some(file) = form.get("file").filter(|file| !file.is_empty()) else {
+        return invalid_anonymize_response("No file data received");
+    };
+    let spec: AnonymizeSpec = match text("policies").map(|json| serde_json::from_str(&json)) {
+        Some(Ok(spec)) => spec,
+        Some(Err(e)) => return invalid_anonymize_response(&format!("Invalid policies: {}", e)),
+        None => AnonymizeSpec::default(),
+    };
+    let seed = match text("seed").map(|seed| seed.parse::<u64>()) {
+        Some(Ok(seed)) => resolve_seed(Some(seed)),
+        Some(Err(_)) => return invalid_anonymize_response("seed must be a whole number"),
+        None => resolve_seed(None),
+    };
+
+    let data = match parse_csv_from_bytes(file) {
+        Ok(data) => data,
+        Err(e) => return invalid_anonymize_response(&format!("Failed to parse CSV: {}", e)),
+    };
+    info!(
+        "Anonymizing {} rows of {} columns with seed {}",
+        data.rows.len(),
+        data.headers.len(),
+        seed
+    );
+
+    match anonymize::anonymize(&data, &spec, seed) {
+        Ok(anonymized) => {
+            let table = Table {
+                headers: anonymized.headers,
+                rows: anonymized
+                    .rows
+                    .into_iter()
+                    .map(|row| row.into_iter().map(Value::from).collect())
+                    .collect(),
+            };
+            file_response(
+                &table,
+                OutputFormat::Csv,
+                NullValue::Empty,
+                "anonymized",
+                seed,
+                None,
+            )
+        }
+        Err(message) => invalid_anonymize_response(&message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{App, test, web};
+
+    fn multipart_request(fields: &[(&str, &str)]) -> test::TestRequest {
+        let boundary = "----AnonymizeBoundary";
+        let mut body = String::new();
+        for (name, value) in fields {
+            let filename = if *name == "file" {
+                "; filename=\"export.csv\""
+            } else {
+                ""
+            };
+            body.push_str(&format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"{}\r\n\r\n{}\r\n",
+                boundary, name, filename, value
+            ));
+        }
+        body.push_str(&format!("--{}--\r\n", boundary));
+
+        test::TestRequest::post()
+            .uri("/anonymize")
+            .insert_header((
+                "content-type",
+                format!("multipart/form-data; boundary={}", boundary),
+            ))
+            .set_payload(body)
+    }
+
+    #[actix_web::test]
+    async fn test_anonymize() {
+        let app =
+            test::init_service(App::new().route("/anonymize", web::post().to(anonymize))).await;
+        let file = "name,phone,born\nAnn Lee,555-0100,1985-01-31\nBo Park,555-0199,\n";
+        let policies = r#"{
+            "columns": {
+                "name": {"action": "replace"},
+                "phone": {"action": "mask", "keep_last": 2},
+                "born": {"action": "generalize", "to": "year"}
+            }
+        }"#;
+
+        let req = multipart_request(&[("file", file), ("policies", policies), ("seed", "9")])
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        assert_eq!(resp.headers().get("X-Seed").unwrap(), "9");
+        assert!(
+            resp.headers()
+                .get("Content-Disposition")
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .contains("anonymized.csv")
+        );
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        let lines: Vec<&str> = body.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "name,phone,born");
+        assert!(lines[1].ends_with(",***-**00,1985"));
+        assert!(!lines[1].starts_with("Ann Lee"));
+        assert!(lines[2].ends_with(",***-**99,"));
+    }
+
+    #[actix_web::test]
+    async fn test_anonymize_rejects_bad_requests() {
+        let app =
+            test::init_service(App::new().route("/anonymize", web::post().to(anonymize))).await;
+
+        for (fields, error) in [
+            (vec![("policies", "{}")], "No file data received"),
+            (
+                vec![
+                    ("file", "a\n1\n"),
+                    ("policies", r#"{"columns": {"b": {"action": "keep"}}}"#),
+                ],
+                "No header named 'b'",
+            ),
+            (
+                vec![("file", "a\n1\n"), ("seed", "soon")],
+                "seed must be a whole number",
+            ),
+        ] {
+            let req = multipart_request(&fields).to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+            let body: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(body["error"], error);
+        }
+
+        let req =
+            multipart_request(&[("file", "a\n1\n"), ("policies", "{\"columns\": 3}")]).to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert!(
+            body["error"]
+                .as_str()
+                .unwrap()
+                .starts_with("Invalid policies")
+        );
+    }
+}
@@ -1,10 +1,19 @@
-use crate::csv_parser::CsvData;
-use crate::db::models::{GenerateFromDatasetRequest, SaveDatasetRequest};
+use super::generate::{file_response, generate_with_generator};
+use super::stream::{stream_response, stream_row_count_error};
+use crate::db::models::{
+    FidelityRequest, GenerateFromDatasetRequest, GenerationSource, SaveDatasetRequest,
+    StreamFromDatasetRequest,
+};
 use crate::db::operations;
-use crate::generators::{DataGenerator, SmartGenerator};
+use crate::fidelity;
+use crate::generators::{DataGenerator, DatasetGenerator, resolve_seed};
+use crate::output::OutputFormat;
+use crate::profile;
+use crate::schema::{self, ColumnSpec, SchemaErrors};
 use actix_web::{HttpResponse, Responder, web};
 use log::{error, info};
 use sqlx::SqlitePool;
+use sqlx::error::BoxDynError;
 
 pub async fn list(pool: web::Data<SqlitePool>) -> impl Responder {
     info!("Listing all datasets");
@@ -20,22 +29,87 @@ pub async fn list(pool: web::Data<SqlitePool>) -> impl Responder {
     }
 }
 
+/// The validated column specs of a save or update request, merged over its
+/// headers. `None` when the request carries no specs, in which case its
+/// plain `column_types` are still checked. An unknown dataset locale is
+/// rejected here too, as are columns naming a missing dictionary.
+async fn request_columns(
+    pool: &SqlitePool,
+    req: &SaveDatasetRequest,
+) -> Result<Option<Vec<ColumnSpec>>, HttpResponse> {
+    if let Some(error) = req.locale.as_deref().and_then(schema::locale_error) {
+        return Err(invalid_columns_response(&SchemaErrors(vec![error])));
+    }
+    let Some(columns) = &req.columns else {
+        let errors = req
+            .column_types
+            .as_ref()
+            .map(|column_types| schema::column_type_errors(&req.headers, column_types))
+            .unwrap_or_default();
+        if !errors.is_empty() {
+            return Err(invalid_columns_response(&SchemaErrors(errors)));
+        }
+        return Ok(None);
+    };
+
+    let mut columns = columns.clone();
+    if let Err(e) = operations::load_dictionaries(pool, &mut columns).await {
+        error!("Failed to load dictionaries: {}", e);
+        return Err(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to load dictionaries: {}", e)
+        })));
+    }
+    schema::dataset_columns(&req.headers, req.column_types.as_ref(), &columns)
+        .map(Some)
+        .map_err(|errors| invalid_columns_response(&errors))
+}
+
+fn invalid_columns_response(errors: &SchemaErrors) -> HttpResponse {
+    info!("Column specs rejected with {} error(s)", errors.0.len());
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "error": errors.to_string(),
+        "errors": errors.0
+    }))
+}
+
 pub async fn save(
     pool: web::Data<SqlitePool>,
     req: web::Json<SaveDatasetRequest>,
 ) -> impl Responder {
     info!("Saving dataset: {}", req.name);
 
-    match operations::save_dataset(
-        pool.get_ref(),
-        &req.name,
-        &req.headers,
-        &req.data_type,
-        req.column_types.as_ref(),
-        req.sample_data.as_deref(),
-    )
-    .await
-    {
+    let columns = match request_columns(pool.get_ref(), &req).await {
+        Ok(columns) => columns,
+        Err(response) => return response,
+    };
+    // Spec types take precedence over the request's plain type map.
+    let spec_types = columns.as_deref().map(schema::column_type_map);
+    let column_types = spec_types.as_ref().or(req.column_types.as_ref());
+
+    let saved: Result<i64, BoxDynError> = async {
+        let mut tx = pool.begin().await?;
+        let id = operations::insert_dataset(
+            &mut tx,
+            &req.name,
+            &req.headers,
+            &req.data_type,
+            column_types,
+            req.sample_data.as_deref(),
+            None,
+        )
+        .await?;
+        if let Some(columns) = &columns {
+            operations::set_dataset_columns(&mut *tx, id, Some(columns)).await?;
+        }
+        if let Some(locale) = &req.locale {
+            operations::set_dataset_locale(&mut *tx, id, Some(locale)).await?;
+        }
+        tx.commit().await?;
+        Ok(id)
+    }
+    .await;
+
+    match saved {
         Ok(id) => {
             info!("Dataset saved with id: {}", id);
             HttpResponse::Ok().json(serde_json::json!({
@@ -73,6 +147,78 @@ pub async fn get_one(pool: web::Data<SqlitePool>, path: web::Path<i64>) -> impl
     }
 }
 
+/// Most rows a fidelity report generates.
+const MAX_FIDELITY_ROWS: usize = 100_000;
+
+/// Generates rows from a dataset and reports how closely each column
+/// matches its stored sample rows.
+pub async fn fidelity(
+    pool: web::Data<SqlitePool>,
+    path: web::Path<i64>,
+    req: web::Json<FidelityRequest>,
+) -> impl Responder {
+    let id = path.into_inner();
+    let row_count = req.row_count.unwrap_or(1000);
+    let seed = resolve_seed(req.seed);
+
+    info!(
+        "Comparing {} generated rows with the sample of dataset {} (seed {})",
+        row_count, id, seed
+    );
+
+    if row_count == 0 || row_count > MAX_FIDELITY_ROWS {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("row_count must be between 1 and {}", MAX_FIDELITY_ROWS)
+        }));
+    }
+
+    let (source, generator) =
+        match load_generator(pool.get_ref(), id, req.use_sample_data, row_count).await {
+            Ok(loaded) => loaded,
+            Err(response) => return response,
+        };
+    if source.sample.rows.is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Dataset with id {} has no sample rows to compare with", id)
+        }));
+    }
+
+    let table = generate_with_generator(generator, row_count, seed);
+    let report = fidelity::compare(&source.sample, &table.text_rows());
+    info!(
+        "Generated rows of dataset '{}' score {:.3}",
+        source.dataset.name, report.score
+    );
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "dataset_id": id,
+        "seed": seed,
+        "report": report
+    }))
+}
+
+/// Per-column statistics of a dataset's stored sample rows.
+pub async fn profile(pool: web::Data<SqlitePool>, path: web::Path<i64>) -> impl Responder {
+    let id = path.into_inner();
+    info!("Profiling dataset with id: {}", id);
+
+    match operations::get_datasets(pool.get_ref(), id).await {
+        Ok(Some((dataset, csv_data))) => HttpResponse::Ok().json(serde_json::json!({
+            "dataset": dataset,
+            "profile": profile::profile(&csv_data)
+        })),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Dataset with id {} not found", id)
+        })),
+        Err(e) => {
+            error!("Failed to profile dataset: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to profile dataset: {}", e)
+            }))
+        }
+    }
+}
+
 pub async fn delete(pool: web::Data<SqlitePool>, path: web::Path<i64>) -> impl Responder {
     let id = path.into_inner();
     info!("Deleting dataset with id: {}", id);
@@ -96,6 +242,50 @@ pub async fn delete(pool: web::Data<SqlitePool>, path: web::Path<i64>) -> impl R
     }
 }
 
+/// Loads a dataset and builds a generator for `row_count` rows, or returns the
+/// error response.
+async fn load_generator(
+    pool: &SqlitePool,
+    id: i64,
+    use_sample_data: Option<bool>,
+    row_count: usize,
+) -> Result<(GenerationSource, DatasetGenerator), HttpResponse> {
+    let source = match operations::get_generation_source(pool, id).await {
+        Ok(Some(source)) => source,
+        Ok(None) => {
+            return Err(HttpResponse::NotFound().json(serde_json::json!({
+                "error": format!("Dataset with id {} not found", id)
+            })));
+        }
+        Err(e) => {
+            error!("Failed to fetch dataset: {}", e);
+            return Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to fetch dataset: {}", e)
+            })));
+        }
+    };
+
+    let use_sample_data = use_sample_data.unwrap_or(true);
+    if use_sample_data && !source.sample.rows.is_empty() {
+        info!(
+            "Using {} sample rows as generation source",
+            source.sample.rows.len()
+        );
+    }
+
+    let generator = DatasetGenerator::from_source(&source, use_sample_data)
+        .and_then(|generator| generator.check_row_count(row_count).map(|_| generator));
+    match generator {
+        Ok(generator) => Ok((source, generator)),
+        Err(e) => {
+            error!("Cannot generate from dataset {}: {}", id, e);
+            Err(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": e.to_string()
+            })))
+        }
+    }
+}
+
 pub async fn generate_from_dataset(
     pool: web::Data<SqlitePool>,
     path: web::Path<i64>,
@@ -103,8 +293,12 @@ pub async fn generate_from_dataset(
 ) -> impl Responder {
     let id = path.into_inner();
     let row_count = req.row_count.unwrap_or(20);
+    let seed = resolve_seed(req.seed);
 
-    info!("Generating {} rows from dataset with id: {}", row_count, id);
+    info!(
+        "Generating {} rows from dataset with id: {} (seed {})",
+        row_count, id, seed
+    );
 
     if row_count == 0 || row_count > 1000 {
         return HttpResponse::BadRequest().json(serde_json::json!({
@@ -112,52 +306,106 @@ pub async fn generate_from_dataset(
         }));
     }
 
-    // Fetch dataset
-    let dataset_result = operations::get_datasets(pool.get_ref(), id).await;
+    let (source, generator) =
+        match load_generator(pool.get_ref(), id, req.use_sample_data, row_count).await {
+            Ok(loaded) => loaded,
+            Err(response) => return response,
+        };
 
-    let (dataset, _) = match dataset_result {
-        Ok(Some(data)) => data,
-        Ok(None) => {
-            return HttpResponse::NotFound().json(serde_json::json!({
-                "error": format!("Dataset with id {} not found", id)
-            }));
-        }
-        Err(e) => {
-            error!("Failed to fetch dataset: {}", e);
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Failed to fetch dataset: {}", e)
-            }));
-        }
-    };
+    let csv_data = generate_with_generator(generator, row_count, seed);
+    let GenerationSource {
+        dataset,
+        column_types,
+        ..
+    } = source;
 
-    let headers: Vec<String> = match serde_json::from_str(&dataset.headers) {
-        Ok(h) => h,
-        Err(e) => {
-            error!("Failed to parse headers: {}", e);
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to parse dataset headers"
-            }));
+    info!(
+        "Generated {} rows from dataset '{}'",
+        row_count, dataset.name
+    );
+
+    let mut dataset_id = None;
+    if req.save.unwrap_or(false) {
+        let name = format!("{} (Generated)", dataset.name);
+        let column_types = (!column_types.is_empty()).then_some(&column_types);
+        match operations::save_generated_dataset(
+            pool.get_ref(),
+            &name,
+            &csv_data,
+            column_types,
+            seed,
+        )
+        .await
+        {
+            Ok(new_id) => {
+                info!("Generated rows saved as dataset {}", new_id);
+                dataset_id = Some(new_id);
+            }
+            Err(e) => {
+                error!("Failed to save generated data: {}", e);
+                return HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": format!("Failed to save generated data: {}", e)
+                }));
+            }
         }
-    };
+    }
+
+    if let Some(format) = req.format {
+        return file_response(
+            &csv_data,
+            format,
+            req.null_as.unwrap_or_default(),
+            &dataset.name,
+            seed,
+            dataset_id,
+        );
+    }
 
-    let generator = SmartGenerator::new(headers.clone());
+    let mut response = serde_json::json!({
+        "data": csv_data,
+        "seed": seed,
+        "message": format!("Generated {} rows from dataset '{}'", row_count, dataset.name)
+    });
+    if let Some(id) = dataset_id {
+        response["dataset_id"] = serde_json::json!(id);
+    }
 
-    let mut rng = rand::rng();
-    let rows: Vec<Vec<String>> = (1..=row_count)
-        .map(|i| generator.generate_row(i, &mut rng))
-        .collect();
+    HttpResponse::Ok().json(response)
+}
 
-    let csv_data = CsvData { headers, rows };
+pub async fn generate_from_dataset_stream(
+    pool: web::Data<SqlitePool>,
+    path: web::Path<i64>,
+    req: web::Json<StreamFromDatasetRequest>,
+) -> impl Responder {
+    let id = path.into_inner();
+    let row_count = req.row_count.unwrap_or(20);
+    let seed = resolve_seed(req.seed);
+    let format = req.format.unwrap_or(OutputFormat::Csv);
 
     info!(
-        "Generated {} rows from dataset '{}'",
-        row_count, dataset.name
+        "Streaming {} rows as {:?} from dataset with id: {} (seed {})",
+        row_count, format, id, seed
     );
 
-    HttpResponse::Ok().json(serde_json::json!({
-        "data": csv_data,
-        "message": format!("Generated {} rows from dataset '{}'", row_count, dataset.name)
-    }))
+    if let Some(response) = stream_row_count_error(row_count) {
+        return response;
+    }
+
+    let (source, generator) =
+        match load_generator(pool.get_ref(), id, req.use_sample_data, row_count).await {
+            Ok(loaded) => loaded,
+            Err(response) => return response,
+        };
+
+    stream_response(
+        generator,
+        row_count,
+        seed,
+        format,
+        req.null_as.unwrap_or_default(),
+        &source.dataset.name,
+    )
 }
 
 pub async fn update(
@@ -168,17 +416,39 @@ pub async fn update(
     let id = path.into_inner();
     info!("Updating dataset with id: {}", id);
 
-    match operations::update_dataset(
-        pool.get_ref(),
-        id,
-        &req.name,
-        &req.headers,
-        &req.data_type,
-        req.column_types.as_ref(),
-        req.sample_data.as_deref(),
-    )
-    .await
-    {
+    let columns = match request_columns(pool.get_ref(), &req).await {
+        Ok(columns) => columns,
+        Err(response) => return response,
+    };
+    // Spec types take precedence over the request's plain type map.
+    let spec_types = columns.as_deref().map(schema::column_type_map);
+    let column_types = spec_types.as_ref().or(req.column_types.as_ref());
+
+    let updated: Result<bool, BoxDynError> = async {
+        let mut tx = pool.begin().await?;
+        let found = operations::update_dataset(
+            &mut tx,
+            id,
+            &req.name,
+            &req.headers,
+            &req.data_type,
+            column_types,
+            req.sample_data.as_deref(),
+        )
+        .await?;
+        if !found {
+            return Ok(false);
+        }
+        if let Some(columns) = &columns {
+            operations::set_dataset_columns(&mut *tx, id, Some(columns)).await?;
+        }
+        operations::set_dataset_locale(&mut *tx, id, req.locale.as_deref()).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+    .await;
+
+    match updated {
         Ok(true) => {
             info!("Dataset {} updated successfully", id);
             HttpResponse::Ok().json(serde_json::json!({
@@ -245,14 +515,12 @@ mod tests {
         pool: SqlitePool,
         route_config: impl FnOnce(&mut web::ServiceConfig),
     ) -> impl Service<actix_http::Request, Response = ServiceResponse, Error = Error> {
-        let app = test::init_service(
+        test::init_service(
             App::new()
                 .app_data(web::Data::new(pool.clone()))
                 .configure(route_config),
         )
-        .await;
-
-        app
+        .await
     }
 
     #[actix_web::test]
@@ -311,6 +579,8 @@ mod tests {
             data_type: "custom".to_string(),
             column_types: None,
             sample_data: None,
+            columns: None,
+            locale: None,
         };
 
         let req = test::TestRequest::post()
@@ -342,6 +612,8 @@ mod tests {
                 vec!["1".to_string(), "Alice".to_string()],
                 vec!["2".to_string(), "Bob".to_string()],
             ]),
+            columns: None,
+            locale: None,
         };
 
         let req = test::TestRequest::post()
@@ -364,7 +636,7 @@ mod tests {
         .await;
 
         let mut column_types = HashMap::new();
-        column_types.insert("id".to_string(), "number".to_string());
+        column_types.insert("id".to_string(), "integer".to_string());
         column_types.insert("email".to_string(), "email".to_string());
 
         let payload = SaveDatasetRequest {
@@ -373,6 +645,8 @@ mod tests {
             data_type: "custom".to_string(),
             column_types: Some(column_types),
             sample_data: None,
+            columns: None,
+            locale: None,
         };
 
         let req = test::TestRequest::post()
@@ -385,161 +659,758 @@ mod tests {
     }
 
     #[actix_web::test]
-    async fn test_get_one_success() {
+    async fn test_save_with_column_params() {
         let pool = test_utils::setup_test_db().await;
-        let headers = vec!["col1".to_string(), "col2".to_string()];
-        let sample_data = vec![vec!["a".to_string(), "b".to_string()]];
-
-        let dataset_id =
-            operations::save_dataset(&pool, "test", &headers, "custom", None, Some(&sample_data))
-                .await
-                .unwrap();
-
-        let app = init_test_service_with_routes(pool, |cfg| {
-            cfg.route("/datasets/{id}", web::get().to(get_one));
+        let app = init_test_service_with_routes(pool.clone(), |cfg| {
+            cfg.route("/datasets", web::post().to(save)).route(
+                "/datasets/{id}/generate",
+                web::post().to(generate_from_dataset),
+            );
         })
         .await;
 
-        let req = test::TestRequest::get()
-            .uri(&format!("/datasets/{}", dataset_id))
+        let payload = serde_json::json!({
+            "name": "people",
+            "headers": ["id", "age", "joined"],
+            "data_type": "custom",
+            "columns": [
+                {"name": "age", "type": "age", "min": 30, "max": 31},
+                {"name": "joined", "type": "date", "min": "2024-02-01", "max": "2024-02-29", "format": "%d.%m.%Y"}
+            ]
+        });
+        let req = test::TestRequest::post()
+            .uri("/datasets")
+            .set_json(&payload)
             .to_request();
         let resp = test::call_service(&app, req).await;
-
         assert!(resp.status().is_success());
         let body: serde_json::Value = test::read_body_json(resp).await;
-        assert_eq!(body["dataset"]["name"].as_str().unwrap(), "test");
-        assert_eq!(body["data"]["headers"].as_array().unwrap().len(), 2);
-        assert_eq!(body["data"]["rows"].as_array().unwrap().len(), 1);
+        let id = body["id"].as_i64().unwrap();
+
+        let source = operations::get_generation_source(&pool, id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(source.column_types["age"], "age");
+        assert_eq!(source.column_types["id"], "auto");
+        assert_eq!(source.columns.unwrap()[0], ColumnSpec::new("id", "auto"));
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/generate", id))
+            .set_json(serde_json::json!({"row_count": 30}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        for row in body["data"]["rows"].as_array().unwrap() {
+            assert!(row[1] == 30 || row[1] == 31);
+            assert!(row[2].as_str().unwrap().ends_with(".02.2024"));
+        }
     }
 
     #[actix_web::test]
-    async fn test_get_one_not_found() {
+    async fn test_save_rejects_invalid_column_params() {
         let pool = test_utils::setup_test_db().await;
-        let app = init_test_service_with_routes(pool, |cfg| {
-            cfg.route("/datasets/{id}", web::get().to(get_one));
+        let app = init_test_service_with_routes(pool.clone(), |cfg| {
+            cfg.route("/datasets", web::post().to(save));
         })
         .await;
 
-        let req = test::TestRequest::get().uri("/datasets/999").to_request();
+        let payload = serde_json::json!({
+            "name": "people",
+            "headers": ["age", "price", "sku"],
+            "data_type": "custom",
+            "columns": [
+                {"name": "age", "type": "age", "min": 90},
+                {"name": "price", "type": "money", "format": "%Y"},
+                {"name": "sku", "type": "regex", "pattern": "SKU-(?=x)\\d{4}"},
+                {"name": "missing", "type": "text"}
+            ]
+        });
+        let req = test::TestRequest::post()
+            .uri("/datasets")
+            .set_json(&payload)
+            .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
         let body: serde_json::Value = test::read_body_json(resp).await;
-        assert!(body["error"].as_str().unwrap().contains("not found"));
+        let messages: Vec<&str> = body["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["message"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "min (90) is greater than max (80, the default)",
+                "'format' is not supported for type 'money'",
+                "invalid pattern 'SKU-(?=x)\\d{4}': unsupported lookahead at position 5",
+                "no header with this name",
+            ]
+        );
+        assert!(operations::list_datasets(&pool).await.unwrap().is_empty());
     }
 
     #[actix_web::test]
-    async fn test_delete_success() {
+    async fn test_save_rejects_invalid_column_types() {
         let pool = test_utils::setup_test_db().await;
-        let headers = vec!["col1".to_string()];
-
-        let dataset_id =
-            operations::save_dataset(&pool, "to_delete", &headers, "custom", None, None)
-                .await
-                .unwrap();
-
-        let app = init_test_service_with_routes(pool, |cfg| {
-            cfg.route("/datasets/{id}", web::delete().to(delete));
+        let dataset_id = operations::save_dataset(
+            &pool,
+            "orders",
+            &["status".to_string()],
+            "custom",
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let app = init_test_service_with_routes(pool.clone(), |cfg| {
+            cfg.route("/datasets", web::post().to(save))
+                .route("/datasets/{id}", web::put().to(update));
         })
         .await;
 
-        let req = test::TestRequest::delete()
-            .uri(&format!("/datasets/{}", dataset_id))
-            .to_request();
-        let resp = test::call_service(&app, req).await;
+        let plain = serde_json::json!({
+            "name": "orders",
+            "headers": ["status", "note"],
+            "data_type": "custom",
+            "column_types": {"status": "telepathy", "unused": "telepathy"}
+        });
+        let with_columns = serde_json::json!({
+            "name": "orders",
+            "headers": ["status", "note"],
+            "data_type": "custom",
+            "column_types": {"status": "telepathy"},
+            "columns": [{"name": "note", "type": "text"}]
+        });
+        for req in [
+            test::TestRequest::post().uri("/datasets").set_json(&plain),
+            test::TestRequest::post()
+                .uri("/datasets")
+                .set_json(&with_columns),
+            test::TestRequest::put()
+                .uri(&format!("/datasets/{}", dataset_id))
+                .set_json(&plain),
+        ] {
+            let resp = test::call_service(&app, req.to_request()).await;
+            assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+            let body: serde_json::Value = test::read_body_json(resp).await;
+            let error = body["error"].as_str().unwrap();
+            assert!(
+                error.starts_with("column_types 'status': unknown type 'telepathy'"),
+                "{}",
+                error
+            );
+            assert_eq!(body["errors"].as_array().unwrap().len(), 1);
+        }
 
-        assert!(resp.status().is_success());
-        let body: serde_json::Value = test::read_body_json(resp).await;
-        assert!(body["message"].as_str().unwrap().contains("successfully"));
+        assert_eq!(operations::list_datasets(&pool).await.unwrap().len(), 1);
+        let (dataset, _) = operations::get_datasets(&pool, dataset_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!dataset.headers.contains("note"));
     }
 
     #[actix_web::test]
-    async fn test_delete_not_found() {
+    async fn test_save_rejects_invalid_enum_weights() {
         let pool = test_utils::setup_test_db().await;
-        let app = init_test_service_with_routes(pool, |cfg| {
-            cfg.route("/datasets/{id}", web::delete().to(delete));
+        let app = init_test_service_with_routes(pool.clone(), |cfg| {
+            cfg.route("/datasets", web::post().to(save));
         })
         .await;
 
-        let req = test::TestRequest::delete()
-            .uri("/datasets/999")
-            .to_request();
-        let resp = test::call_service(&app, req).await;
+        for (entry, message) in [
+            ("enum: a=1, b", "'b' has no weight"),
+            (
+                "enum: a=-1, b=2",
+                "weight of 'a' must be a number of at least 0",
+            ),
+            ("enum: a=0, b=0", "at least one weight must be above 0"),
+        ] {
+            let req = test::TestRequest::post()
+                .uri("/datasets")
+                .set_json(serde_json::json!({
+                    "name": "orders",
+                    "headers": ["status"],
+                    "data_type": "custom",
+                    "column_types": {"status": entry}
+                }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+            let body: serde_json::Value = test::read_body_json(resp).await;
+            let error = body["error"].as_str().unwrap();
+            assert!(
+                error.starts_with("column_types 'status'") && error.contains(message),
+                "{}",
+                error
+            );
+        }
 
-        assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+        assert!(operations::list_datasets(&pool).await.unwrap().is_empty());
     }
 
     #[actix_web::test]
-    async fn test_generate_from_dataset_success() {
+    async fn test_generate_download_with_nulls() {
         let pool = test_utils::setup_test_db().await;
-        let headers = vec!["id".to_string(), "name".to_string(), "email".to_string()];
-
-        let dataset_id = operations::save_dataset(&pool, "test", &headers, "custom", None, None)
-            .await
-            .unwrap();
-
-        let app = init_test_service_with_routes(pool, |cfg| {
-            cfg.route(
+        let app = init_test_service_with_routes(pool.clone(), |cfg| {
+            cfg.route("/datasets", web::post().to(save)).route(
                 "/datasets/{id}/generate",
                 web::post().to(generate_from_dataset),
             );
         })
         .await;
 
-        let payload = GenerateFromDatasetRequest {
-            row_count: Some(10),
-        };
-
+        let payload = serde_json::json!({
+            "name": "people",
+            "headers": ["id", "nickname"],
+            "data_type": "custom",
+            "columns": [{"name": "nickname", "type": "text", "null_rate": 1.0}]
+        });
         let req = test::TestRequest::post()
-            .uri(&format!("/datasets/{}/generate", dataset_id))
+            .uri("/datasets")
             .set_json(&payload)
             .to_request();
         let resp = test::call_service(&app, req).await;
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let id = body["id"].as_i64().unwrap();
 
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/generate", id))
+            .set_json(serde_json::json!({"row_count": 2, "format": "sql", "null_as": "NULL"}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
         assert!(resp.status().is_success());
-        let body: serde_json::Value = test::read_body_json(resp).await;
-        assert_eq!(body["data"]["rows"].as_array().unwrap().len(), 10);
-        assert!(body["message"].as_str().unwrap().contains("10 rows"));
+        let body = test::read_body(resp).await;
+        assert_eq!(
+            std::str::from_utf8(&body).unwrap(),
+            "INSERT INTO \"people\" (\"id\", \"nickname\") VALUES (1, NULL);\n\
+             INSERT INTO \"people\" (\"id\", \"nickname\") VALUES (2, NULL);\n"
+        );
     }
 
     #[actix_web::test]
-    async fn test_generate_from_dataset_default_row_count() {
+    async fn test_generate_with_dataset_locale() {
         let pool = test_utils::setup_test_db().await;
-        let headers = vec!["id".to_string()];
-
-        let dataset_id = operations::save_dataset(&pool, "test", &headers, "custom", None, None)
-            .await
-            .unwrap();
-
-        let app = init_test_service_with_routes(pool, |cfg| {
-            cfg.route(
+        let app = init_test_service_with_routes(pool.clone(), |cfg| {
+            cfg.route("/datasets", web::post().to(save)).route(
                 "/datasets/{id}/generate",
                 web::post().to(generate_from_dataset),
             );
         })
         .await;
 
-        let payload = GenerateFromDatasetRequest { row_count: None };
-
+        let payload = serde_json::json!({
+            "name": "kunden",
+            "headers": ["zip", "joined_date", "phone"],
+            "data_type": "custom",
+            "locale": "de_DE",
+            "columns": [{"name": "phone", "type": "phone", "locale": "ja_JP"}]
+        });
         let req = test::TestRequest::post()
-            .uri(&format!("/datasets/{}/generate", dataset_id))
+            .uri("/datasets")
             .set_json(&payload)
             .to_request();
         let resp = test::call_service(&app, req).await;
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let id = body["id"].as_i64().unwrap();
 
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/generate", id))
+            .set_json(serde_json::json!({"row_count": 5}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
         assert!(resp.status().is_success());
         let body: serde_json::Value = test::read_body_json(resp).await;
-        assert_eq!(body["data"]["rows"].as_array().unwrap().len(), 20);
+        for row in body["data"]["rows"].as_array().unwrap() {
+            let zip = row[0].as_str().unwrap();
+            assert!(zip.len() == 5 && zip.chars().all(|c| c.is_ascii_digit()));
+            let joined = row[1].as_str().unwrap();
+            assert!(chrono::NaiveDate::parse_from_str(joined, "%d.%m.%Y").is_ok());
+            assert!(row[2].as_str().unwrap().starts_with("+81 "));
+        }
+
+        let payload = serde_json::json!({
+            "name": "kunden",
+            "headers": ["zip"],
+            "data_type": "custom",
+            "locale": "xx_XX"
+        });
+        let req = test::TestRequest::post()
+            .uri("/datasets")
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body["errors"][0]["message"],
+            "Unknown locale 'xx_XX' (expected one of: en_US, de_DE, fr_FR, ja_JP, pt_BR)"
+        );
     }
 
     #[actix_web::test]
-    async fn test_generate_from_dataset_invalid_row_count_zero() {
+    async fn test_generate_unique_columns() {
+        let pool = test_utils::setup_test_db().await;
+        let app = init_test_service_with_routes(pool.clone(), |cfg| {
+            cfg.route("/datasets", web::post().to(save)).route(
+                "/datasets/{id}/generate",
+                web::post().to(generate_from_dataset),
+            );
+        })
+        .await;
+
+        let payload = serde_json::json!({
+            "name": "accounts",
+            "headers": ["email", "tier"],
+            "data_type": "custom",
+            "columns": [
+                {"name": "email", "type": "email", "unique": true},
+                {"name": "tier", "type": "enum", "values": ["free", "pro", "team"], "unique": true}
+            ]
+        });
+        let req = test::TestRequest::post()
+            .uri("/datasets")
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let id = body["id"].as_i64().unwrap();
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/generate", id))
+            .set_json(serde_json::json!({"row_count": 3}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let mut tiers: Vec<&str> = body["data"]["rows"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|row| row[1].as_str().unwrap())
+            .collect();
+        tiers.sort();
+        assert_eq!(tiers, vec!["free", "pro", "team"]);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/generate", id))
+            .set_json(serde_json::json!({"row_count": 4}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body["error"],
+            "Unique column 'tier' has only 3 possible values, but 4 rows were requested"
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_get_one_success() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["col1".to_string(), "col2".to_string()];
+        let sample_data = vec![vec!["a".to_string(), "b".to_string()]];
+
+        let dataset_id =
+            operations::save_dataset(&pool, "test", &headers, "custom", None, Some(&sample_data))
+                .await
+                .unwrap();
+
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route("/datasets/{id}", web::get().to(get_one));
+        })
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/datasets/{}", dataset_id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["dataset"]["name"].as_str().unwrap(), "test");
+        assert_eq!(body["data"]["headers"].as_array().unwrap().len(), 2);
+        assert_eq!(body["data"]["rows"].as_array().unwrap().len(), 1);
+    }
+
+    #[actix_web::test]
+    async fn test_get_one_not_found() {
+        let pool = test_utils::setup_test_db().await;
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route("/datasets/{id}", web::get().to(get_one));
+        })
+        .await;
+
+        let req = test::TestRequest::get().uri("/datasets/999").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert!(body["error"].as_str().unwrap().contains("not found"));
+    }
+
+    #[actix_web::test]
+    async fn test_fidelity() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["age".to_string(), "plan".to_string()];
+        let sample_data: Vec<Vec<String>> = (0..40)
+            .map(|i| {
+                let plan = if i % 4 == 0 { "pro" } else { "free" };
+                vec![(20 + i).to_string(), plan.to_string()]
+            })
+            .collect();
+        let dataset_id =
+            operations::save_dataset(&pool, "test", &headers, "custom", None, Some(&sample_data))
+                .await
+                .unwrap();
+        let empty_id = operations::save_dataset(&pool, "empty", &headers, "custom", None, None)
+            .await
+            .unwrap();
+
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route("/datasets/{id}/fidelity", web::post().to(fidelity));
+        })
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/fidelity", dataset_id))
+            .set_json(serde_json::json!({"row_count": 2000, "seed": 4}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["seed"], 4);
+        let report = &body["report"];
+        assert_eq!(report["sample_rows"], 40);
+        assert_eq!(report["generated_rows"], 2000);
+        assert_eq!(report["columns"][0]["measure"], "ks");
+        assert_eq!(report["columns"][1]["measure"], "tvd");
+        assert!(report["columns"][1]["statistic"].as_f64().unwrap() < 0.05);
+        assert!(report["score"].as_f64().unwrap() > 0.85);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/fidelity", dataset_id))
+            .set_json(serde_json::json!({"use_sample_data": false, "seed": 4}))
+            .to_request();
+        let body: serde_json::Value =
+            test::read_body_json(test::call_service(&app, req).await).await;
+        assert!(body["report"]["score"].as_f64().unwrap() < 0.5);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/fidelity", empty_id))
+            .set_json(serde_json::json!({}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/fidelity", dataset_id))
+            .set_json(serde_json::json!({"row_count": 100_001}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_profile() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["price".to_string(), "note".to_string()];
+        let sample_data = vec![
+            vec!["9.50".to_string(), "ok".to_string()],
+            vec!["10.50".to_string(), "".to_string()],
+        ];
+        let dataset_id =
+            operations::save_dataset(&pool, "test", &headers, "custom", None, Some(&sample_data))
+                .await
+                .unwrap();
+
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route("/datasets/{id}/profile", web::get().to(profile));
+        })
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/datasets/{}/profile", dataset_id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["dataset"]["name"], "test");
+        let price = &body["profile"]["columns"][0];
+        assert_eq!(price["kind"], "decimal");
+        assert_eq!(price["detected_type"], "decimal");
+        assert_eq!(price["numeric"]["mean"], 10.0);
+        assert!(price.get("lengths").is_none());
+        let note = &body["profile"]["columns"][1];
+        assert_eq!(note["blank"], 1);
+        assert_eq!(
+            note["top_values"],
+            serde_json::json!([{"value": "ok", "count": 1}])
+        );
+
+        let req = test::TestRequest::get()
+            .uri("/datasets/999/profile")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_delete_success() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["col1".to_string()];
+
+        let dataset_id =
+            operations::save_dataset(&pool, "to_delete", &headers, "custom", None, None)
+                .await
+                .unwrap();
+
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route("/datasets/{id}", web::delete().to(delete));
+        })
+        .await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/datasets/{}", dataset_id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert!(body["message"].as_str().unwrap().contains("successfully"));
+    }
+
+    #[actix_web::test]
+    async fn test_delete_not_found() {
+        let pool = test_utils::setup_test_db().await;
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route("/datasets/{id}", web::delete().to(delete));
+        })
+        .await;
+
+        let req = test::TestRequest::delete()
+            .uri("/datasets/999")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_generate_from_dataset_success() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["id".to_string(), "name".to_string(), "email".to_string()];
+
+        let dataset_id = operations::save_dataset(&pool, "test", &headers, "custom", None, None)
+            .await
+            .unwrap();
+
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route(
+                "/datasets/{id}/generate",
+                web::post().to(generate_from_dataset),
+            );
+        })
+        .await;
+
+        let payload = GenerateFromDatasetRequest {
+            row_count: Some(10),
+            ..Default::default()
+        };
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/generate", dataset_id))
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["data"]["rows"].as_array().unwrap().len(), 10);
+        assert!(body["message"].as_str().unwrap().contains("10 rows"));
+    }
+
+    #[actix_web::test]
+    async fn test_generate_from_dataset_default_row_count() {
         let pool = test_utils::setup_test_db().await;
         let headers = vec!["id".to_string()];
 
-        let dataset_id = operations::save_dataset(&pool, "test", &headers, "custom", None, None)
-            .await
-            .unwrap();
+        let dataset_id = operations::save_dataset(&pool, "test", &headers, "custom", None, None)
+            .await
+            .unwrap();
+
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route(
+                "/datasets/{id}/generate",
+                web::post().to(generate_from_dataset),
+            );
+        })
+        .await;
+
+        let payload = GenerateFromDatasetRequest {
+            row_count: None,
+            ..Default::default()
+        };
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/generate", dataset_id))
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["data"]["rows"].as_array().unwrap().len(), 20);
+    }
+
+    #[actix_web::test]
+    async fn test_generate_from_dataset_invalid_row_count_zero() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["id".to_string()];
+
+        let dataset_id = operations::save_dataset(&pool, "test", &headers, "custom", None, None)
+            .await
+            .unwrap();
+
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route(
+                "/datasets/{id}/generate",
+                web::post().to(generate_from_dataset),
+            );
+        })
+        .await;
+
+        let payload = GenerateFromDatasetRequest {
+            row_count: Some(0),
+            ..Default::default()
+        };
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/generate", dataset_id))
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert!(
+            body["error"]
+                .as_str()
+                .unwrap()
+                .contains("between 1 and 1000")
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_generate_from_dataset_invalid_row_count_too_large() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["id".to_string()];
+
+        let dataset_id = operations::save_dataset(&pool, "test", &headers, "custom", None, None)
+            .await
+            .unwrap();
+
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route(
+                "/datasets/{id}/generate",
+                web::post().to(generate_from_dataset),
+            );
+        })
+        .await;
+
+        let payload = GenerateFromDatasetRequest {
+            row_count: Some(1001),
+            ..Default::default()
+        };
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/generate", dataset_id))
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_generate_from_dataset_not_found() {
+        let pool = test_utils::setup_test_db().await;
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route(
+                "/datasets/{id}/generate",
+                web::post().to(generate_from_dataset),
+            );
+        })
+        .await;
+
+        let payload = GenerateFromDatasetRequest {
+            row_count: Some(10),
+            ..Default::default()
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/datasets/999/generate")
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_generate_from_dataset_honors_column_types() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["contact".to_string(), "label".to_string()];
+        let mut column_types = HashMap::new();
+        column_types.insert("contact".to_string(), "email".to_string());
+
+        let dataset_id =
+            operations::save_dataset(&pool, "test", &headers, "custom", Some(&column_types), None)
+                .await
+                .unwrap();
+
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route(
+                "/datasets/{id}/generate",
+                web::post().to(generate_from_dataset),
+            );
+        })
+        .await;
+
+        let payload = GenerateFromDatasetRequest {
+            row_count: Some(5),
+            ..Default::default()
+        };
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/generate", dataset_id))
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        for row in body["data"]["rows"].as_array().unwrap() {
+            assert!(row[0].as_str().unwrap().contains('@'));
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_generate_from_dataset_unknown_column_type() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["contact".to_string()];
+        let mut column_types = HashMap::new();
+        column_types.insert("contact".to_string(), "telepathy".to_string());
+
+        let dataset_id =
+            operations::save_dataset(&pool, "test", &headers, "custom", Some(&column_types), None)
+                .await
+                .unwrap();
 
         let app = init_test_service_with_routes(pool, |cfg| {
             cfg.route(
@@ -549,7 +1420,10 @@ mod tests {
         })
         .await;
 
-        let payload = GenerateFromDatasetRequest { row_count: Some(0) };
+        let payload = GenerateFromDatasetRequest {
+            row_count: Some(5),
+            ..Default::default()
+        };
 
         let req = test::TestRequest::post()
             .uri(&format!("/datasets/{}/generate", dataset_id))
@@ -559,18 +1433,109 @@ mod tests {
 
         assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
         let body: serde_json::Value = test::read_body_json(resp).await;
-        assert!(
-            body["error"]
-                .as_str()
-                .unwrap()
-                .contains("between 1 and 1000")
-        );
+        let error = body["error"].as_str().unwrap();
+        assert!(error.contains("telepathy"));
+        assert!(error.contains("contact"));
     }
 
     #[actix_web::test]
-    async fn test_generate_from_dataset_invalid_row_count_too_large() {
+    async fn test_generate_from_dataset_uses_sample_data() {
         let pool = test_utils::setup_test_db().await;
-        let headers = vec!["id".to_string()];
+        let headers = vec!["status".to_string(), "quantity".to_string()];
+        let sample_data = vec![
+            vec!["active".to_string(), "2".to_string()],
+            vec!["active".to_string(), "4".to_string()],
+            vec!["suspended".to_string(), "6".to_string()],
+            vec!["active".to_string(), "8".to_string()],
+        ];
+
+        let dataset_id = operations::save_dataset(
+            &pool,
+            "test",
+            &headers,
+            "uploaded",
+            None,
+            Some(&sample_data),
+        )
+        .await
+        .unwrap();
+
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route(
+                "/datasets/{id}/generate",
+                web::post().to(generate_from_dataset),
+            );
+        })
+        .await;
+
+        let payload = GenerateFromDatasetRequest {
+            row_count: Some(50),
+            ..Default::default()
+        };
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/generate", dataset_id))
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        for row in body["data"]["rows"].as_array().unwrap() {
+            let status = row[0].as_str().unwrap();
+            assert!(status == "active" || status == "suspended");
+            let quantity = row[1].as_i64().unwrap();
+            assert!((2..=8).contains(&quantity));
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_generate_from_dataset_without_sample_data() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["status".to_string()];
+        let sample_data = vec![vec!["active".to_string()], vec!["active".to_string()]];
+
+        let dataset_id = operations::save_dataset(
+            &pool,
+            "test",
+            &headers,
+            "uploaded",
+            None,
+            Some(&sample_data),
+        )
+        .await
+        .unwrap();
+
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route(
+                "/datasets/{id}/generate",
+                web::post().to(generate_from_dataset),
+            );
+        })
+        .await;
+
+        let payload = GenerateFromDatasetRequest {
+            row_count: Some(20),
+            use_sample_data: Some(false),
+            ..Default::default()
+        };
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/generate", dataset_id))
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let rows = body["data"]["rows"].as_array().unwrap();
+        assert!(rows.iter().any(|row| row[0].as_str().unwrap() != "active"));
+    }
+
+    #[actix_web::test]
+    async fn test_generate_from_dataset_same_seed_same_rows() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["id".to_string(), "name".to_string(), "email".to_string()];
 
         let dataset_id = operations::save_dataset(&pool, "test", &headers, "custom", None, None)
             .await
@@ -584,8 +1549,49 @@ mod tests {
         })
         .await;
 
+        let mut bodies = Vec::new();
+        for _ in 0..2 {
+            let payload = GenerateFromDatasetRequest {
+                row_count: Some(10),
+                seed: Some(7),
+                ..Default::default()
+            };
+            let req = test::TestRequest::post()
+                .uri(&format!("/datasets/{}/generate", dataset_id))
+                .set_json(&payload)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert!(resp.status().is_success());
+            let body: serde_json::Value = test::read_body_json(resp).await;
+            bodies.push(body);
+        }
+
+        assert_eq!(bodies[0]["seed"].as_u64().unwrap(), 7);
+        assert_eq!(bodies[0]["data"], bodies[1]["data"]);
+    }
+
+    #[actix_web::test]
+    async fn test_generate_from_dataset_save_stores_seed() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["id".to_string(), "name".to_string()];
+
+        let dataset_id = operations::save_dataset(&pool, "people", &headers, "custom", None, None)
+            .await
+            .unwrap();
+
+        let app = init_test_service_with_routes(pool.clone(), |cfg| {
+            cfg.route(
+                "/datasets/{id}/generate",
+                web::post().to(generate_from_dataset),
+            );
+        })
+        .await;
+
         let payload = GenerateFromDatasetRequest {
-            row_count: Some(1001),
+            row_count: Some(5),
+            seed: Some(1234),
+            save: Some(true),
+            ..Default::default()
         };
 
         let req = test::TestRequest::post()
@@ -594,12 +1600,30 @@ mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let saved_id = body["dataset_id"].as_i64().unwrap();
+
+        let (saved, saved_data) = operations::get_datasets(&pool, saved_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(saved.name, "people (Generated)");
+        assert_eq!(saved.data_type, "generated");
+        assert_eq!(saved.seed, Some(1234));
+        assert_eq!(saved_data.rows.len(), 5);
     }
 
     #[actix_web::test]
-    async fn test_generate_from_dataset_not_found() {
+    async fn test_generate_from_dataset_as_markdown() {
         let pool = test_utils::setup_test_db().await;
+        let headers = vec!["id".to_string(), "email".to_string()];
+
+        let dataset_id =
+            operations::save_dataset(&pool, "Customer Schema", &headers, "custom", None, None)
+                .await
+                .unwrap();
+
         let app = init_test_service_with_routes(pool, |cfg| {
             cfg.route(
                 "/datasets/{id}/generate",
@@ -609,15 +1633,130 @@ mod tests {
         .await;
 
         let payload = GenerateFromDatasetRequest {
-            row_count: Some(10),
+            row_count: Some(3),
+            format: Some(OutputFormat::Markdown),
+            ..Default::default()
         };
 
         let req = test::TestRequest::post()
-            .uri("/datasets/999/generate")
+            .uri(&format!("/datasets/{}/generate", dataset_id))
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        assert_eq!(
+            resp.headers().get("content-disposition").unwrap(),
+            "attachment; filename=\"customer_schema.md\""
+        );
+
+        let body = test::read_body(resp).await;
+        let lines: Vec<&str> = std::str::from_utf8(&body).unwrap().lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], "| id | email |");
+        assert_eq!(lines[1], "| --- | --- |");
+        assert!(lines[2].starts_with("| 1 | "));
+    }
+
+    #[actix_web::test]
+    async fn test_generate_from_dataset_stream_csv() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["id".to_string(), "email".to_string()];
+
+        let dataset_id =
+            operations::save_dataset(&pool, "Customer Schema", &headers, "custom", None, None)
+                .await
+                .unwrap();
+
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route(
+                "/datasets/{id}/generate/stream",
+                web::post().to(generate_from_dataset_stream),
+            );
+        })
+        .await;
+
+        let payload = StreamFromDatasetRequest {
+            row_count: Some(2_500),
+            seed: Some(3),
+            ..Default::default()
+        };
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/generate/stream", dataset_id))
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        assert_eq!(resp.headers().get("x-seed").unwrap(), "3");
+        assert_eq!(
+            resp.headers().get("content-disposition").unwrap(),
+            "attachment; filename=\"customer_schema.csv\""
+        );
+
+        let body = test::read_body(resp).await;
+        let text = std::str::from_utf8(&body).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2_501);
+        assert_eq!(lines[0], "id,email");
+        assert!(lines[1].contains('@'));
+    }
+
+    #[actix_web::test]
+    async fn test_generate_from_dataset_stream_above_json_limit() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["id".to_string()];
+
+        let dataset_id = operations::save_dataset(&pool, "ids", &headers, "custom", None, None)
+            .await
+            .unwrap();
+
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route(
+                "/datasets/{id}/generate/stream",
+                web::post().to(generate_from_dataset_stream),
+            );
+        })
+        .await;
+
+        let payload = StreamFromDatasetRequest {
+            row_count: Some(20_000),
+            format: Some(OutputFormat::Ndjson),
+            ..Default::default()
+        };
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/datasets/{}/generate/stream", dataset_id))
             .set_json(&payload)
             .to_request();
         let resp = test::call_service(&app, req).await;
 
+        assert!(resp.status().is_success());
+        let body = test::read_body(resp).await;
+        let text = std::str::from_utf8(&body).unwrap();
+        assert_eq!(text.lines().count(), 20_000);
+        let last: serde_json::Value = serde_json::from_str(text.lines().last().unwrap()).unwrap();
+        assert_eq!(last["id"], 20_000);
+    }
+
+    #[actix_web::test]
+    async fn test_generate_from_dataset_stream_not_found() {
+        let pool = test_utils::setup_test_db().await;
+        let app = init_test_service_with_routes(pool, |cfg| {
+            cfg.route(
+                "/datasets/{id}/generate/stream",
+                web::post().to(generate_from_dataset_stream),
+            );
+        })
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/datasets/999/generate/stream")
+            .set_json(StreamFromDatasetRequest::default())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
         assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
     }
 
@@ -642,6 +1781,8 @@ mod tests {
             data_type: "uploaded".to_string(),
             column_types: None,
             sample_data: None,
+            columns: None,
+            locale: None,
         };
 
         let req = test::TestRequest::put()
@@ -655,6 +1796,47 @@ mod tests {
         assert!(body["message"].as_str().unwrap().contains("successfully"));
     }
 
+    #[actix_web::test]
+    async fn test_update_with_columns() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["note".to_string()];
+        let dataset_id = operations::save_dataset(&pool, "notes", &headers, "custom", None, None)
+            .await
+            .unwrap();
+
+        let app = init_test_service_with_routes(pool.clone(), |cfg| {
+            cfg.route("/datasets/{id}", web::put().to(update));
+        })
+        .await;
+
+        let mut note = ColumnSpec::new("note", "text");
+        note.min_length = Some(20);
+        note.max_length = Some(40);
+        let payload = SaveDatasetRequest {
+            name: "notes".to_string(),
+            headers,
+            data_type: "custom".to_string(),
+            column_types: None,
+            sample_data: None,
+            columns: Some(vec![note.clone()]),
+            locale: Some("fr_FR".to_string()),
+        };
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/datasets/{}", dataset_id))
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        let source = operations::get_generation_source(&pool, dataset_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(source.columns, Some(vec![note]));
+        assert_eq!(source.dataset.locale.as_deref(), Some("fr_FR"));
+    }
+
     #[actix_web::test]
     async fn test_update_not_found() {
         let pool = test_utils::setup_test_db().await;
@@ -669,6 +1851,8 @@ mod tests {
             data_type: "custom".to_string(),
             column_types: None,
             sample_data: None,
+            columns: None,
+            locale: None,
         };
 
         let req = test::TestRequest::put()
@@ -0,0 +1,350 @@
+use crate::db::operations;
+use crate::dictionary::{self, DictionaryEntry, DictionarySpec};
+use crate::multipart::parse_form;
+use actix_multipart::Multipart;
+use actix_web::{HttpResponse, Responder, web};
+use log::{error, info};
+use sqlx::SqlitePool;
+
+pub async fn list(pool: web::Data<SqlitePool>) -> impl Responder {
+    info!("Listing all dictionaries");
+
+    match operations::list_dictionaries(pool.get_ref()).await {
+        Ok(dictionaries) => HttpResponse::Ok().json(dictionaries),
+        Err(e) => {
+            error!("Failed to list dictionaries: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to list dictionaries: {}", e)
+            }))
+        }
+    }
+}
+
+fn invalid_dictionary_response(message: &str) -> HttpResponse {
+    info!("Dictionary rejected: {}", message);
+    HttpResponse::BadRequest().json(serde_json::json!({ "error": message }))
+}
+
+/// Saves a new dictionary, or with an `id` replaces that one. Names are
+/// unique, since columns refer to dictionaries by name.
+async fn save_entries(
+    pool: &SqlitePool,
+    id: Option<i64>,
+    name: &str,
+    entries: &[DictionaryEntry],
+) -> HttpResponse {
+    if let Err(message) =
+        dictionary::check_name(name).and_then(|_| dictionary::check_entries(entries))
+    {
+        return invalid_dictionary_response(&message);
+    }
+
+    let saved = async {
+        if let Some(existing) = operations::find_dictionary(pool, name).await?
+            && Some(existing) != id
+        {
+            return Ok(Err(format!("A dictionary named '{}' already exists", name)));
+        }
+        match id {
+            Some(id) => operations::update_dictionary(pool, id, name, entries)
+                .await
+                .map(|found| Ok(found.then_some(id))),
+            None => operations::save_dictionary(pool, name, entries)
+                .await
+                .map(|id| Ok(Some(id))),
+        }
+    }
+    .await;
+
+    match saved {
+        Ok(Ok(Some(id))) => {
+            info!("Dictionary '{}' saved with id: {}", name, id);
+            HttpResponse::Ok().json(serde_json::json!({
+                "id": id,
+                "entry_count": entries.len(),
+                "message": "Dictionary saved successfully"
+            }))
+        }
+        Ok(Ok(None)) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Dictionary with id {} not found", id.unwrap_or_default())
+        })),
+        Ok(Err(message)) => invalid_dictionary_response(&message),
+        Err(e) => {
+            error!("Failed to save dictionary: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to save dictionary: {}", e)
+            }))
+        }
+    }
+}
+
+pub async fn create(pool: web::Data<SqlitePool>, req: web::Json<DictionarySpec>) -> impl Responder {
+    info!("Saving dictionary: {}", req.name);
+
+    match req.entries() {
+        Ok(entries) => save_entries(pool.get_ref(), None, &req.name, &entries).await,
+        Err(message) => invalid_dictionary_response(&message),
+    }
+}
+
+/// Creates a dictionary from an uploaded word list or CSV column. Form
+/// fields: `file`, `name`, and for a CSV `column` plus an optional
+/// `weight_column`.
+pub async fn upload(pool: web::Data<SqlitePool>, payload: Multipart) -> impl Responder {
+    let form = match parse_form(payload).await {
+        Ok(form) => form,
+        Err(e) => return invalid_dictionary_response(&e.to_string()),
+    };
+    let text = |field: &str| {
+        form.get(field)
+            .map(|bytes| String::from_utf8_lossy(bytes).trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    let Some(file) = form.get("file") else {
+        return invalid_dictionary_response("No file data received");
+    };
+    let name = text("name").unwrap_or_default();
+    info!("Uploading dictionary: {} ({} bytes)", name, file.len());
+
+    let entries = dictionary::entries_from_upload(
+        file,
+        text("column").as_deref(),
+        text("weight_column").as_deref(),
+    );
+    match entries {
+        Ok(entries) => save_entries(pool.get_ref(), None, &name, &entries).await,
+        Err(message) => invalid_dictionary_response(&message),
+    }
+}
+
+pub async fn get_one(pool: web::Data<SqlitePool>, path: web::Path<i64>) -> impl Responder {
+    let id = path.into_inner();
+    info!("Getting dictionary with id: {}", id);
+
+    match operations::get_dictionary(pool.get_ref(), id).await {
+        Ok(Some((dictionary, entries))) => HttpResponse::Ok().json(serde_json::json!({
+            "id": dictionary.id,
+            "name": dictionary.name,
+            "created_at": dictionary.created_at,
+            "entries": entries
+        })),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Dictionary with id {} not found", id)
+        })),
+        Err(e) => {
+            error!("Failed to get dictionary: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get dictionary: {}", e)
+            }))
+        }
+    }
+}
+
+/// Replaces a dictionary's name and values. Columns naming the old name stop
+/// finding it.
+pub async fn update(
+    pool: web::Data<SqlitePool>,
+    path: web::Path<i64>,
+    req: web::Json<DictionarySpec>,
+) -> impl Responder {
+    let id = path.into_inner();
+    info!("Updating dictionary with id: {}", id);
+
+    match req.entries() {
+        Ok(entries) => save_entries(pool.get_ref(), Some(id), &req.name, &entries).await,
+        Err(message) => invalid_dictionary_response(&message),
+    }
+}
+
+pub async fn delete(pool: web::Data<SqlitePool>, path: web::Path<i64>) -> impl Responder {
+    let id = path.into_inner();
+    info!("Deleting dictionary with id: {}", id);
+
+    match operations::delete_dictionary(pool.get_ref(), id).await {
+        Ok(true) => {
+            info!("Dictionary {} deleted successfully", id);
+            HttpResponse::Ok().json(serde_json::json!({
+                "message": "Dictionary deleted successfully"
+            }))
+        }
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Dictionary with id {} not found", id)
+        })),
+        Err(e) => {
+            error!("Failed to delete dictionary: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to delete dictionary: {}", e)
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils;
+    use actix_web::{App, test};
+
+    async fn app(
+        pool: SqlitePool,
+    ) -> impl actix_web::dev::Service<
+        actix_http::Request,
+        Response = actix_web::dev::ServiceResponse,
+        Error = actix_web::Error,
+    > {
+        test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool))
+                .route("/dictionaries", web::get().to(list))
+                .route("/dictionaries", web::post().to(create))
+                .route("/dictionaries/upload", web::post().to(upload))
+                .route("/dictionaries/{id}", web::get().to(get_one))
+                .route("/dictionaries/{id}", web::put().to(update))
+                .route("/dictionaries/{id}", web::delete().to(delete)),
+        )
+        .await
+    }
+
+    fn multipart_request(fields: &[(&str, &str)]) -> test::TestRequest {
+        let boundary = "----DictionaryBoundary";
+        let mut body = String::new();
+        for (name, value) in fields {
+            let filename = if *name == "file" {
+                "; filename=\"values.csv\""
+            } else {
+                ""
+            };
+            body.push_str(&format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"{}\r\n\r\n{}\r\n",
+                boundary, name, filename, value
+            ));
+        }
+        body.push_str(&format!("--{}--\r\n", boundary));
+
+        test::TestRequest::post()
+            .uri("/dictionaries/upload")
+            .insert_header((
+                "content-type",
+                format!("multipart/form-data; boundary={}", boundary),
+            ))
+            .set_payload(body)
+    }
+
+    #[actix_web::test]
+    async fn test_create_get_update_delete() {
+        let pool = test_utils::setup_test_db().await;
+        let app = app(pool).await;
+
+        let req = test::TestRequest::post()
+            .uri("/dictionaries")
+            .set_json(serde_json::json!({"name": "departments", "values": ["ENG", "OPS"]}))
+            .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        let id = body["id"].as_i64().unwrap();
+        assert_eq!(body["entry_count"], 2);
+
+        let req = test::TestRequest::post()
+            .uri("/dictionaries")
+            .set_json(serde_json::json!({"name": "departments", "values": ["FIN"]}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body["error"],
+            "A dictionary named 'departments' already exists"
+        );
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/dictionaries/{}", id))
+            .set_json(serde_json::json!({
+                "name": "departments",
+                "values": ["ENG", "FIN"],
+                "weights": [4, 1]
+            }))
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/dictionaries/{}", id))
+            .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(
+            body["entries"],
+            serde_json::json!([{"value": "ENG", "weight": 4.0}, {"value": "FIN", "weight": 1.0}])
+        );
+
+        let req = test::TestRequest::get().uri("/dictionaries").to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body[0]["entry_count"], 2);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/dictionaries/{}", id))
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+        let req = test::TestRequest::get()
+            .uri(&format!("/dictionaries/{}", id))
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            actix_web::http::StatusCode::NOT_FOUND
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_create_rejects_invalid_weights() {
+        let pool = test_utils::setup_test_db().await;
+        let app = app(pool).await;
+
+        let req = test::TestRequest::post()
+            .uri("/dictionaries")
+            .set_json(serde_json::json!({"name": "codes", "values": ["A", "B"], "weights": [1]}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body["error"],
+            "'weights' has 1 numbers but there are 2 values"
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_upload_csv_column() {
+        let pool = test_utils::setup_test_db().await;
+        let app = app(pool.clone()).await;
+
+        let req = multipart_request(&[
+            ("name", "categories"),
+            ("column", "category"),
+            ("weight_column", "sales"),
+            ("file", "category,sales\nBooks,10\nGarden,2\n"),
+        ])
+        .to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["entry_count"], 2);
+
+        let id = body["id"].as_i64().unwrap();
+        let (_, entries) = operations::get_dictionary(&pool, id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(entries[1].value, "Garden");
+        assert_eq!(entries[1].weight, Some(2.0));
+    }
+
+    #[actix_web::test]
+    async fn test_upload_word_list_needs_a_name() {
+        let pool = test_utils::setup_test_db().await;
+        let app = app(pool).await;
+
+        let req = multipart_request(&[("file", "ENG\nOPS\n")]).to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["error"], "Dictionary name must not be empty");
+    }
+}
@@ -2,15 +2,23 @@ use actix_multipart::Multipart;
 use actix_web::HttpResponse;
 use log::{error, info, warn};
 use serde::Serialize;
+use std::collections::HashMap;
 use std::time::Instant;
 
 use super::ErrorResponse;
 use crate::csv_parser::parse_csv_from_bytes;
+use crate::generators::{InferredColumn, infer_columns};
 use crate::multipart::{MultipartError, parse_multipart};
+use crate::schema;
 
 #[derive(Serialize)]
 pub struct HeadersResponse {
     pub headers: Vec<String>,
+    /// Each column's type as inferred from its values.
+    pub columns: Vec<InferredColumn>,
+    /// The inferred type of each header, to save as the dataset's
+    /// `column_types`.
+    pub column_types: HashMap<String, String>,
     pub message: String,
 }
 
@@ -38,12 +46,18 @@ pub async fn extract_headers(payload: Multipart) -> Result<HttpResponse, Multipa
                 elapsed.as_secs_f64() * 1000.0
             );
 
+            let headers: Vec<String> = csv_data
+                .headers
+                .iter()
+                .map(|s| s.trim().to_string())
+                .collect();
+            let columns = infer_columns(&headers, &csv_data.rows);
+            let specs: Vec<_> = columns.iter().map(|c| c.column.clone()).collect();
+
             Ok(HttpResponse::Ok().json(HeadersResponse {
-                headers: csv_data
-                    .headers
-                    .iter()
-                    .map(|s| s.trim().to_string())
-                    .collect(),
+                headers,
+                column_types: schema::column_type_map(&specs),
+                columns,
                 message: format!(
                     "Extracted {} column{}",
                     header_count,
@@ -153,6 +167,8 @@ mod tests {
     fn test_headers_response_message_singular() {
         let response = HeadersResponse {
             headers: vec!["id".to_string()],
+            columns: Vec::new(),
+            column_types: HashMap::new(),
             message: format!("Extracted {} column{}", 1, if 1 == 1 { "" } else { "s" }),
         };
 
@@ -163,6 +179,8 @@ mod tests {
     fn test_headers_response_message_plural() {
         let response = HeadersResponse {
             headers: vec!["id".to_string(), "name".to_string(), "age".to_string()],
+            columns: Vec::new(),
+            column_types: HashMap::new(),
             message: format!("Extracted {} column{}", 3, if 3 == 1 { "" } else { "s" }),
         };
 
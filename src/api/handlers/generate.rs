@@ -1,32 +1,100 @@
-use crate::csv_parser::CsvData;
-use crate::generators::{DataGenerator, SmartGenerator};
-use actix_web::{HttpResponse, Responder, web};
-use log::{debug, info};
+use crate::db::operations;
+use crate::generators::{DataGenerator, SmartGenerator, resolve_seed};
+use crate::output::{self, NullValue, OutputFormat, attachment_filename};
+use crate::value::Table;
+use actix_web::{HttpResponse, HttpResponseBuilder, Responder, web};
+use log::{debug, error, info};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
 
 #[derive(Deserialize, Serialize)]
 pub struct GenerateRequest {
     pub row_count: Option<usize>,
     pub save: Option<bool>,
     pub headers: Option<Vec<String>>,
+    pub seed: Option<u64>,
+    /// Returns the rows as a file download instead of JSON.
+    pub format: Option<OutputFormat>,
+    /// How null cells are written in the download.
+    pub null_as: Option<NullValue>,
 }
 
-fn generate_with_generator<G: DataGenerator>(generator: G, row_count: usize) -> CsvData {
-    let mut rng = rand::rng();
+pub fn generate_with_generator<G: DataGenerator>(
+    generator: G,
+    row_count: usize,
+    seed: u64,
+) -> Table {
+    let mut rng = StdRng::seed_from_u64(seed);
     let headers = generator.headers();
 
-    debug!("Generating {} rows using functional map", row_count);
-    let rows: Vec<Vec<String>> = (1..=row_count)
+    debug!(
+        "Generating {} rows using functional map with seed {}",
+        row_count, seed
+    );
+    let rows = (1..=row_count)
         .map(|i| generator.generate_row(i, &mut rng))
         .collect();
 
-    CsvData { headers, rows }
+    Table { headers, rows }
 }
 
-pub async fn generate_placeholder(req: web::Json<GenerateRequest>) -> impl Responder {
+/// Starts a download response with the content type, an attachment filename
+/// derived from `name`, and the seed in `X-Seed` since the body is just data.
+pub fn download_response(format: OutputFormat, name: &str, seed: u64) -> HttpResponseBuilder {
+    let mut builder = HttpResponse::Ok();
+    builder
+        .content_type(format.content_type())
+        .insert_header((
+            "Content-Disposition",
+            format!(
+                "attachment; filename=\"{}\"",
+                attachment_filename(name, format)
+            ),
+        ))
+        .insert_header(("X-Seed", seed.to_string()));
+    builder
+}
+
+/// Renders generated rows as a file. A saved dataset's id goes in
+/// `X-Dataset-Id`.
+pub fn file_response(
+    data: &Table,
+    format: OutputFormat,
+    null_as: NullValue,
+    name: &str,
+    seed: u64,
+    dataset_id: Option<i64>,
+) -> HttpResponse {
+    let body = match output::render(data, format, null_as, name) {
+        Ok(body) => body,
+        Err(e) => {
+            error!("Failed to render generated data as {:?}: {}", format, e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to render generated data: {}", e)
+            }));
+        }
+    };
+
+    let mut builder = download_response(format, name, seed);
+    if let Some(id) = dataset_id {
+        builder.insert_header(("X-Dataset-Id", id.to_string()));
+    }
+    builder.body(body)
+}
+
+pub async fn generate_placeholder(
+    pool: web::Data<SqlitePool>,
+    req: web::Json<GenerateRequest>,
+) -> impl Responder {
     let row_count = req.row_count.unwrap_or(20);
+    let seed = resolve_seed(req.seed);
 
-    info!("Generating {} placeholder rows", row_count);
+    info!(
+        "Generating {} placeholder rows with seed {}",
+        row_count, seed
+    );
 
     if row_count == 0 || row_count > 1000 {
         return HttpResponse::BadRequest().json(serde_json::json!({
@@ -45,27 +113,73 @@ pub async fn generate_placeholder(req: web::Json<GenerateRequest>) -> impl Respo
     });
 
     let generator = SmartGenerator::new(headers.clone());
-    let csv_data = generate_with_generator(generator, row_count);
+    let csv_data = generate_with_generator(generator, row_count, seed);
 
     info!("Generated {} rows successfully", row_count);
 
-    HttpResponse::Ok().json(serde_json::json!({
+    let mut dataset_id = None;
+    if req.save.unwrap_or(false) {
+        let name = format!("Generated data (seed {})", seed);
+        match operations::save_generated_dataset(pool.get_ref(), &name, &csv_data, None, seed).await
+        {
+            Ok(id) => {
+                info!("Generated rows saved as dataset {}", id);
+                dataset_id = Some(id);
+            }
+            Err(e) => {
+                error!("Failed to save generated data: {}", e);
+                return HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": format!("Failed to save generated data: {}", e)
+                }));
+            }
+        }
+    }
+
+    if let Some(format) = req.format {
+        return file_response(
+            &csv_data,
+            format,
+            req.null_as.unwrap_or_default(),
+            "generated_data",
+            seed,
+            dataset_id,
+        );
+    }
+
+    let mut response = serde_json::json!({
         "data": csv_data,
+        "seed": seed,
         "message": format!("Generated {} rows successfully", row_count)
-    }))
+    });
+    if let Some(id) = dataset_id {
+        response["dataset_id"] = serde_json::json!(id);
+    }
+
+    HttpResponse::Ok().json(response)
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_borrows_for_generic_args)]
 mod tests {
     use super::*;
-    use actix_web::{App, test as actix_test, web};
+    use crate::db::test_utils;
+    use actix_web::dev::{Service, ServiceResponse};
+    use actix_web::{App, Error, test as actix_test, web};
+
+    async fn init_generate_service()
+    -> impl Service<actix_http::Request, Response = ServiceResponse, Error = Error> {
+        let pool = test_utils::setup_test_db().await;
+        actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool))
+                .route("/generate", web::post().to(generate_placeholder)),
+        )
+        .await
+    }
 
     #[tokio::test]
     async fn test_generate_default() {
-        let app = actix_test::init_service(
-            App::new().route("/generate", web::post().to(generate_placeholder)),
-        )
-        .await;
+        let app = init_generate_service().await;
 
         let req = actix_test::TestRequest::post()
             .uri("/generate")
@@ -93,10 +207,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_generate_custom_row_count() {
-        let app = actix_test::init_service(
-            App::new().route("/generate", web::post().to(generate_placeholder)),
-        )
-        .await;
+        let app = init_generate_service().await;
 
         let req = actix_test::TestRequest::post()
             .uri("/generate")
@@ -119,10 +230,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_generate_custom_headers() {
-        let app = actix_test::init_service(
-            App::new().route("/generate", web::post().to(generate_placeholder)),
-        )
-        .await;
+        let app = init_generate_service().await;
 
         let custom_headers = vec!["user_id", "username", "phone"];
         let req = actix_test::TestRequest::post()
@@ -150,10 +258,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_generate_single_row() {
-        let app = actix_test::init_service(
-            App::new().route("/generate", web::post().to(generate_placeholder)),
-        )
-        .await;
+        let app = init_generate_service().await;
 
         let req = actix_test::TestRequest::post()
             .uri("/generate")
@@ -172,10 +277,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_generate_max_rows() {
-        let app = actix_test::init_service(
-            App::new().route("/generate", web::post().to(generate_placeholder)),
-        )
-        .await;
+        let app = init_generate_service().await;
 
         let req = actix_test::TestRequest::post()
             .uri("/generate")
@@ -194,10 +296,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_generate_zero_rows_invalid() {
-        let app = actix_test::init_service(
-            App::new().route("/generate", web::post().to(generate_placeholder)),
-        )
-        .await;
+        let app = init_generate_service().await;
 
         let req = actix_test::TestRequest::post()
             .uri("/generate")
@@ -218,10 +317,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_generate_too_many_rows_invalid() {
-        let app = actix_test::init_service(
-            App::new().route("/generate", web::post().to(generate_placeholder)),
-        )
-        .await;
+        let app = init_generate_service().await;
 
         let req = actix_test::TestRequest::post()
             .uri("/generate")
@@ -242,10 +338,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_generate_extremely_large_row_count() {
-        let app = actix_test::init_service(
-            App::new().route("/generate", web::post().to(generate_placeholder)),
-        )
-        .await;
+        let app = init_generate_service().await;
 
         let req = actix_test::TestRequest::post()
             .uri("/generate")
@@ -260,10 +353,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_generate_single_header() {
-        let app = actix_test::init_service(
-            App::new().route("/generate", web::post().to(generate_placeholder)),
-        )
-        .await;
+        let app = init_generate_service().await;
 
         let req = actix_test::TestRequest::post()
             .uri("/generate")
@@ -292,10 +382,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_generate_many_headers() {
-        let app = actix_test::init_service(
-            App::new().route("/generate", web::post().to(generate_placeholder)),
-        )
-        .await;
+        let app = init_generate_service().await;
 
         let headers: Vec<String> = (1..=20).map(|i| format!("col{}", i)).collect();
         let req = actix_test::TestRequest::post()
@@ -320,12 +407,161 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_generate_with_seed_is_reproducible() {
+        let app = init_generate_service().await;
+
+        let mut bodies = Vec::new();
+        for _ in 0..2 {
+            let req = actix_test::TestRequest::post()
+                .uri("/generate")
+                .set_json(serde_json::json!({
+                    "row_count": 10,
+                    "seed": 42
+                }))
+                .to_request();
+            let resp = actix_test::call_service(&app, req).await;
+            assert!(resp.status().is_success());
+            let body: serde_json::Value = actix_test::read_body_json(resp).await;
+            bodies.push(body);
+        }
+
+        assert_eq!(bodies[0]["seed"].as_u64().unwrap(), 42);
+        assert_eq!(bodies[0]["data"], bodies[1]["data"]);
+    }
+
+    #[tokio::test]
+    async fn test_generate_echoes_generated_seed() {
+        let app = init_generate_service().await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/generate")
+            .set_json(serde_json::json!({}))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        let body: serde_json::Value = actix_test::read_body_json(resp).await;
+
+        let seed = body["seed"].as_u64().unwrap();
+        assert!(seed < 1u64 << 53);
+    }
+
+    #[tokio::test]
+    async fn test_generate_save_stores_seed() {
+        let pool = test_utils::setup_test_db().await;
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/generate", web::post().to(generate_placeholder)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/generate")
+            .set_json(serde_json::json!({
+                "row_count": 3,
+                "seed": 99,
+                "save": true
+            }))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let body: serde_json::Value = actix_test::read_body_json(resp).await;
+        let dataset_id = body["dataset_id"].as_i64().unwrap();
+
+        let (dataset, csv_data) = operations::get_datasets(&pool, dataset_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(dataset.data_type, "generated");
+        assert_eq!(dataset.seed, Some(99));
+        assert_eq!(csv_data.rows.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_generate_as_tsv_download() {
+        let app = init_generate_service().await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/generate")
+            .set_json(serde_json::json!({
+                "row_count": 4,
+                "headers": ["id", "city"],
+                "seed": 8,
+                "format": "tsv"
+            }))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        assert!(
+            resp.headers()
+                .get("content-type")
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .starts_with("text/tab-separated-values")
+        );
+        assert_eq!(
+            resp.headers().get("content-disposition").unwrap(),
+            "attachment; filename=\"generated_data.tsv\""
+        );
+        assert_eq!(resp.headers().get("x-seed").unwrap(), "8");
+
+        let body = actix_test::read_body(resp).await;
+        let text = std::str::from_utf8(&body).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], "id\tcity");
+        assert!(lines[4].starts_with("4\t"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_as_sql_with_save() {
+        let app = init_generate_service().await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/generate")
+            .set_json(serde_json::json!({
+                "row_count": 2,
+                "headers": ["id"],
+                "format": "sql",
+                "save": true
+            }))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        assert!(resp.headers().get("x-dataset-id").is_some());
+        let body = actix_test::read_body(resp).await;
+        assert_eq!(
+            std::str::from_utf8(&body).unwrap(),
+            "INSERT INTO \"generated_data\" (\"id\") VALUES (1);\n\
+             INSERT INTO \"generated_data\" (\"id\") VALUES (2);\n"
+        );
+    }
+
+    #[test]
+    fn test_resolve_seed() {
+        assert_eq!(resolve_seed(Some(5)), 5);
+        assert!(resolve_seed(None) < 1u64 << 53);
+    }
+
+    #[test]
+    fn test_generate_with_generator_same_seed_same_rows() {
+        let headers = vec!["name".to_string(), "email".to_string()];
+        let first = generate_with_generator(SmartGenerator::new(headers.clone()), 5, 7);
+        let second = generate_with_generator(SmartGenerator::new(headers), 5, 7);
+        assert_eq!(first.rows, second.rows);
+    }
+
     #[test]
     fn test_generate_request_deserialization_full() {
         let json = r#"{
             "row_count": 25,
             "save": true,
-            "headers": ["id", "name", "email"]
+            "headers": ["id", "name", "email"],
+            "seed": 7
         }"#;
 
         let request: GenerateRequest = serde_json::from_str(json).unwrap();
@@ -339,6 +575,7 @@ mod tests {
                 "email".to_string()
             ])
         );
+        assert_eq!(request.seed, Some(7));
     }
 
     #[test]
@@ -366,7 +603,7 @@ mod tests {
         let headers = vec!["id".to_string(), "name".to_string()];
         let generator = SmartGenerator::new(headers.clone());
 
-        let csv_data = generate_with_generator(generator, 10);
+        let csv_data = generate_with_generator(generator, 10, 42);
 
         assert_eq!(csv_data.headers, headers);
         assert_eq!(csv_data.rows.len(), 10);
@@ -381,7 +618,7 @@ mod tests {
         let headers = vec!["test".to_string()];
         let generator = SmartGenerator::new(headers.clone());
 
-        let csv_data = generate_with_generator(generator, 1);
+        let csv_data = generate_with_generator(generator, 1, 42);
 
         assert_eq!(csv_data.headers.len(), 1);
         assert_eq!(csv_data.rows.len(), 1);
@@ -393,7 +630,7 @@ mod tests {
         let headers = vec!["col1".to_string(), "col2".to_string(), "col3".to_string()];
         let generator = SmartGenerator::new(headers.clone());
 
-        let csv_data = generate_with_generator(generator, 500);
+        let csv_data = generate_with_generator(generator, 500, 42);
 
         assert_eq!(csv_data.headers, headers);
         assert_eq!(csv_data.rows.len(), 500);
@@ -405,10 +642,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_generate_response_structure() {
-        let app = actix_test::init_service(
-            App::new().route("/generate", web::post().to(generate_placeholder)),
-        )
-        .await;
+        let app = init_generate_service().await;
 
         let req = actix_test::TestRequest::post()
             .uri("/generate")
@@ -433,10 +667,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_generate_empty_headers_array() {
-        let app = actix_test::init_service(
-            App::new().route("/generate", web::post().to(generate_placeholder)),
-        )
-        .await;
+        let app = init_generate_service().await;
 
         let req = actix_test::TestRequest::post()
             .uri("/generate")
@@ -461,10 +692,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_generate_special_characters_in_headers() {
-        let app = actix_test::init_service(
-            App::new().route("/generate", web::post().to(generate_placeholder)),
-        )
-        .await;
+        let app = init_generate_service().await;
 
         let special_headers = vec!["user-id", "first_name", "email@domain", "age (years)"];
         let req = actix_test::TestRequest::post()
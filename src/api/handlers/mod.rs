@@ -1,15 +1,26 @@
+pub mod anonymize;
 pub mod datasets;
+pub mod dictionaries;
 pub mod extract_headers;
 pub mod generate;
 pub mod health;
+pub mod projects;
+pub mod schemas;
+pub mod stream;
+pub mod types;
 pub mod upload;
 
 use crate::csv_parser::CsvData;
+use crate::generators::InferredColumn;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
+pub use anonymize::anonymize;
 pub use extract_headers::extract_headers;
 pub use generate::generate_placeholder;
 pub use health::health_check;
+pub use stream::generate_stream;
+pub use types::{list_locales, list_types};
 pub use upload::upload_csv;
 
 // Shared response types
@@ -21,5 +32,10 @@ pub struct ErrorResponse {
 #[derive(Serialize, Deserialize)]
 pub struct SuccessResponse {
     pub data: CsvData,
+    /// Each column's type as inferred from its values.
+    pub columns: Vec<InferredColumn>,
+    /// The inferred type of each header, to save as the dataset's
+    /// `column_types`.
+    pub column_types: HashMap<String, String>,
     pub message: String,
 }
@@ -0,0 +1,462 @@
+use super::generate::download_response;
+use crate::db::models::{GenerateProjectRequest, GenerationSource};
+use crate::db::operations;
+use crate::generators::{DatasetGenerator, resolve_seed};
+use crate::output;
+use crate::project::{self, MAX_TABLE_ROWS, ProjectErrors, ProjectSpec};
+use actix_web::{HttpResponse, Responder, web};
+use log::{error, info};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use sqlx::SqlitePool;
+use sqlx::error::BoxDynError;
+use std::collections::HashMap;
+
+pub async fn list(pool: web::Data<SqlitePool>) -> impl Responder {
+    info!("Listing all projects");
+
+    match operations::list_projects(pool.get_ref()).await {
+        Ok(projects) => HttpResponse::Ok().json(projects),
+        Err(e) => {
+            error!("Failed to list projects: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to list projects: {}", e)
+            }))
+        }
+    }
+}
+
+/// The generation sources of a project's datasets, keyed by dataset id.
+/// Datasets that no longer exist are left out.
+async fn load_sources(
+    pool: &SqlitePool,
+    spec: &ProjectSpec,
+) -> Result<HashMap<i64, GenerationSource>, BoxDynError> {
+    let mut sources = HashMap::new();
+    for table in &spec.tables {
+        if sources.contains_key(&table.dataset_id) {
+            continue;
+        }
+        if let Some(source) = operations::get_generation_source(pool, table.dataset_id).await? {
+            sources.insert(table.dataset_id, source);
+        }
+    }
+    Ok(sources)
+}
+
+/// Loads the project's datasets and checks the spec against them.
+async fn validated_sources(
+    pool: &SqlitePool,
+    spec: &ProjectSpec,
+) -> Result<HashMap<i64, GenerationSource>, HttpResponse> {
+    let sources = load_sources(pool, spec).await.map_err(|e| {
+        error!("Failed to fetch project datasets: {}", e);
+        HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to fetch project datasets: {}", e)
+        }))
+    })?;
+
+    let headers: HashMap<i64, Vec<String>> = sources
+        .iter()
+        .map(|(id, source)| (*id, source.sample.headers.clone()))
+        .collect();
+    spec.validate(&headers)
+        .map_err(|errors| invalid_project_response(&errors))?;
+
+    Ok(sources)
+}
+
+fn invalid_project_response(errors: &ProjectErrors) -> HttpResponse {
+    info!("Project rejected with {} error(s)", errors.0.len());
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "error": errors.to_string(),
+        "errors": errors.0
+    }))
+}
+
+pub async fn create(pool: web::Data<SqlitePool>, req: web::Json<ProjectSpec>) -> impl Responder {
+    info!("Saving project: {}", req.name);
+
+    if let Err(response) = validated_sources(pool.get_ref(), &req).await {
+        return response;
+    }
+
+    match operations::save_project(pool.get_ref(), &req).await {
+        Ok(id) => {
+            info!("Project saved with id: {}", id);
+            HttpResponse::Ok().json(serde_json::json!({
+                "id": id,
+                "message": "Project saved successfully"
+            }))
+        }
+        Err(e) => {
+            error!("Failed to save project: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to save project: {}", e)
+            }))
+        }
+    }
+}
+
+pub async fn get_one(pool: web::Data<SqlitePool>, path: web::Path<i64>) -> impl Responder {
+    let id = path.into_inner();
+    info!("Getting project with id: {}", id);
+
+    match operations::get_project(pool.get_ref(), id).await {
+        Ok(Some((project, spec))) => HttpResponse::Ok().json(serde_json::json!({
+            "id": project.id,
+            "name": spec.name,
+            "created_at": project.created_at,
+            "tables": spec.tables,
+            "foreign_keys": spec.foreign_keys
+        })),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Project with id {} not found", id)
+        })),
+        Err(e) => {
+            error!("Failed to get project: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get project: {}", e)
+            }))
+        }
+    }
+}
+
+pub async fn delete(pool: web::Data<SqlitePool>, path: web::Path<i64>) -> impl Responder {
+    let id = path.into_inner();
+    info!("Deleting project with id: {}", id);
+
+    match operations::delete_project(pool.get_ref(), id).await {
+        Ok(true) => {
+            info!("Project {} deleted successfully", id);
+            HttpResponse::Ok().json(serde_json::json!({
+                "message": "Project deleted successfully"
+            }))
+        }
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Project with id {} not found", id)
+        })),
+        Err(e) => {
+            error!("Failed to delete project: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to delete project: {}", e)
+            }))
+        }
+    }
+}
+
+/// Generates every table of a project, parents first, as one JSON response
+/// or one downloaded file.
+pub async fn generate(
+    pool: web::Data<SqlitePool>,
+    path: web::Path<i64>,
+    req: web::Json<GenerateProjectRequest>,
+) -> impl Responder {
+    let id = path.into_inner();
+    let row_count = req.row_count.unwrap_or(20);
+    let seed = resolve_seed(req.seed);
+
+    info!("Generating project with id: {} (seed {})", id, seed);
+
+    if row_count == 0 || row_count > MAX_TABLE_ROWS {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("row_count must be between 1 and {}", MAX_TABLE_ROWS)
+        }));
+    }
+    if let Some(format) = req.format
+        && !format.holds_many_tables()
+    {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!(
+                "{:?} output holds a single table; use json, sql or markdown for a project",
+                format
+            )
+        }));
+    }
+
+    let spec = match operations::get_project(pool.get_ref(), id).await {
+        Ok(Some((_, spec))) => spec,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": format!("Project with id {} not found", id)
+            }));
+        }
+        Err(e) => {
+            error!("Failed to fetch project: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to fetch project: {}", e)
+            }));
+        }
+    };
+
+    // The datasets may have changed since the project was saved.
+    let sources = match validated_sources(pool.get_ref(), &spec).await {
+        Ok(sources) => sources,
+        Err(response) => return response,
+    };
+
+    let use_sample_data = req.use_sample_data.unwrap_or(true);
+    let generators: Result<Vec<DatasetGenerator>, String> = spec
+        .tables
+        .iter()
+        .map(|table| {
+            DatasetGenerator::from_source(&sources[&table.dataset_id], use_sample_data)
+                .map_err(|e| format!("table '{}': {}", table.name, e))
+        })
+        .collect();
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let tables = generators.and_then(|generators| {
+        project::generate(&spec, &generators, row_count, &mut rng).map_err(|e| e.to_string())
+    });
+    let tables = match tables {
+        Ok(tables) => tables,
+        Err(e) => {
+            error!("Cannot generate project {}: {}", id, e);
+            return HttpResponse::BadRequest().json(serde_json::json!({ "error": e }));
+        }
+    };
+
+    info!(
+        "Generated {} tables for project '{}'",
+        tables.len(),
+        spec.name
+    );
+
+    if let Some(format) = req.format {
+        let bundle = output::render_bundle(
+            tables.iter().map(|t| (t.name.as_str(), &t.data)),
+            format,
+            req.null_as.unwrap_or_default(),
+        );
+        return match bundle {
+            Ok(body) => download_response(format, &spec.name, seed).body(body),
+            Err(e) => {
+                error!("Failed to render project as {:?}: {}", format, e);
+                HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": format!("Failed to render generated data: {}", e)
+                }))
+            }
+        };
+    }
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "tables": tables,
+        "seed": seed,
+        "message": format!("Generated {} tables for project '{}'", tables.len(), spec.name)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils;
+    use actix_web::{App, test};
+
+    async fn init_app(
+        pool: SqlitePool,
+    ) -> impl actix_web::dev::Service<
+        actix_http::Request,
+        Response = actix_web::dev::ServiceResponse,
+        Error = actix_web::Error,
+    > {
+        test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool))
+                .route("/projects", web::get().to(list))
+                .route("/projects", web::post().to(create))
+                .route("/projects/{id}", web::get().to(get_one))
+                .route("/projects/{id}", web::delete().to(delete))
+                .route("/projects/{id}/generate", web::post().to(generate)),
+        )
+        .await
+    }
+
+    /// Saves a customers and an orders dataset and a project linking them.
+    async fn save_shop(pool: &SqlitePool) -> serde_json::Value {
+        let customers = vec!["id".to_string(), "email".to_string()];
+        let orders = vec!["id".to_string(), "customer_id".to_string()];
+        let customers_id =
+            operations::save_dataset(pool, "customers", &customers, "custom", None, None)
+                .await
+                .unwrap();
+        let orders_id = operations::save_dataset(pool, "orders", &orders, "custom", None, None)
+            .await
+            .unwrap();
+
+        serde_json::json!({
+            "name": "Shop",
+            "tables": [
+                {"name": "customers", "dataset_id": customers_id, "rows": 3},
+                {"name": "orders", "dataset_id": orders_id}
+            ],
+            "foreign_keys": [{
+                "column": "orders.customer_id",
+                "references": "customers.id",
+                "children": {"min": 2, "max": 2}
+            }]
+        })
+    }
+
+    async fn create_project(
+        app: &impl actix_web::dev::Service<
+            actix_http::Request,
+            Response = actix_web::dev::ServiceResponse,
+            Error = actix_web::Error,
+        >,
+        payload: &serde_json::Value,
+    ) -> i64 {
+        let req = test::TestRequest::post()
+            .uri("/projects")
+            .set_json(payload)
+            .to_request();
+        let resp = test::call_service(app, req).await;
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        body["id"].as_i64().unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_create_and_generate() {
+        let pool = test_utils::setup_test_db().await;
+        let payload = save_shop(&pool).await;
+        let app = init_app(pool).await;
+        let id = create_project(&app, &payload).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/projects/{}", id))
+            .to_request();
+        let body: serde_json::Value =
+            test::read_body_json(test::call_service(&app, req).await).await;
+        assert_eq!(body["name"], "Shop");
+        assert_eq!(body["foreign_keys"][0]["references"], "customers.id");
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/generate", id))
+            .set_json(serde_json::json!({"seed": 5}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["seed"], 5);
+        assert_eq!(body["tables"][0]["name"], "customers");
+        assert_eq!(
+            body["tables"][0]["data"]["rows"].as_array().unwrap().len(),
+            3
+        );
+        let orders = body["tables"][1]["data"]["rows"].as_array().unwrap();
+        assert_eq!(orders.len(), 6);
+        for customer in [1, 2, 3] {
+            assert_eq!(orders.iter().filter(|row| row[1] == customer).count(), 2);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_generate_sql_bundle() {
+        let pool = test_utils::setup_test_db().await;
+        let payload = save_shop(&pool).await;
+        let app = init_app(pool).await;
+        let id = create_project(&app, &payload).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/generate", id))
+            .set_json(serde_json::json!({"seed": 1, "format": "sql"}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        assert_eq!(
+            resp.headers().get("Content-Disposition").unwrap(),
+            "attachment; filename=\"shop.sql\""
+        );
+        let body = test::read_body(resp).await;
+        let script = std::str::from_utf8(&body).unwrap();
+        let first_order = script.find("INSERT INTO \"orders\"").unwrap();
+        assert_eq!(
+            script[..first_order]
+                .matches("INSERT INTO \"customers\"")
+                .count(),
+            3
+        );
+        assert_eq!(script.matches("INSERT INTO \"orders\"").count(), 6);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/generate", id))
+            .set_json(serde_json::json!({"format": "csv"}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_create_rejects_invalid_project() {
+        let pool = test_utils::setup_test_db().await;
+        let mut payload = save_shop(&pool).await;
+        payload["foreign_keys"][0]["references"] = serde_json::json!("customers.uuid");
+        let app = init_app(pool.clone()).await;
+
+        let req = test::TestRequest::post()
+            .uri("/projects")
+            .set_json(&payload)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body["errors"][0],
+            "foreign_keys[0] 'orders.customer_id -> customers.uuid': table 'customers' has no column 'uuid'"
+        );
+        assert!(operations::list_projects(&pool).await.unwrap().is_empty());
+    }
+
+    #[actix_web::test]
+    async fn test_generate_after_dataset_deleted() {
+        let pool = test_utils::setup_test_db().await;
+        let payload = save_shop(&pool).await;
+        let app = init_app(pool.clone()).await;
+        let id = create_project(&app, &payload).await;
+        let orders_id = payload["tables"][1]["dataset_id"].as_i64().unwrap();
+        operations::delete_dataset(&pool, orders_id).await.unwrap();
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/generate", id))
+            .set_json(serde_json::json!({}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert!(
+            body["error"]
+                .as_str()
+                .unwrap()
+                .contains(&format!("dataset {} not found", orders_id))
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_list_and_delete() {
+        let pool = test_utils::setup_test_db().await;
+        let payload = save_shop(&pool).await;
+        let app = init_app(pool).await;
+        let id = create_project(&app, &payload).await;
+
+        let req = test::TestRequest::get().uri("/projects").to_request();
+        let body: serde_json::Value =
+            test::read_body_json(test::call_service(&app, req).await).await;
+        assert_eq!(body[0]["name"], "Shop");
+        assert_eq!(body[0]["table_count"], 2);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/projects/{}", id))
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/projects/{}/generate", id))
+            .set_json(serde_json::json!({}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+    }
+}
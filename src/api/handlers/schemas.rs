@@ -0,0 +1,292 @@
+use crate::db::operations;
+use crate::schema::{Schema, SchemaError, SchemaErrors, SchemaFormat};
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use log::{error, info};
+use sqlx::SqlitePool;
+
+/// Parses a schema from the request body. The format comes from the
+/// `Content-Type` header; anything else is read as YAML, which also accepts
+/// JSON. Columns naming a dictionary are checked against the saved ones.
+async fn parse_schema(
+    pool: &SqlitePool,
+    req: &HttpRequest,
+    body: &[u8],
+) -> Result<Schema, HttpResponse> {
+    let format = req
+        .headers()
+        .get("content-type")
+        .and_then(|value| value.to_str().ok())
+        .and_then(SchemaFormat::from_content_type)
+        .unwrap_or(SchemaFormat::Yaml);
+
+    let text = std::str::from_utf8(body).map_err(|_| {
+        invalid_schema_response(&SchemaErrors(vec![SchemaError::document(
+            "Schema file must be UTF-8 text",
+        )]))
+    })?;
+
+    let mut schema = Schema::read(text, format).map_err(|e| invalid_schema_response(&e))?;
+    if let Err(e) = operations::load_dictionaries(pool, &mut schema.columns).await {
+        error!("Failed to load dictionaries: {}", e);
+        return Err(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to load dictionaries: {}", e)
+        })));
+    }
+    schema.validate().map_err(|e| invalid_schema_response(&e))?;
+    Ok(schema)
+}
+
+fn invalid_schema_response(errors: &SchemaErrors) -> HttpResponse {
+    info!("Schema rejected with {} error(s)", errors.0.len());
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "valid": false,
+        "error": errors.to_string(),
+        "errors": errors.0
+    }))
+}
+
+pub async fn validate(
+    pool: web::Data<SqlitePool>,
+    req: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    info!("Validating schema file ({} bytes)", body.len());
+
+    match parse_schema(pool.get_ref(), &req, &body).await {
+        Ok(schema) => HttpResponse::Ok().json(serde_json::json!({
+            "valid": true,
+            "schema": schema
+        })),
+        Err(response) => response,
+    }
+}
+
+pub async fn import(
+    pool: web::Data<SqlitePool>,
+    req: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    let schema = match parse_schema(pool.get_ref(), &req, &body).await {
+        Ok(schema) => schema,
+        Err(response) => return response,
+    };
+
+    info!(
+        "Importing schema '{}' with {} columns",
+        schema.name,
+        schema.columns.len()
+    );
+
+    match operations::save_schema_dataset(pool.get_ref(), &schema).await {
+        Ok(id) => {
+            info!("Schema imported as dataset {}", id);
+            HttpResponse::Ok().json(serde_json::json!({
+                "id": id,
+                "message": "Schema imported successfully"
+            }))
+        }
+        Err(e) => {
+            error!("Failed to import schema: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to import schema: {}", e)
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils;
+    use crate::schema::{Bound, ColumnSpec};
+    use actix_web::{App, test};
+
+    const SCHEMA_YAML: &str = r#"
+version: 1
+name: Orders
+columns:
+  - name: order_id
+    type: id
+  - name: status
+    type: enum
+    values: [open, shipped]
+  - name: total
+    type: money
+    min: 5
+    max: 50
+"#;
+
+    #[actix_web::test]
+    async fn test_validate_yaml() {
+        let pool = test_utils::setup_test_db().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool))
+                .route("/schemas/validate", web::post().to(validate)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/schemas/validate")
+            .insert_header(("content-type", "application/yaml"))
+            .set_payload(SCHEMA_YAML)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["valid"], true);
+        assert_eq!(body["schema"]["columns"][1]["values"][1], "shipped");
+    }
+
+    #[actix_web::test]
+    async fn test_validate_reports_column_errors() {
+        let pool = test_utils::setup_test_db().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool))
+                .route("/schemas/validate", web::post().to(validate)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/schemas/validate")
+            .insert_header(("content-type", "application/toml"))
+            .set_payload(
+                "version = 1\nname = \"x\"\n\n[[columns]]\nname = \"age\"\ntype = \"age\"\nmin = \"old\"\n",
+            )
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["valid"], false);
+        assert_eq!(body["errors"][0]["column"], "age");
+        assert_eq!(body["errors"][0]["index"], 0);
+        assert_eq!(
+            body["errors"][0]["message"],
+            "min must be a number, got 'old'"
+        );
+        assert_eq!(
+            body["error"],
+            "columns[0] 'age': min must be a number, got 'old'"
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_validate_unparseable_body() {
+        let pool = test_utils::setup_test_db().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool))
+                .route("/schemas/validate", web::post().to(validate)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/schemas/validate")
+            .insert_header(("content-type", "application/json"))
+            .set_payload("{\"version\": 1,")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert!(
+            body["error"]
+                .as_str()
+                .unwrap()
+                .starts_with("Invalid schema file:")
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_import_saves_dataset_with_columns() {
+        let pool = test_utils::setup_test_db().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/schemas/import", web::post().to(import)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/schemas/import")
+            .set_payload(SCHEMA_YAML)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let id = body["id"].as_i64().unwrap();
+
+        let source = operations::get_generation_source(&pool, id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(source.dataset.name, "Orders");
+        assert_eq!(source.dataset.data_type, "custom");
+        assert_eq!(source.column_types["status"], "enum");
+        let columns = source.columns.unwrap();
+        assert_eq!(columns.len(), 3);
+        assert_eq!(columns[2].max, Some(Bound::Number(50.0)));
+        assert_eq!(columns[0], ColumnSpec::new("order_id", "id"));
+    }
+
+    #[actix_web::test]
+    async fn test_import_invalid_schema_saves_nothing() {
+        let pool = test_utils::setup_test_db().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/schemas/import", web::post().to(import)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/schemas/import")
+            .set_payload("version: 1\nname: x\ncolumns:\n  - name: a\n    type: enum\n")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        assert!(operations::list_datasets(&pool).await.unwrap().is_empty());
+    }
+
+    #[actix_web::test]
+    async fn test_validate_checks_dictionaries() {
+        let pool = test_utils::setup_test_db().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/schemas/validate", web::post().to(validate)),
+        )
+        .await;
+        let schema = "version: 1\nname: x\ncolumns:\n  - name: dept\n    type: dictionary\n    dictionary: departments\n";
+
+        let req = test::TestRequest::post()
+            .uri("/schemas/validate")
+            .set_payload(schema)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body["error"],
+            "columns[0] 'dept': no dictionary named 'departments'"
+        );
+
+        let entries = vec![crate::dictionary::DictionaryEntry {
+            value: "ENG".to_string(),
+            weight: None,
+        }];
+        operations::save_dictionary(&pool, "departments", &entries)
+            .await
+            .unwrap();
+        let req = test::TestRequest::post()
+            .uri("/schemas/validate")
+            .set_payload(schema)
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+    }
+}
@@ -0,0 +1,235 @@
+use super::generate::download_response;
+use crate::generators::{DataGenerator, SmartGenerator, resolve_seed};
+use crate::output::{MAX_STREAM_ROWS, NullValue, OutputFormat, RowChunks};
+use actix_web::web::Bytes;
+use actix_web::{HttpResponse, Responder, web};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct StreamRequest {
+    pub row_count: Option<usize>,
+    pub headers: Option<Vec<String>>,
+    pub seed: Option<u64>,
+    /// Defaults to CSV.
+    pub format: Option<OutputFormat>,
+    pub null_as: Option<NullValue>,
+}
+
+pub async fn generate_stream(req: web::Json<StreamRequest>) -> impl Responder {
+    let row_count = req.row_count.unwrap_or(20);
+    let seed = resolve_seed(req.seed);
+    let format = req.format.unwrap_or(OutputFormat::Csv);
+
+    info!(
+        "Streaming {} placeholder rows as {:?} with seed {}",
+        row_count, format, seed
+    );
+
+    if let Some(response) = stream_row_count_error(row_count) {
+        return response;
+    }
+
+    let headers = req.headers.clone().unwrap_or_else(|| {
+        vec![
+            "id".to_string(),
+            "name".to_string(),
+            "email".to_string(),
+            "age".to_string(),
+            "city".to_string(),
+        ]
+    });
+
+    let generator = SmartGenerator::new(headers);
+    if let Err(e) = generator.check_row_count(row_count) {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": e.to_string() }));
+    }
+    stream_response(
+        generator,
+        row_count,
+        seed,
+        format,
+        req.null_as.unwrap_or_default(),
+        "generated_data",
+    )
+}
+
+/// Returns the 400 response for a row count outside `1..=MAX_STREAM_ROWS`.
+pub fn stream_row_count_error(row_count: usize) -> Option<HttpResponse> {
+    if row_count == 0 || row_count > MAX_STREAM_ROWS {
+        return Some(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("row_count must be between 1 and {}", MAX_STREAM_ROWS)
+        })));
+    }
+    None
+}
+
+/// Builds a chunked response that generates rows lazily as the client reads.
+/// The seed is echoed in the `X-Seed` header since the body is just data.
+pub fn stream_response<G: DataGenerator + 'static>(
+    generator: G,
+    row_count: usize,
+    seed: u64,
+    format: OutputFormat,
+    null_as: NullValue,
+    name: &str,
+) -> HttpResponse {
+    let chunks = RowChunks::new(generator, row_count, seed, format, null_as, name)
+        .map(|chunk| chunk.map(Bytes::from));
+
+    download_response(format, name, seed).streaming(futures_util::stream::iter(chunks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{App, test as actix_test, web};
+
+    #[tokio::test]
+    async fn test_generate_stream_csv() {
+        let app = actix_test::init_service(
+            App::new().route("/generate/stream", web::post().to(generate_stream)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/generate/stream")
+            .set_json(serde_json::json!({
+                "row_count": 1500,
+                "headers": ["id", "email"],
+                "seed": 7
+            }))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        assert_eq!(resp.headers().get("x-seed").unwrap(), "7");
+        assert!(
+            resp.headers()
+                .get("content-type")
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .starts_with("text/csv")
+        );
+        assert_eq!(
+            resp.headers().get("content-disposition").unwrap(),
+            "attachment; filename=\"generated_data.csv\""
+        );
+
+        let body = actix_test::read_body(resp).await;
+        let text = std::str::from_utf8(&body).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 1501);
+        assert_eq!(lines[0], "id,email");
+        assert!(lines[1500].starts_with("1500,"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_stream_ndjson() {
+        let app = actix_test::init_service(
+            App::new().route("/generate/stream", web::post().to(generate_stream)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/generate/stream")
+            .set_json(serde_json::json!({
+                "row_count": 3,
+                "headers": ["id", "name"],
+                "format": "ndjson"
+            }))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        let body = actix_test::read_body(resp).await;
+        let text = std::str::from_utf8(&body).unwrap();
+        let rows: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[2]["id"], 3);
+        assert!(rows[0]["name"].as_str().unwrap().contains(' '));
+    }
+
+    #[tokio::test]
+    async fn test_generate_stream_json_array_spans_chunks() {
+        let app = actix_test::init_service(
+            App::new().route("/generate/stream", web::post().to(generate_stream)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/generate/stream")
+            .set_json(serde_json::json!({
+                "row_count": 2_500,
+                "headers": ["id"],
+                "format": "json"
+            }))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        assert_eq!(
+            resp.headers().get("content-disposition").unwrap(),
+            "attachment; filename=\"generated_data.json\""
+        );
+        let body: serde_json::Value = actix_test::read_body_json(resp).await;
+        assert_eq!(body.as_array().unwrap().len(), 2_500);
+        assert_eq!(body[2_499]["id"], 2_500);
+    }
+
+    #[tokio::test]
+    async fn test_generate_stream_same_seed_same_body() {
+        let app = actix_test::init_service(
+            App::new().route("/generate/stream", web::post().to(generate_stream)),
+        )
+        .await;
+
+        let mut bodies = Vec::new();
+        for _ in 0..2 {
+            let req = actix_test::TestRequest::post()
+                .uri("/generate/stream")
+                .set_json(serde_json::json!({ "row_count": 50, "seed": 11 }))
+                .to_request();
+            let resp = actix_test::call_service(&app, req).await;
+            bodies.push(actix_test::read_body(resp).await);
+        }
+
+        assert_eq!(bodies[0], bodies[1]);
+    }
+
+    #[tokio::test]
+    async fn test_generate_stream_row_count_limits() {
+        let app = actix_test::init_service(
+            App::new().route("/generate/stream", web::post().to(generate_stream)),
+        )
+        .await;
+
+        for row_count in [0, MAX_STREAM_ROWS + 1] {
+            let req = actix_test::TestRequest::post()
+                .uri("/generate/stream")
+                .set_json(serde_json::json!({ "row_count": row_count }))
+                .to_request();
+            let resp = actix_test::call_service(&app, req).await;
+            assert_eq!(resp.status(), 400);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_stream_invalid_format() {
+        let app = actix_test::init_service(
+            App::new().route("/generate/stream", web::post().to(generate_stream)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/generate/stream")
+            .set_json(serde_json::json!({ "format": "xml" }))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+}
@@ -0,0 +1,92 @@
+use crate::generators::{locale_names, registry};
+use actix_web::{HttpResponse, Responder};
+use log::debug;
+
+/// Lists the registered column types and the parameters each accepts.
+pub async fn list_types() -> impl Responder {
+    debug!("Listing column types");
+
+    let types: Vec<serde_json::Value> = registry()
+        .types()
+        .map(|column_type| {
+            serde_json::json!({
+                "name": column_type.name(),
+                "description": column_type.description(),
+                "params": column_type.params()
+            })
+        })
+        .collect();
+
+    HttpResponse::Ok().json(types)
+}
+
+/// Lists the bundled locale packs a dataset or column can name.
+pub async fn list_locales() -> impl Responder {
+    debug!("Listing locales");
+
+    let locales: Vec<serde_json::Value> = locale_names()
+        .into_iter()
+        .map(|(code, name)| serde_json::json!({"code": code, "name": name}))
+        .collect();
+
+    HttpResponse::Ok().json(locales)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{App, test, web};
+
+    #[actix_web::test]
+    async fn test_list_types() {
+        let app = test::init_service(App::new().route("/types", web::get().to(list_types))).await;
+
+        let req = test::TestRequest::get().uri("/types").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let types = body.as_array().unwrap();
+        assert_eq!(types.len(), registry().names().len());
+        assert_eq!(types[0]["name"], "uuid");
+
+        let money = types.iter().find(|t| t["name"] == "money").unwrap();
+        let params: Vec<&str> = money["params"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            params,
+            vec![
+                "min",
+                "max",
+                "precision",
+                "currency",
+                "min_column",
+                "max_column"
+            ]
+        );
+        assert_eq!(money["params"][4]["type"], "column");
+        assert_eq!(money["params"][2]["type"], "integer");
+
+        let values = &types.iter().find(|t| t["name"] == "enum").unwrap()["params"][0];
+        assert_eq!(values["type"], "string_list");
+        assert_eq!(values["required"], true);
+    }
+
+    #[actix_web::test]
+    async fn test_list_locales() {
+        let app =
+            test::init_service(App::new().route("/locales", web::get().to(list_locales))).await;
+
+        let req = test::TestRequest::get().uri("/locales").to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+
+        let locales = body.as_array().unwrap();
+        assert_eq!(locales.len(), 5);
+        assert_eq!(locales[1]["code"], "de_DE");
+        assert_eq!(locales[1]["name"], "Deutsch (Deutschland)");
+    }
+}
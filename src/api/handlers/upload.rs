@@ -6,6 +6,8 @@ use std::time::Instant;
 
 use super::{ErrorResponse, SuccessResponse};
 use crate::csv_parser::parse_csv_from_bytes;
+use crate::generators::infer_columns;
+use crate::schema;
 
 pub async fn upload_csv(mut payload: Multipart) -> HttpResponse {
     let start_time = Instant::now();
@@ -58,8 +60,12 @@ pub async fn upload_csv(mut payload: Multipart) -> HttpResponse {
                 elapsed.as_secs_f64() * 1000.0
             );
 
+            let columns = infer_columns(&csv_data.headers, &csv_data.rows);
+            let specs: Vec<_> = columns.iter().map(|c| c.column.clone()).collect();
             HttpResponse::Ok().json(SuccessResponse {
                 data: csv_data,
+                column_types: schema::column_type_map(&specs),
+                columns,
                 message: format!("Successfully parsed CSV with {} rows", row_count),
             })
         }
@@ -77,6 +83,7 @@ mod tests {
     use super::*;
     use crate::csv_parser::CsvData;
     use actix_web::{App, test as actix_test, web};
+    use std::collections::HashMap;
 
     // Helper function to create multipart request with CSV data
     fn create_multipart_request(csv_content: &str) -> actix_test::TestRequest {
@@ -274,6 +281,37 @@ mod tests {
         assert!(data.get("rows").is_some());
     }
 
+    #[tokio::test]
+    async fn test_upload_csv_infers_column_types() {
+        let app =
+            actix_test::init_service(App::new().route("/upload", web::post().to(upload_csv))).await;
+
+        let csv_content = "ref,joined,contact,plan
+            1,31/01/2024,ann@test.com,free
+            2,15/03/2023,bo@test.com,pro
+            3,01/02/2024,,free
+            4,02/02/2024,cy@test.com,free";
+        let req = create_multipart_request(csv_content).to_request();
+
+        let resp = actix_test::call_service(&app, req).await;
+        let body: serde_json::Value = actix_test::read_body_json(resp).await;
+
+        assert_eq!(
+            body["column_types"],
+            serde_json::json!({"ref": "id", "joined": "date", "contact": "email", "plan": "enum"})
+        );
+        let columns = body["columns"].as_array().unwrap();
+        assert_eq!(columns[0]["kind"], "integer");
+        assert_eq!(columns[1]["column"]["format"], "%d/%m/%Y");
+        assert_eq!(columns[1]["column"]["min"], "2023-03-15");
+        assert_eq!(columns[2]["confidence"], 1.0);
+        assert_eq!(columns[2]["column"]["null_rate"], 0.25);
+        assert_eq!(
+            columns[3]["column"]["values"],
+            serde_json::json!(["free", "pro"])
+        );
+    }
+
     #[tokio::test]
     async fn test_upload_csv_message_format() {
         let app =
@@ -341,6 +379,8 @@ mod tests {
 
         let response = SuccessResponse {
             data: csv_data,
+            columns: Vec::new(),
+            column_types: HashMap::new(),
             message: "Test message".to_string(),
         };
 
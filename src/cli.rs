@@ -0,0 +1,536 @@
+use crate::db::operations;
+use crate::generators::{
+    DataGenerator, DatasetGenerator, SmartGenerator, resolve_seed, with_locale,
+};
+use crate::output::{MAX_STREAM_ROWS, NullValue, OutputFormat, RowChunks};
+use crate::profile::{self, DatasetProfile};
+use crate::schema::{Schema, SchemaFormat};
+use clap::{Arg, ArgAction, ArgMatches, Command, value_parser};
+use log::info;
+use sqlx::SqlitePool;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// `testdatagen generate`: writes generated rows to a file or stdout without
+/// starting the web server.
+pub fn generate_command() -> Command {
+    Command::new("generate")
+        .about("Generate rows from headers or a saved dataset")
+        .arg(
+            Arg::new("headers")
+                .long("headers")
+                .help("Comma-separated column names, e.g. id,name,email")
+                .value_delimiter(',')
+                .conflicts_with_all(["dataset", "schema"]),
+        )
+        .arg(
+            Arg::new("dataset")
+                .long("dataset")
+                .short('d')
+                .help("Generate from the saved dataset with this id")
+                .value_parser(value_parser!(i64))
+                .conflicts_with("schema"),
+        )
+        .arg(
+            Arg::new("schema")
+                .long("schema")
+                .help("Generate from a schema file (.yaml, .yml, .json or .toml)"),
+        )
+        .arg(
+            Arg::new("rows")
+                .long("rows")
+                .short('n')
+                .help("Number of rows to generate")
+                .default_value("20")
+                .value_parser(value_parser!(u64).range(1..=MAX_STREAM_ROWS as u64)),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .help("Seed for reproducible output")
+                .value_parser(value_parser!(u64)),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .short('f')
+                .help("csv, tsv, json, ndjson, sql or markdown")
+                .default_value("csv")
+                .value_parser(|s: &str| s.parse::<OutputFormat>()),
+        )
+        .arg(
+            Arg::new("null-as")
+                .long("null-as")
+                .help("How null cells are written: empty, NULL, \\N or null")
+                .default_value("empty")
+                .value_parser(|s: &str| s.parse::<NullValue>()),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .short('o')
+                .help("File to write to (defaults to stdout)"),
+        )
+        .arg(
+            Arg::new("no-sample")
+                .long("no-sample")
+                .help("Ignore the dataset's stored sample rows")
+                .requires("dataset")
+                .action(ArgAction::SetTrue),
+        )
+}
+
+pub async fn run_generate(matches: &ArgMatches, pool: &SqlitePool) -> Result<(), Box<dyn Error>> {
+    let row_count = *matches.get_one::<u64>("rows").unwrap() as usize;
+    let seed = resolve_seed(matches.get_one::<u64>("seed").copied());
+    let format = *matches.get_one::<OutputFormat>("format").unwrap();
+    let null_as = *matches.get_one::<NullValue>("null-as").unwrap();
+
+    let (generator, name) = match (
+        matches.get_one::<i64>("dataset"),
+        matches.get_one::<String>("schema"),
+    ) {
+        (_, Some(path)) => {
+            let schema = load_schema(Path::new(path), pool).await?;
+            let columns = with_locale(&schema.columns, schema.locale.as_deref());
+            let generator = DatasetGenerator::Smart(SmartGenerator::from_columns(&columns)?);
+            (generator, schema.name)
+        }
+        (Some(&id), None) => {
+            let source = operations::get_generation_source(pool, id)
+                .await
+                .map_err(|e| e as Box<dyn Error>)?
+                .ok_or_else(|| format!("Dataset with id {} not found", id))?;
+            let generator = DatasetGenerator::from_source(&source, !matches.get_flag("no-sample"))?;
+            (generator, source.dataset.name)
+        }
+        (None, None) => {
+            let headers = match matches.get_many::<String>("headers") {
+                Some(headers) => headers.map(|h| h.trim().to_string()).collect(),
+                None => vec![
+                    "id".to_string(),
+                    "name".to_string(),
+                    "email".to_string(),
+                    "age".to_string(),
+                    "city".to_string(),
+                ],
+            };
+            let generator = DatasetGenerator::Smart(SmartGenerator::new(headers));
+            (generator, "generated_data".to_string())
+        }
+    };
+
+    generator.check_row_count(row_count)?;
+
+    info!(
+        "Generating {} rows from '{}' as {:?} with seed {}",
+        row_count, name, format, seed
+    );
+
+    match matches.get_one::<String>("output") {
+        Some(path) => {
+            let mut file = BufWriter::new(File::create(path)?);
+            write_rows(
+                generator, row_count, seed, format, null_as, &name, &mut file,
+            )?;
+            file.flush()?;
+        }
+        None => {
+            let mut stdout = io::stdout().lock();
+            write_rows(
+                generator,
+                row_count,
+                seed,
+                format,
+                null_as,
+                &name,
+                &mut stdout,
+            )?;
+            stdout.flush()?;
+        }
+    }
+
+    // Stdout may be the data itself, so the seed goes to stderr.
+    eprintln!("Generated {} rows with seed {}", row_count, seed);
+    Ok(())
+}
+
+/// `testdatagen profile`: prints per-column statistics of a saved dataset's
+/// sample rows.
+pub fn profile_command() -> Command {
+    Command::new("profile")
+        .about("Show column statistics of a saved dataset's sample rows")
+        .arg(
+            Arg::new("dataset")
+                .long("dataset")
+                .short('d')
+                .help("Profile the saved dataset with this id")
+                .required(true)
+                .value_parser(value_parser!(i64)),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help("Print the profile as JSON, as the API returns it")
+                .action(ArgAction::SetTrue),
+        )
+}
+
+pub async fn run_profile(matches: &ArgMatches, pool: &SqlitePool) -> Result<(), Box<dyn Error>> {
+    let id = *matches.get_one::<i64>("dataset").unwrap();
+    let (dataset, csv_data) = operations::get_datasets(pool, id)
+        .await
+        .map_err(|e| e as Box<dyn Error>)?
+        .ok_or_else(|| format!("Dataset with id {} not found", id))?;
+    let profile = profile::profile(&csv_data);
+
+    let mut stdout = io::stdout().lock();
+    if matches.get_flag("json") {
+        serde_json::to_writer_pretty(&mut stdout, &profile)?;
+        writeln!(stdout)?;
+    } else {
+        write_profile(&dataset.name, &profile, &mut stdout)?;
+    }
+    Ok(())
+}
+
+/// A readable summary of a profile, one block per column.
+pub fn write_profile<W: Write>(
+    name: &str,
+    profile: &DatasetProfile,
+    out: &mut W,
+) -> io::Result<()> {
+    writeln!(out, "{}: {} sample rows", name, profile.row_count)?;
+    for column in &profile.columns {
+        writeln!(out)?;
+        writeln!(
+            out,
+            "{}: {}, type {} ({:.0}% confidence)",
+            column.name,
+            column.kind.name(),
+            column.detected_type,
+            column.confidence * 100.0
+        )?;
+        writeln!(
+            out,
+            "  count {}, distinct {}, blank {}",
+            column.count, column.distinct, column.blank
+        )?;
+        if let Some(numeric) = &column.numeric {
+            writeln!(
+                out,
+                "  min {}, max {}, mean {:.2}, std dev {:.2}",
+                numeric.min, numeric.max, numeric.mean, numeric.std_dev
+            )?;
+        }
+        if let Some(lengths) = &column.lengths {
+            writeln!(
+                out,
+                "  length {} to {}, mean {:.1}",
+                lengths.min, lengths.max, lengths.mean
+            )?;
+        }
+        if !column.top_values.is_empty() {
+            let top: Vec<String> = column
+                .top_values
+                .iter()
+                .map(|v| format!("{} ({})", v.value, v.count))
+                .collect();
+            writeln!(out, "  top: {}", top.join(", "))?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads a schema file and validates it once its dictionaries are loaded.
+pub async fn load_schema(path: &Path, pool: &SqlitePool) -> Result<Schema, Box<dyn Error>> {
+    let format = SchemaFormat::from_path(path).ok_or_else(|| {
+        format!(
+            "Cannot tell the schema format of '{}'; use a .yaml, .yml, .json or .toml file",
+            path.display()
+        )
+    })?;
+    let text = std::fs::read_to_string(path)?;
+    let mut schema = Schema::read(&text, format)?;
+    operations::load_dictionaries(pool, &mut schema.columns)
+        .await
+        .map_err(|e| e as Box<dyn Error>)?;
+    schema.validate()?;
+    Ok(schema)
+}
+
+/// Encodes rows chunk by chunk, so large row counts never sit in memory.
+pub fn write_rows<G: DataGenerator, W: Write>(
+    generator: G,
+    row_count: usize,
+    seed: u64,
+    format: OutputFormat,
+    null_as: NullValue,
+    name: &str,
+    out: &mut W,
+) -> io::Result<()> {
+    for chunk in RowChunks::new(generator, row_count, seed, format, null_as, name) {
+        out.write_all(&chunk?)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A file in the temp dir named after the process and a counter, so
+    /// parallel tests and concurrent runs never share one; removed on drop.
+    struct TempFile(std::path::PathBuf);
+
+    impl TempFile {
+        fn new(extension: &str) -> Self {
+            static NEXT: AtomicUsize = AtomicUsize::new(0);
+            TempFile(std::env::temp_dir().join(format!(
+                "testdatagen_cli_{}_{}.{}",
+                std::process::id(),
+                NEXT.fetch_add(1, Ordering::Relaxed),
+                extension
+            )))
+        }
+
+        fn arg(&self) -> String {
+            self.0.to_str().unwrap().to_string()
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn parse(args: &[&str]) -> Result<ArgMatches, clap::Error> {
+        let mut argv = vec!["generate"];
+        argv.extend_from_slice(args);
+        generate_command().try_get_matches_from(argv)
+    }
+
+    #[test]
+    fn test_generate_command_defaults() {
+        let matches = parse(&[]).unwrap();
+        assert_eq!(matches.get_one::<u64>("rows"), Some(&20));
+        assert_eq!(
+            matches.get_one::<OutputFormat>("format"),
+            Some(&OutputFormat::Csv)
+        );
+        assert!(matches.get_one::<u64>("seed").is_none());
+        assert_eq!(
+            matches.get_one::<NullValue>("null-as"),
+            Some(&NullValue::Empty)
+        );
+    }
+
+    #[test]
+    fn test_generate_command_parses_headers() {
+        let matches = parse(&["--headers", "id,name,email", "--format", "ndjson"]).unwrap();
+        let headers: Vec<&String> = matches.get_many::<String>("headers").unwrap().collect();
+        assert_eq!(headers, ["id", "name", "email"]);
+        assert_eq!(
+            matches.get_one::<OutputFormat>("format"),
+            Some(&OutputFormat::Ndjson)
+        );
+    }
+
+    #[test]
+    fn test_generate_command_rejects_invalid_args() {
+        assert!(parse(&["--headers", "id", "--dataset", "1"]).is_err());
+        assert!(parse(&["--rows", "0"]).is_err());
+        assert!(parse(&["--format", "xml"]).is_err());
+        assert!(parse(&["--null-as", "none"]).is_err());
+        assert!(parse(&["--no-sample"]).is_err());
+        assert!(parse(&["--schema", "a.yaml", "--dataset", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_write_rows_is_reproducible() {
+        let mut first = Vec::new();
+        let mut second = Vec::new();
+        for out in [&mut first, &mut second] {
+            let generator = SmartGenerator::new(vec!["id".to_string(), "email".to_string()]);
+            write_rows(
+                generator,
+                1_500,
+                7,
+                OutputFormat::Csv,
+                NullValue::Empty,
+                "data",
+                out,
+            )
+            .unwrap();
+        }
+
+        assert_eq!(first, second);
+        let text = String::from_utf8(first).unwrap();
+        assert_eq!(text.lines().count(), 1_501);
+        assert_eq!(text.lines().next(), Some("id,email"));
+    }
+
+    #[tokio::test]
+    async fn test_run_generate_from_dataset_to_file() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["id".to_string(), "city".to_string()];
+        let id = operations::save_dataset(&pool, "Cities", &headers, "custom", None, None)
+            .await
+            .unwrap();
+
+        let path = TempFile::new("json");
+        let path_arg = path.arg();
+        let id_arg = id.to_string();
+        let matches = parse(&[
+            "--dataset",
+            &id_arg,
+            "--rows",
+            "5",
+            "--seed",
+            "3",
+            "--format",
+            "json",
+            "-o",
+            &path_arg,
+        ])
+        .unwrap();
+
+        run_generate(&matches, &pool).await.unwrap();
+
+        let rows: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path.0).unwrap()).unwrap();
+        assert_eq!(rows.as_array().unwrap().len(), 5);
+        assert_eq!(rows[4]["id"], 5);
+    }
+
+    #[tokio::test]
+    async fn test_profile_command() {
+        assert!(profile_command().try_get_matches_from(["profile"]).is_err());
+
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["id".to_string(), "plan".to_string()];
+        let sample = vec![
+            vec!["1".to_string(), "free".to_string()],
+            vec!["2".to_string(), "".to_string()],
+            vec!["3".to_string(), "free".to_string()],
+        ];
+        let id =
+            operations::save_dataset(&pool, "Plans", &headers, "uploaded", None, Some(&sample))
+                .await
+                .unwrap();
+        let (_, csv_data) = operations::get_datasets(&pool, id).await.unwrap().unwrap();
+
+        let mut out = Vec::new();
+        write_profile("Plans", &profile::profile(&csv_data), &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Plans: 3 sample rows\n\
+             \n\
+             id: integer, type id (100% confidence)\n\
+             \x20 count 3, distinct 3, blank 0\n\
+             \x20 min 1, max 3, mean 2.00, std dev 0.82\n\
+             \x20 top: 1 (1), 2 (1), 3 (1)\n\
+             \n\
+             plan: enum, type enum (50% confidence)\n\
+             \x20 count 3, distinct 1, blank 1\n\
+             \x20 length 4 to 4, mean 4.0\n\
+             \x20 top: free (2)\n"
+        );
+
+        let id_arg = id.to_string();
+        let matches = profile_command()
+            .try_get_matches_from(["profile", "--dataset", &id_arg, "--json"])
+            .unwrap();
+        run_profile(&matches, &pool).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run_generate_from_schema_file() {
+        let pool = test_utils::setup_test_db().await;
+        let schema_path = TempFile::new("toml");
+        let output_path = TempFile::new("csv");
+        std::fs::write(
+            &schema_path.0,
+            "version = 1\nname = \"plans\"\n\n[[columns]]\nname = \"plan\"\ntype = \"enum\"\nvalues = [\"basic\", \"plus\"]\n",
+        )
+        .unwrap();
+
+        let schema_arg = schema_path.arg();
+        let output_arg = output_path.arg();
+        let matches = parse(&["--schema", &schema_arg, "--rows", "30", "-o", &output_arg]).unwrap();
+        run_generate(&matches, &pool).await.unwrap();
+
+        let output = std::fs::read_to_string(&output_path.0).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "plan");
+        assert_eq!(lines.len(), 31);
+        assert!(lines[1..].iter().all(|v| *v == "basic" || *v == "plus"));
+    }
+
+    #[tokio::test]
+    async fn test_run_generate_writes_nulls() {
+        let pool = test_utils::setup_test_db().await;
+        let schema_path = TempFile::new("yaml");
+        let output_path = TempFile::new("tsv");
+        std::fs::write(
+            &schema_path.0,
+            "version: 1\nname: people\ncolumns:\n  - name: id\n  - name: nickname\n    type: text\n    null_rate: 1\n",
+        )
+        .unwrap();
+
+        let schema_arg = schema_path.arg();
+        let output_arg = output_path.arg();
+        let matches = parse(&[
+            "--schema",
+            &schema_arg,
+            "--rows",
+            "3",
+            "--format",
+            "tsv",
+            "--null-as",
+            "\\N",
+            "-o",
+            &output_arg,
+        ])
+        .unwrap();
+        run_generate(&matches, &pool).await.unwrap();
+
+        let output = std::fs::read_to_string(&output_path.0).unwrap();
+        assert_eq!(output, "id\tnickname\n1\t\\N\n2\t\\N\n3\t\\N\n");
+    }
+
+    #[tokio::test]
+    async fn test_load_schema_errors() {
+        let pool = test_utils::setup_test_db().await;
+        let err = load_schema(Path::new("schema.txt"), &pool)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Cannot tell the schema format"));
+
+        let path = TempFile::new("yaml");
+        std::fs::write(
+            &path.0,
+            "version: 1\nname: x\ncolumns:\n  - name: a\n    type: nope\n",
+        )
+        .unwrap();
+        let err = load_schema(&path.0, &pool).await.unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("columns[0] 'a': unknown type 'nope'")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_generate_unknown_dataset() {
+        let pool = test_utils::setup_test_db().await;
+        let matches = parse(&["--dataset", "999"]).unwrap();
+
+        let err = run_generate(&matches, &pool).await.unwrap_err();
+        assert_eq!(err.to_string(), "Dataset with id 999 not found");
+    }
+}
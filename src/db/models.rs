@@ -1,3 +1,6 @@
+use crate::csv_parser::CsvData;
+use crate::output::{NullValue, OutputFormat};
+use crate::schema::ColumnSpec;
 use chrono::DateTime;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -14,6 +17,21 @@ pub struct Dataset {
     pub headers: String,
     pub column_types: Option<String>,
     pub has_sample_data: bool,
+    pub seed: Option<i64>,
+    /// JSON array of `ColumnSpec`s, set when the dataset came from a schema file.
+    pub columns: Option<String>,
+    /// Locale pack of every column that names none.
+    pub locale: Option<String>,
+}
+
+/// A saved dataset with its parsed column types and stored sample rows:
+/// everything needed to build a generator for it.
+#[derive(Debug)]
+pub struct GenerationSource {
+    pub dataset: Dataset,
+    pub column_types: HashMap<String, String>,
+    pub columns: Option<Vec<ColumnSpec>>,
+    pub sample: CsvData,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -31,11 +49,94 @@ pub struct SaveDatasetRequest {
     pub data_type: String,
     pub column_types: Option<HashMap<String, String>>,
     pub sample_data: Option<Vec<Vec<String>>>,
+    /// Per-column types and generator parameters, keyed by header name.
+    /// Headers without a spec keep their `column_types` entry.
+    pub columns: Option<Vec<ColumnSpec>>,
+    /// Locale pack of every column that names none, e.g. "de_DE".
+    #[serde(default)]
+    pub locale: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct GenerateFromDatasetRequest {
     pub row_count: Option<usize>,
+    /// Learn value distributions from stored sample rows (defaults to true).
+    pub use_sample_data: Option<bool>,
+    pub seed: Option<u64>,
+    /// Save the generated rows as a new 'generated' dataset.
+    pub save: Option<bool>,
+    /// Returns the rows as a file download instead of JSON.
+    pub format: Option<OutputFormat>,
+    /// How null cells are written in the download.
+    pub null_as: Option<NullValue>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StreamFromDatasetRequest {
+    pub row_count: Option<usize>,
+    pub use_sample_data: Option<bool>,
+    pub seed: Option<u64>,
+    /// Defaults to CSV.
+    pub format: Option<OutputFormat>,
+    pub null_as: Option<NullValue>,
+}
+
+/// Generation parameters of a fidelity report; the rows are generated as
+/// `GenerateFromDatasetRequest` would, then compared with the sample rows.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FidelityRequest {
+    /// Defaults to 1000.
+    pub row_count: Option<usize>,
+    pub use_sample_data: Option<bool>,
+    pub seed: Option<u64>,
+}
+
+/// A saved project; `tables` and `foreign_keys` hold the JSON of its
+/// `ProjectSpec`.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Project {
+    pub id: i64,
+    pub name: String,
+    pub created_at: DateTime<chrono::Utc>,
+    pub tables: String,
+    pub foreign_keys: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectSummary {
+    pub id: i64,
+    pub name: String,
+    pub created_at: DateTime<chrono::Utc>,
+    pub table_count: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GenerateProjectRequest {
+    /// Rows of every table without its own `rows` or a `children` count.
+    pub row_count: Option<usize>,
+    pub use_sample_data: Option<bool>,
+    pub seed: Option<u64>,
+    /// Returns every table in one file instead of JSON: `json`, `sql` or
+    /// `markdown`.
+    pub format: Option<OutputFormat>,
+    pub null_as: Option<NullValue>,
+}
+
+/// A saved dictionary; `entries` holds the JSON of its `DictionaryEntry`s.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Dictionary {
+    pub id: i64,
+    pub name: String,
+    pub created_at: DateTime<chrono::Utc>,
+    pub entries: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DictionarySummary {
+    pub id: i64,
+    pub name: String,
+    pub created_at: DateTime<chrono::Utc>,
+    pub entry_count: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -50,6 +151,7 @@ pub struct DataSetSummary {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
@@ -83,6 +185,7 @@ mod tests {
         assert_eq!(request.data_type, "custom");
         assert!(request.column_types.is_none());
         assert!(request.sample_data.is_none());
+        assert!(request.columns.is_none());
     }
 
     #[test]
@@ -97,6 +200,16 @@ mod tests {
         let json = r#"{}"#;
         let request: GenerateFromDatasetRequest = serde_json::from_str(json).unwrap();
         assert_eq!(request.row_count, None);
+        assert_eq!(request.seed, None);
+        assert_eq!(request.save, None);
+    }
+
+    #[test]
+    fn test_generate_from_dataset_request_deserialization_seed() {
+        let json = r#"{"row_count": 10, "seed": 42, "save": true}"#;
+        let request: GenerateFromDatasetRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.seed, Some(42));
+        assert_eq!(request.save, Some(true));
     }
 
     #[test]
@@ -154,6 +267,8 @@ mod tests {
             data_type: "custom".to_string(),
             column_types: Some(column_types),
             sample_data: None,
+            columns: None,
+            locale: None,
         };
         assert!(request.column_types.is_some());
         assert_eq!(request.column_types.unwrap().len(), 2);
@@ -171,6 +286,8 @@ mod tests {
             data_type: "uploaded".to_string(),
             column_types: None,
             sample_data: Some(sample_data),
+            columns: None,
+            locale: None,
         };
         assert!(request.sample_data.is_some());
         assert_eq!(request.sample_data.unwrap().len(), 2);
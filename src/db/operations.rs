@@ -1,7 +1,14 @@
 use crate::csv_parser::CsvData;
-use crate::db::models::{DataSetSummary, Dataset, DatasetRow};
-use sqlx::SqlitePool;
+use crate::db::models::{
+    DataSetSummary, Dataset, DatasetRow, Dictionary, DictionarySummary, GenerationSource, Project,
+    ProjectSummary,
+};
+use crate::dictionary::DictionaryEntry;
+use crate::project::ProjectSpec;
+use crate::schema::{ColumnSpec, Schema};
+use crate::value::Table;
 use sqlx::error::BoxDynError;
+use sqlx::{Executor, Sqlite, SqlitePool, Transaction};
 
 pub async fn save_dataset(
     pool: &SqlitePool,
@@ -13,6 +20,118 @@ pub async fn save_dataset(
 ) -> Result<i64, BoxDynError> {
     let mut tx = pool.begin().await?;
 
+    let dataset_id = insert_dataset(
+        &mut tx,
+        name,
+        headers,
+        data_type,
+        column_types,
+        sample_data,
+        None,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(dataset_id)
+}
+
+/// Saves the output of a generation run as a 'generated' dataset, keeping the
+/// seed so the run can be reproduced.
+pub async fn save_generated_dataset(
+    pool: &SqlitePool,
+    name: &str,
+    table: &Table,
+    column_types: Option<&std::collections::HashMap<String, String>>,
+    seed: u64,
+) -> Result<i64, BoxDynError> {
+    let mut tx = pool.begin().await?;
+
+    let dataset_id = insert_dataset(
+        &mut tx,
+        name,
+        &table.headers,
+        "generated",
+        column_types,
+        Some(&table.text_rows()),
+        Some(seed as i64),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(dataset_id)
+}
+
+/// Saves an imported schema file as a 'custom' dataset. The full column specs
+/// are kept alongside the plain header and column type lists.
+pub async fn save_schema_dataset(pool: &SqlitePool, schema: &Schema) -> Result<i64, BoxDynError> {
+    let mut tx = pool.begin().await?;
+
+    let dataset_id = insert_dataset(
+        &mut tx,
+        &schema.name,
+        &schema.headers(),
+        "custom",
+        Some(&schema.column_types()),
+        None,
+        None,
+    )
+    .await?;
+    set_dataset_columns(&mut *tx, dataset_id, Some(&schema.columns)).await?;
+    set_dataset_locale(&mut *tx, dataset_id, schema.locale.as_deref()).await?;
+
+    tx.commit().await?;
+
+    Ok(dataset_id)
+}
+
+/// Stores (or with `None`, clears) the full column specs of a dataset.
+pub async fn set_dataset_columns<'e>(
+    executor: impl Executor<'e, Database = Sqlite>,
+    id: i64,
+    columns: Option<&[ColumnSpec]>,
+) -> Result<(), BoxDynError> {
+    let columns_json = match columns {
+        Some(columns) => Some(serde_json::to_string(columns)?),
+        None => None,
+    };
+
+    sqlx::query!(
+        "UPDATE datasets SET columns = ? WHERE id = ?",
+        columns_json,
+        id
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Stores (or with `None`, clears) the dataset-wide locale.
+pub async fn set_dataset_locale<'e>(
+    executor: impl Executor<'e, Database = Sqlite>,
+    id: i64,
+    locale: Option<&str>,
+) -> Result<(), BoxDynError> {
+    sqlx::query!("UPDATE datasets SET locale = ? WHERE id = ?", locale, id)
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
+
+/// Inserts a dataset and its sample rows inside `tx`, so callers can store
+/// column specs and the locale in the same transaction.
+pub async fn insert_dataset(
+    tx: &mut Transaction<'_, Sqlite>,
+    name: &str,
+    headers: &[String],
+    data_type: &str,
+    column_types: Option<&std::collections::HashMap<String, String>>,
+    sample_data: Option<&[Vec<String>]>,
+    seed: Option<i64>,
+) -> Result<i64, BoxDynError> {
     let headers_json = serde_json::to_string(headers)?;
     let column_types_json = match column_types {
         Some(types) => Some(serde_json::to_string(types)?),
@@ -27,17 +146,18 @@ pub async fn save_dataset(
     let has_sample_data = limited_sample_data.is_some();
 
     let result = sqlx::query!(
-        r#"INSERT INTO datasets (name, row_count, column_count, data_type, headers, column_types, has_sample_data)
-    VALUES (?, ?, ?, ?, ?, ?, ?)"#,
+        r#"INSERT INTO datasets (name, row_count, column_count, data_type, headers, column_types, has_sample_data, seed)
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
         name,
         row_count,
         column_count,
         data_type,
         headers_json,
         column_types_json,
-        has_sample_data
+        has_sample_data,
+        seed
     )
-    .execute(&mut *tx)
+    .execute(&mut **tx)
     .await?;
 
     let dataset_id = result.last_insert_rowid();
@@ -54,13 +174,11 @@ pub async fn save_dataset(
                 row_index,
                 row_json
             )
-            .execute(&mut *tx)
+            .execute(&mut **tx)
             .await?;
         }
     }
 
-    tx.commit().await?;
-
     Ok(dataset_id)
 }
 
@@ -70,7 +188,7 @@ pub async fn get_datasets(
 ) -> Result<Option<(Dataset, CsvData)>, BoxDynError> {
     let dataset = sqlx::query_as!(
         Dataset,
-        r#"SELECT id as "id!", name as "name!", created_at as "created_at!: _", row_count as "row_count!", column_count as "column_count!", data_type as "data_type!", headers as "headers!", column_types as "column_types?", has_sample_data as "has_sample_data!" FROM datasets WHERE id = ?"#,
+        r#"SELECT id as "id!", name as "name!", created_at as "created_at!: _", row_count as "row_count!", column_count as "column_count!", data_type as "data_type!", headers as "headers!", column_types as "column_types?", has_sample_data as "has_sample_data!", seed as "seed?", columns as "columns?", locale as "locale?" FROM datasets WHERE id = ?"#,
         id
     )
     .fetch_optional(pool)
@@ -103,6 +221,36 @@ pub async fn get_datasets(
     Ok(Some((dataset, csv_data)))
 }
 
+pub async fn get_generation_source(
+    pool: &SqlitePool,
+    id: i64,
+) -> Result<Option<GenerationSource>, BoxDynError> {
+    let (dataset, sample) = match get_datasets(pool, id).await? {
+        Some(data) => data,
+        None => return Ok(None),
+    };
+
+    let column_types = match dataset.column_types.as_deref() {
+        Some(types_json) => serde_json::from_str(types_json)?,
+        None => std::collections::HashMap::new(),
+    };
+    let columns = match dataset.columns.as_deref() {
+        Some(columns_json) => {
+            let mut columns: Vec<ColumnSpec> = serde_json::from_str(columns_json)?;
+            load_dictionaries(pool, &mut columns).await?;
+            Some(columns)
+        }
+        None => None,
+    };
+
+    Ok(Some(GenerationSource {
+        dataset,
+        column_types,
+        columns,
+        sample,
+    }))
+}
+
 pub async fn list_datasets(pool: &SqlitePool) -> Result<Vec<DataSetSummary>, BoxDynError> {
     let datasets = sqlx::query_as!(
         DataSetSummary,
@@ -122,8 +270,10 @@ pub async fn delete_dataset(pool: &SqlitePool, id: i64) -> Result<bool, BoxDynEr
     Ok(result.rows_affected() > 0)
 }
 
+/// Replaces a dataset's headers, types and sample rows inside `tx`; the
+/// caller commits. Returns `false` when there is no dataset with `id`.
 pub async fn update_dataset(
-    pool: &SqlitePool,
+    tx: &mut Transaction<'_, Sqlite>,
     id: i64,
     name: &str,
     headers: &[String],
@@ -132,15 +282,13 @@ pub async fn update_dataset(
     sample_data: Option<&[Vec<String>]>,
 ) -> Result<bool, BoxDynError> {
     let existing = sqlx::query!("SELECT id FROM datasets WHERE id = ?", id)
-        .fetch_optional(pool)
+        .fetch_optional(&mut **tx)
         .await?;
 
     if existing.is_none() {
         return Ok(false);
     }
 
-    let mut tx = pool.begin().await?;
-
     let headers_json = serde_json::to_string(headers)?;
     let column_types_json = match column_types {
         Some(types) => Some(serde_json::to_string(types)?),
@@ -154,10 +302,11 @@ pub async fn update_dataset(
     let column_count = headers.len() as i64;
     let has_sample_data = limited_sample_data.is_some();
 
+    // Edited headers and types replace any imported column specs.
     sqlx::query!(
         r#"UPDATE datasets
            SET name = ?, row_count = ?, column_count = ?, data_type = ?,
-               headers = ?, column_types = ?, has_sample_data = ?
+               headers = ?, column_types = ?, has_sample_data = ?, columns = NULL
            WHERE id = ?"#,
         name,
         row_count,
@@ -168,11 +317,11 @@ pub async fn update_dataset(
         has_sample_data,
         id
     )
-    .execute(&mut *tx)
+    .execute(&mut **tx)
     .await?;
 
     sqlx::query!("DELETE FROM dataset_rows WHERE dataset_id = ?", id)
-        .execute(&mut *tx)
+        .execute(&mut **tx)
         .await?;
 
     if let Some(rows) = limited_sample_data {
@@ -187,13 +336,11 @@ pub async fn update_dataset(
                 row_index,
                 row_json
             )
-            .execute(&mut *tx)
+            .execute(&mut **tx)
             .await?;
         }
     }
 
-    tx.commit().await?;
-
     Ok(true)
 }
 
@@ -235,13 +382,201 @@ pub async fn duplicate_dataset(
     )
     .await?;
 
+    sqlx::query!(
+        "UPDATE datasets SET columns = ?, locale = ?, seed = ? WHERE id = ?",
+        dataset.columns,
+        dataset.locale,
+        dataset.seed,
+        new_id
+    )
+    .execute(pool)
+    .await?;
+
     Ok(Some(new_id))
 }
 
+pub async fn save_project(pool: &SqlitePool, spec: &ProjectSpec) -> Result<i64, BoxDynError> {
+    let tables_json = serde_json::to_string(&spec.tables)?;
+    let foreign_keys_json = serde_json::to_string(&spec.foreign_keys)?;
+
+    let result = sqlx::query!(
+        "INSERT INTO projects (name, tables, foreign_keys) VALUES (?, ?, ?)",
+        spec.name,
+        tables_json,
+        foreign_keys_json
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn get_project(
+    pool: &SqlitePool,
+    id: i64,
+) -> Result<Option<(Project, ProjectSpec)>, BoxDynError> {
+    let project = sqlx::query_as!(
+        Project,
+        r#"SELECT id as "id!", name as "name!", created_at as "created_at!: _", tables as "tables!", foreign_keys as "foreign_keys!" FROM projects WHERE id = ?"#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let project = match project {
+        Some(p) => p,
+        None => return Ok(None),
+    };
+
+    let spec = ProjectSpec {
+        name: project.name.clone(),
+        tables: serde_json::from_str(&project.tables)?,
+        foreign_keys: serde_json::from_str(&project.foreign_keys)?,
+    };
+
+    Ok(Some((project, spec)))
+}
+
+pub async fn list_projects(pool: &SqlitePool) -> Result<Vec<ProjectSummary>, BoxDynError> {
+    let projects = sqlx::query_as!(
+        ProjectSummary,
+        r#"SELECT id as "id!", name as "name!", created_at as "created_at!: _", json_array_length(tables) as "table_count!: i64" FROM projects ORDER BY created_at DESC"#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(projects)
+}
+
+pub async fn delete_project(pool: &SqlitePool, id: i64) -> Result<bool, BoxDynError> {
+    let result = sqlx::query!("DELETE FROM projects WHERE id = ?", id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn save_dictionary(
+    pool: &SqlitePool,
+    name: &str,
+    entries: &[DictionaryEntry],
+) -> Result<i64, BoxDynError> {
+    let entries_json = serde_json::to_string(entries)?;
+
+    let result = sqlx::query!(
+        "INSERT INTO dictionaries (name, entries) VALUES (?, ?)",
+        name,
+        entries_json
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn get_dictionary(
+    pool: &SqlitePool,
+    id: i64,
+) -> Result<Option<(Dictionary, Vec<DictionaryEntry>)>, BoxDynError> {
+    let dictionary = sqlx::query_as!(
+        Dictionary,
+        r#"SELECT id as "id!", name as "name!", created_at as "created_at!: _", entries as "entries!" FROM dictionaries WHERE id = ?"#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let dictionary = match dictionary {
+        Some(d) => d,
+        None => return Ok(None),
+    };
+    let entries = serde_json::from_str(&dictionary.entries)?;
+
+    Ok(Some((dictionary, entries)))
+}
+
+/// The id of the dictionary called `name`, if there is one.
+pub async fn find_dictionary(pool: &SqlitePool, name: &str) -> Result<Option<i64>, BoxDynError> {
+    let id = sqlx::query_scalar!(
+        r#"SELECT id as "id!" FROM dictionaries WHERE name = ?"#,
+        name
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(id)
+}
+
+pub async fn list_dictionaries(pool: &SqlitePool) -> Result<Vec<DictionarySummary>, BoxDynError> {
+    let dictionaries = sqlx::query_as!(
+        DictionarySummary,
+        r#"SELECT id as "id!", name as "name!", created_at as "created_at!: _", json_array_length(entries) as "entry_count!: i64" FROM dictionaries ORDER BY name"#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(dictionaries)
+}
+
+pub async fn update_dictionary(
+    pool: &SqlitePool,
+    id: i64,
+    name: &str,
+    entries: &[DictionaryEntry],
+) -> Result<bool, BoxDynError> {
+    let entries_json = serde_json::to_string(entries)?;
+
+    let result = sqlx::query!(
+        "UPDATE dictionaries SET name = ?, entries = ? WHERE id = ?",
+        name,
+        entries_json,
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn delete_dictionary(pool: &SqlitePool, id: i64) -> Result<bool, BoxDynError> {
+    let result = sqlx::query!("DELETE FROM dictionaries WHERE id = ?", id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Loads the entries of every dictionary the columns name, so they can be
+/// validated and generated. Columns naming a missing dictionary are left
+/// without entries.
+pub async fn load_dictionaries(
+    pool: &SqlitePool,
+    columns: &mut [ColumnSpec],
+) -> Result<(), BoxDynError> {
+    for column in columns.iter_mut() {
+        let Some(name) = &column.dictionary else {
+            continue;
+        };
+        let entries_json = sqlx::query_scalar!(
+            r#"SELECT entries as "entries!" FROM dictionaries WHERE name = ?"#,
+            name
+        )
+        .fetch_optional(pool)
+        .await?;
+        column.dictionary_entries = match entries_json {
+            Some(json) => Some(serde_json::from_str::<Vec<DictionaryEntry>>(&json)?.into()),
+            None => None,
+        };
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::db::test_utils;
+    use crate::value::Value;
     use std::collections::HashMap;
 
     #[tokio::test]
@@ -334,6 +669,41 @@ mod tests {
         assert_eq!(csv_data.rows.len(), 100);
     }
 
+    #[tokio::test]
+    async fn test_save_generated_dataset_stores_seed() {
+        let pool = test_utils::setup_test_db().await;
+        let table = Table {
+            headers: vec!["id".to_string(), "note".to_string()],
+            rows: vec![
+                vec![Value::Int(1), Value::from("a")],
+                vec![Value::Int(2), Value::Null],
+            ],
+        };
+
+        let dataset_id = save_generated_dataset(&pool, "run", &table, None, 42)
+            .await
+            .unwrap();
+
+        let (dataset, saved) = get_datasets(&pool, dataset_id).await.unwrap().unwrap();
+        assert_eq!(dataset.data_type, "generated");
+        assert_eq!(dataset.seed, Some(42));
+        assert!(dataset.has_sample_data);
+        assert_eq!(saved.rows, vec![vec!["1", "a"], vec!["2", ""]]);
+    }
+
+    #[tokio::test]
+    async fn test_save_dataset_has_no_seed() {
+        let pool = test_utils::setup_test_db().await;
+        let headers = vec!["id".to_string()];
+
+        let dataset_id = save_dataset(&pool, "plain", &headers, "custom", None, None)
+            .await
+            .unwrap();
+
+        let (dataset, _) = get_datasets(&pool, dataset_id).await.unwrap().unwrap();
+        assert_eq!(dataset.seed, None);
+    }
+
     #[tokio::test]
     async fn test_get_datasets_not_found() {
         let pool = test_utils::setup_test_db().await;
@@ -429,8 +799,9 @@ mod tests {
             .unwrap();
 
         let new_headers = vec!["col1".to_string(), "col2".to_string()];
+        let mut tx = pool.begin().await.unwrap();
         let updated = update_dataset(
-            &pool,
+            &mut tx,
             dataset_id,
             "updated",
             &new_headers,
@@ -440,6 +811,7 @@ mod tests {
         )
         .await
         .unwrap();
+        tx.commit().await.unwrap();
 
         assert!(updated);
 
@@ -450,12 +822,141 @@ mod tests {
         assert_eq!(csv_data.headers.len(), 2);
     }
 
+    fn test_schema() -> Schema {
+        let mut status = ColumnSpec::new("status", "enum");
+        status.values = Some(vec!["open".to_string(), "closed".to_string()]);
+        Schema {
+            version: 1,
+            name: "tickets".to_string(),
+            description: None,
+            locale: Some("de_DE".to_string()),
+            columns: vec![ColumnSpec::new("id", "id"), status],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_schema_dataset() {
+        let pool = test_utils::setup_test_db().await;
+        let schema = test_schema();
+
+        let id = save_schema_dataset(&pool, &schema).await.unwrap();
+
+        let source = get_generation_source(&pool, id).await.unwrap().unwrap();
+        assert_eq!(source.dataset.name, "tickets");
+        assert_eq!(source.sample.headers, vec!["id", "status"]);
+        assert_eq!(source.column_types["status"], "enum");
+        assert_eq!(source.dataset.locale.as_deref(), Some("de_DE"));
+        assert_eq!(source.columns, Some(schema.columns));
+    }
+
+    #[tokio::test]
+    async fn test_update_dataset_clears_columns() {
+        let pool = test_utils::setup_test_db().await;
+        let id = save_schema_dataset(&pool, &test_schema()).await.unwrap();
+
+        let headers = vec!["id".to_string()];
+        let mut tx = pool.begin().await.unwrap();
+        update_dataset(&mut tx, id, "tickets", &headers, "custom", None, None)
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+
+        let source = get_generation_source(&pool, id).await.unwrap().unwrap();
+        assert!(source.columns.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_dataset_keeps_columns() {
+        let pool = test_utils::setup_test_db().await;
+        let schema = test_schema();
+        let id = save_schema_dataset(&pool, &schema).await.unwrap();
+
+        let copy_id = duplicate_dataset(&pool, id, None).await.unwrap().unwrap();
+
+        let source = get_generation_source(&pool, copy_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(source.columns, Some(schema.columns));
+        assert_eq!(source.dataset.locale, schema.locale);
+    }
+
+    fn entry(value: &str, weight: Option<f64>) -> DictionaryEntry {
+        DictionaryEntry {
+            value: value.to_string(),
+            weight,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dictionary_crud() {
+        let pool = test_utils::setup_test_db().await;
+        let entries = vec![entry("ENG", Some(3.0)), entry("OPS", Some(1.0))];
+
+        let id = save_dictionary(&pool, "departments", &entries)
+            .await
+            .unwrap();
+        assert!(
+            save_dictionary(&pool, "departments", &entries)
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            find_dictionary(&pool, "departments").await.unwrap(),
+            Some(id)
+        );
+
+        let summaries = list_dictionaries(&pool).await.unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].entry_count, 2);
+
+        let renamed = vec![entry("Books", None)];
+        assert!(
+            update_dictionary(&pool, id, "categories", &renamed)
+                .await
+                .unwrap()
+        );
+        let (dictionary, stored) = get_dictionary(&pool, id).await.unwrap().unwrap();
+        assert_eq!(dictionary.name, "categories");
+        assert_eq!(stored, renamed);
+
+        assert!(delete_dictionary(&pool, id).await.unwrap());
+        assert!(get_dictionary(&pool, id).await.unwrap().is_none());
+        assert!(!delete_dictionary(&pool, id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_generation_source_loads_dictionaries() {
+        let pool = test_utils::setup_test_db().await;
+        save_dictionary(&pool, "departments", &[entry("ENG", None)])
+            .await
+            .unwrap();
+        let mut department = ColumnSpec::new("department", "dictionary");
+        department.dictionary = Some("departments".to_string());
+        let mut missing = ColumnSpec::new("team", "dictionary");
+        missing.dictionary = Some("teams".to_string());
+        let schema = Schema {
+            columns: vec![department, missing],
+            ..test_schema()
+        };
+        let id = save_schema_dataset(&pool, &schema).await.unwrap();
+
+        let source = get_generation_source(&pool, id).await.unwrap().unwrap();
+        let columns = source.columns.unwrap();
+        assert_eq!(
+            columns[0].dictionary_entries.as_deref(),
+            Some(&[entry("ENG", None)][..])
+        );
+        assert!(columns[1].dictionary_entries.is_none());
+    }
+
     #[tokio::test]
     async fn test_update_dataset_not_found() {
         let pool = test_utils::setup_test_db().await;
         let headers = vec!["col1".to_string()];
 
-        let updated = update_dataset(&pool, 999, "name", &headers, "custom", None, None)
+        let mut tx = pool.begin().await.unwrap();
+        let updated = update_dataset(&mut tx, 999, "name", &headers, "custom", None, None)
             .await
             .unwrap();
 
@@ -485,8 +986,9 @@ mod tests {
             vec!["30".to_string()],
         ];
 
+        let mut tx = pool.begin().await.unwrap();
         update_dataset(
-            &pool,
+            &mut tx,
             dataset_id,
             "updated",
             &headers,
@@ -496,6 +998,7 @@ mod tests {
         )
         .await
         .unwrap();
+        tx.commit().await.unwrap();
 
         let result = get_datasets(&pool, dataset_id).await.unwrap();
         let (dataset, csv_data) = result.unwrap();
@@ -535,6 +1038,27 @@ mod tests {
         assert_eq!(csv_data.rows, sample_data);
     }
 
+    #[tokio::test]
+    async fn test_duplicate_dataset_keeps_seed() {
+        let pool = test_utils::setup_test_db().await;
+        let table = Table {
+            headers: vec!["id".to_string()],
+            rows: vec![vec![Value::Int(1)]],
+        };
+        let original_id = save_generated_dataset(&pool, "run", &table, None, 42)
+            .await
+            .unwrap();
+
+        let duplicate_id = duplicate_dataset(&pool, original_id, None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let (dataset, _) = get_datasets(&pool, duplicate_id).await.unwrap().unwrap();
+        assert_eq!(dataset.data_type, "generated");
+        assert_eq!(dataset.seed, Some(42));
+    }
+
     #[tokio::test]
     async fn test_duplicate_dataset_custom_name() {
         let pool = test_utils::setup_test_db().await;
@@ -13,7 +13,10 @@ pub async fn setup_test_db() -> SqlitePool {
                 data_type TEXT NOT NULL,
                 headers TEXT NOT NULL,
                 column_types TEXT,
-                has_sample_data BOOLEAN NOT NULL
+                has_sample_data BOOLEAN NOT NULL,
+                seed INTEGER,
+                columns TEXT,
+                locale TEXT
             )"#,
     )
     .execute(&pool)
@@ -33,5 +36,30 @@ pub async fn setup_test_db() -> SqlitePool {
     .await
     .unwrap();
 
+    sqlx::query(
+        r#"CREATE TABLE projects (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                tables TEXT NOT NULL,
+                foreign_keys TEXT NOT NULL
+            )"#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        r#"CREATE TABLE dictionaries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                entries TEXT NOT NULL
+            )"#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
     pool
 }
@@ -0,0 +1,269 @@
+use crate::csv_parser::parse_csv_from_bytes;
+use serde::{Deserialize, Serialize};
+
+/// Most entries one dictionary may hold.
+pub const MAX_ENTRIES: usize = 100_000;
+
+/// A named list of values from the user's own domain, picked from by
+/// `dictionary` columns:
+///
+/// ```json
+/// {
+///   "name": "departments",
+///   "values": ["ENG", "OPS", "FIN"],
+///   "weights": [6, 3, 1]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DictionarySpec {
+    pub name: String,
+    pub values: Vec<String>,
+    /// Relative frequency of each value; values are equally likely without.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<Vec<f64>>,
+}
+
+/// One value of a dictionary, as stored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DictionaryEntry {
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+}
+
+impl DictionarySpec {
+    /// The spec's entries once its name, values and weights are valid.
+    pub fn entries(&self) -> Result<Vec<DictionaryEntry>, String> {
+        check_name(&self.name)?;
+        if let Some(weights) = &self.weights
+            && weights.len() != self.values.len()
+        {
+            return Err(format!(
+                "'weights' has {} numbers but there are {} values",
+                weights.len(),
+                self.values.len()
+            ));
+        }
+        if let Some(index) = self.values.iter().position(|v| v.is_empty()) {
+            return Err(format!("values[{}] is empty", index));
+        }
+        let entries: Vec<DictionaryEntry> = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| DictionaryEntry {
+                value: value.clone(),
+                weight: self.weights.as_ref().map(|weights| weights[i]),
+            })
+            .collect();
+        check_entries(&entries)?;
+        Ok(entries)
+    }
+}
+
+pub fn check_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        Err("Dictionary name must not be empty".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks the entry count and that weights are non-negative numbers with at
+/// least one above zero.
+pub fn check_entries(entries: &[DictionaryEntry]) -> Result<(), String> {
+    if entries.is_empty() {
+        return Err("A dictionary needs at least one value".to_string());
+    }
+    if entries.len() > MAX_ENTRIES {
+        return Err(format!(
+            "A dictionary holds at most {} values, got {}",
+            MAX_ENTRIES,
+            entries.len()
+        ));
+    }
+    let weights: Vec<f64> = entries.iter().filter_map(|e| e.weight).collect();
+    if weights.is_empty() {
+        return Ok(());
+    }
+    for entry in entries {
+        match entry.weight {
+            None => {
+                return Err(format!(
+                    "'{}' has no weight; give every value a weight or none",
+                    entry.value
+                ));
+            }
+            Some(weight) if !weight.is_finite() || weight < 0.0 => {
+                return Err(format!(
+                    "weight of '{}' must be a number of at least 0, got {}",
+                    entry.value, weight
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+    if weights.iter().all(|w| *w == 0.0) {
+        return Err("at least one weight must be above 0".to_string());
+    }
+    Ok(())
+}
+
+/// Reads the entries of an uploaded file. With a `column`, the file is a CSV
+/// and the entries are that column's non-empty cells, weighted by
+/// `weight_column` when given. Without, every non-blank line is a value.
+pub fn entries_from_upload(
+    bytes: &[u8],
+    column: Option<&str>,
+    weight_column: Option<&str>,
+) -> Result<Vec<DictionaryEntry>, String> {
+    let Some(column) = column else {
+        if weight_column.is_some() {
+            return Err("'weight_column' needs a 'column' to weigh".to_string());
+        }
+        let text = std::str::from_utf8(bytes)
+            .map_err(|_| "Dictionary file must be UTF-8 text".to_string())?;
+        let entries = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| DictionaryEntry {
+                value: line.to_string(),
+                weight: None,
+            })
+            .collect();
+        return Ok(entries);
+    };
+
+    let csv = parse_csv_from_bytes(bytes).map_err(|e| format!("Failed to parse CSV: {}", e))?;
+    let position = |name: &str| {
+        csv.headers
+            .iter()
+            .position(|header| header == name)
+            .ok_or_else(|| format!("the CSV has no column named '{}'", name))
+    };
+    let values = position(column)?;
+    let weights = weight_column.map(position).transpose()?;
+
+    let mut entries = Vec::new();
+    for (index, row) in csv.rows.iter().enumerate() {
+        let value = row.get(values).map(|v| v.trim()).unwrap_or("");
+        if value.is_empty() {
+            continue;
+        }
+        let weight = match weights {
+            Some(weights) => {
+                let cell = row.get(weights).map(|w| w.trim()).unwrap_or("");
+                let weight = cell
+                    .parse::<f64>()
+                    .map_err(|_| format!("row {}: weight '{}' is not a number", index + 1, cell))?;
+                Some(weight)
+            }
+            None => None,
+        };
+        entries.push(DictionaryEntry {
+            value: value.to_string(),
+            weight,
+        });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(values: &[&str], weights: Option<Vec<f64>>) -> DictionarySpec {
+        DictionarySpec {
+            name: "departments".to_string(),
+            values: values.iter().map(|v| v.to_string()).collect(),
+            weights,
+        }
+    }
+
+    #[test]
+    fn test_spec_entries() {
+        let entries = spec(&["ENG", "OPS"], Some(vec![3.0, 1.0]))
+            .entries()
+            .unwrap();
+        assert_eq!(entries[1].value, "OPS");
+        assert_eq!(entries[1].weight, Some(1.0));
+
+        let error = |spec: DictionarySpec| spec.entries().unwrap_err();
+        assert_eq!(
+            error(spec(&["ENG"], Some(vec![1.0, 2.0]))),
+            "'weights' has 2 numbers but there are 1 values"
+        );
+        assert_eq!(
+            error(spec(&[], None)),
+            "A dictionary needs at least one value"
+        );
+        assert_eq!(error(spec(&["ENG", ""], None)), "values[1] is empty");
+        assert_eq!(
+            error(spec(&["ENG", "OPS"], Some(vec![1.0, -2.0]))),
+            "weight of 'OPS' must be a number of at least 0, got -2"
+        );
+        assert_eq!(
+            error(spec(&["ENG"], Some(vec![0.0]))),
+            "at least one weight must be above 0"
+        );
+        let mixed = [
+            DictionaryEntry {
+                value: "ENG".to_string(),
+                weight: Some(3.0),
+            },
+            DictionaryEntry {
+                value: "OPS".to_string(),
+                weight: None,
+            },
+        ];
+        assert_eq!(
+            check_entries(&mixed).unwrap_err(),
+            "'OPS' has no weight; give every value a weight or none"
+        );
+        let mut unnamed = spec(&["ENG"], None);
+        unnamed.name = " ".to_string();
+        assert_eq!(error(unnamed), "Dictionary name must not be empty");
+    }
+
+    #[test]
+    fn test_entries_from_word_list() {
+        let entries = entries_from_upload(b"Books\n\n  Garden \r\nToys\n", None, None).unwrap();
+        let values: Vec<&str> = entries.iter().map(|e| e.value.as_str()).collect();
+        assert_eq!(values, vec!["Books", "Garden", "Toys"]);
+        assert!(entries.iter().all(|e| e.weight.is_none()));
+    }
+
+    #[test]
+    fn test_entries_from_csv_column() {
+        let csv = b"code,name,share\nENG,Engineering,6\n,Unknown,1\nOPS,Operations,3\n";
+        let entries = entries_from_upload(csv, Some("code"), Some("share")).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                DictionaryEntry {
+                    value: "ENG".to_string(),
+                    weight: Some(6.0)
+                },
+                DictionaryEntry {
+                    value: "OPS".to_string(),
+                    weight: Some(3.0)
+                },
+            ]
+        );
+
+        assert_eq!(
+            entries_from_upload(csv, Some("team"), None).unwrap_err(),
+            "the CSV has no column named 'team'"
+        );
+        assert_eq!(
+            entries_from_upload(csv, Some("code"), Some("name")).unwrap_err(),
+            "row 1: weight 'Engineering' is not a number"
+        );
+        assert_eq!(
+            entries_from_upload(csv, None, Some("share")).unwrap_err(),
+            "'weight_column' needs a 'column' to weigh"
+        );
+    }
+}
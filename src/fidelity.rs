@@ -0,0 +1,347 @@
+//! How closely generated rows match a dataset's sample rows, column by
+//! column: the distance between their value distributions, and how far their
+//! null rates and cardinalities drift.
+use crate::csv_parser::CsvData;
+use crate::generators::{InferredKind, infer_columns};
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// How a column's sample and generated values are compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Measure {
+    /// Kolmogorov-Smirnov statistic of numbers, or of dates and timestamps
+    /// as points in time.
+    Ks,
+    /// Kolmogorov-Smirnov statistic of the values' character counts, for
+    /// free text whose values rarely repeat.
+    KsLength,
+    /// Total variation distance between the frequencies of each value, for
+    /// booleans and enums.
+    Tvd,
+    /// The sample holds no values, only blanks.
+    NullsOnly,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FidelityReport {
+    pub sample_rows: usize,
+    pub generated_rows: usize,
+    /// Mean of the column scores, from 0 to 1.
+    pub score: f64,
+    pub columns: Vec<ColumnFidelity>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColumnFidelity {
+    pub name: String,
+    pub measure: Measure,
+    /// Distance between the two distributions under `measure`, from 0 for
+    /// identical to 1 for disjoint.
+    pub statistic: f64,
+    pub sample_null_rate: f64,
+    pub generated_null_rate: f64,
+    /// Generated null rate minus the sample's.
+    pub null_rate_delta: f64,
+    /// Distinct non-blank values on each side.
+    pub sample_distinct: usize,
+    pub generated_distinct: usize,
+    /// Generated share of distinct values among non-blank ones minus the
+    /// sample's, so row counts of the two sides may differ.
+    pub cardinality_delta: f64,
+    /// `1 - statistic`, scaled down by the null rate drift; 1 is a perfect
+    /// match.
+    pub score: f64,
+}
+
+/// Compares each column of `generated`, in the order of the sample headers,
+/// with the same column of `sample`. Values are trimmed and blanks count as
+/// nulls, as they do when learning from a sample.
+pub fn compare(sample: &CsvData, generated: &[Vec<String>]) -> FidelityReport {
+    let inferred = infer_columns(&sample.headers, &sample.rows);
+    let columns: Vec<ColumnFidelity> = inferred
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            let original = cells(&sample.rows, index);
+            let synthetic = cells(generated, index);
+            let (measure, statistic) = distance(
+                column.kind,
+                column.column.format.as_deref(),
+                &present(&original),
+                &present(&synthetic),
+            );
+            compare_column(&column.name, measure, statistic, &original, &synthetic)
+        })
+        .collect();
+
+    let score = if columns.is_empty() {
+        1.0
+    } else {
+        columns.iter().map(|c| c.score).sum::<f64>() / columns.len() as f64
+    };
+
+    FidelityReport {
+        sample_rows: sample.rows.len(),
+        generated_rows: generated.len(),
+        score,
+        columns,
+    }
+}
+
+fn compare_column(
+    name: &str,
+    measure: Measure,
+    statistic: f64,
+    original: &[&str],
+    synthetic: &[&str],
+) -> ColumnFidelity {
+    let sample_null_rate = null_rate(original);
+    let generated_null_rate = null_rate(synthetic);
+    let null_rate_delta = generated_null_rate - sample_null_rate;
+    let sample_distinct = distinct(original);
+    let generated_distinct = distinct(synthetic);
+
+    ColumnFidelity {
+        name: name.to_string(),
+        measure,
+        statistic,
+        sample_null_rate,
+        generated_null_rate,
+        null_rate_delta,
+        sample_distinct,
+        generated_distinct,
+        cardinality_delta: distinct_share(synthetic, generated_distinct)
+            - distinct_share(original, sample_distinct),
+        score: (1.0 - statistic) * (1.0 - null_rate_delta.abs()),
+    }
+}
+
+/// The measure fitting a column of `kind`, and the distance under it.
+fn distance(
+    kind: InferredKind,
+    format: Option<&str>,
+    original: &[&str],
+    synthetic: &[&str],
+) -> (Measure, f64) {
+    match kind {
+        InferredKind::Empty => (Measure::NullsOnly, 0.0),
+        InferredKind::Integer | InferredKind::Decimal => {
+            let parse = |v: &str| v.parse::<f64>().ok().filter(|n| n.is_finite());
+            (Measure::Ks, ks_statistic(original, synthetic, parse))
+        }
+        InferredKind::Date => {
+            let format = format.unwrap_or("%Y-%m-%d");
+            let parse = |v: &str| {
+                NaiveDate::parse_from_str(v, format)
+                    .ok()
+                    .map(|d| d.num_days_from_ce() as f64)
+            };
+            (Measure::Ks, ks_statistic(original, synthetic, parse))
+        }
+        InferredKind::Timestamp => {
+            let format = format.unwrap_or("%Y-%m-%dT%H:%M:%S");
+            let parse = |v: &str| {
+                NaiveDateTime::parse_from_str(v, format)
+                    .ok()
+                    .map(|t| t.and_utc().timestamp() as f64)
+            };
+            (Measure::Ks, ks_statistic(original, synthetic, parse))
+        }
+        InferredKind::Boolean | InferredKind::Enum => {
+            (Measure::Tvd, total_variation(original, synthetic))
+        }
+        InferredKind::Uuid | InferredKind::Email | InferredKind::Text => {
+            let length = |v: &str| Some(v.chars().count() as f64);
+            (Measure::KsLength, ks_statistic(original, synthetic, length))
+        }
+    }
+}
+
+/// The largest gap between the empirical distribution functions of the
+/// values `parse` reads on each side. Either side reading nothing counts as
+/// disjoint, unless neither does.
+fn ks_statistic(original: &[&str], synthetic: &[&str], parse: impl Fn(&str) -> Option<f64>) -> f64 {
+    let sorted = |values: &[&str]| {
+        let mut numbers: Vec<f64> = values.iter().filter_map(|v| parse(v)).collect();
+        numbers.sort_by(f64::total_cmp);
+        numbers
+    };
+    let (a, b) = (sorted(original), sorted(synthetic));
+    if a.is_empty() || b.is_empty() {
+        return if a.is_empty() && b.is_empty() {
+            0.0
+        } else {
+            1.0
+        };
+    }
+
+    let (mut i, mut j, mut statistic) = (0, 0, 0.0_f64);
+    while i < a.len() && j < b.len() {
+        let x = a[i].min(b[j]);
+        while i < a.len() && a[i] <= x {
+            i += 1;
+        }
+        while j < b.len() && b[j] <= x {
+            j += 1;
+        }
+        let gap = (i as f64 / a.len() as f64 - j as f64 / b.len() as f64).abs();
+        statistic = statistic.max(gap);
+    }
+    statistic
+}
+
+/// Half the summed difference between each value's share on either side.
+fn total_variation(original: &[&str], synthetic: &[&str]) -> f64 {
+    if original.is_empty() || synthetic.is_empty() {
+        return if original.is_empty() && synthetic.is_empty() {
+            0.0
+        } else {
+            1.0
+        };
+    }
+    let (p, q) = (frequencies(original), frequencies(synthetic));
+    let values: HashSet<&str> = p.keys().chain(q.keys()).copied().collect();
+    values
+        .into_iter()
+        .map(|v| (p.get(v).unwrap_or(&0.0) - q.get(v).unwrap_or(&0.0)).abs())
+        .sum::<f64>()
+        / 2.0
+}
+
+fn frequencies<'a>(values: &[&'a str]) -> HashMap<&'a str, f64> {
+    let mut shares = HashMap::new();
+    for value in values {
+        *shares.entry(*value).or_insert(0.0) += 1.0 / values.len() as f64;
+    }
+    shares
+}
+
+/// The trimmed cells of a column; missing cells of short rows are blank.
+fn cells(rows: &[Vec<String>], index: usize) -> Vec<&str> {
+    rows.iter()
+        .map(|row| row.get(index).map_or("", |value| value.trim()))
+        .collect()
+}
+
+fn present<'a>(cells: &[&'a str]) -> Vec<&'a str> {
+    cells.iter().copied().filter(|v| !v.is_empty()).collect()
+}
+
+fn null_rate(cells: &[&str]) -> f64 {
+    if cells.is_empty() {
+        return 0.0;
+    }
+    cells.iter().filter(|v| v.is_empty()).count() as f64 / cells.len() as f64
+}
+
+fn distinct(cells: &[&str]) -> usize {
+    present(cells).into_iter().collect::<HashSet<_>>().len()
+}
+
+fn distinct_share(cells: &[&str], distinct: usize) -> f64 {
+    match present(cells).len() {
+        0 => 0.0,
+        count => distinct as f64 / count as f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(values: &[&[&str]]) -> Vec<Vec<String>> {
+        values
+            .iter()
+            .map(|row| row.iter().map(|v| v.to_string()).collect())
+            .collect()
+    }
+
+    fn sample(headers: &[&str], values: &[&[&str]]) -> CsvData {
+        CsvData {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: rows(values),
+        }
+    }
+
+    #[test]
+    fn test_ks_statistic() {
+        let number = |v: &str| v.parse::<f64>().ok();
+        assert_eq!(
+            ks_statistic(&["1", "2", "3"], &["3", "1", "2"], number),
+            0.0
+        );
+        assert_eq!(ks_statistic(&["1", "2"], &["5", "6"], number), 1.0);
+        assert_eq!(
+            ks_statistic(&["1", "2", "3", "4"], &["3", "4"], number),
+            0.5
+        );
+        assert_eq!(
+            ks_statistic(&["1", "1", "2"], &["1", "2", "2"], number),
+            1.0 / 3.0
+        );
+        assert_eq!(ks_statistic(&["1"], &[], number), 1.0);
+    }
+
+    #[test]
+    fn test_total_variation() {
+        assert_eq!(total_variation(&["a", "b"], &["b", "a"]), 0.0);
+        assert_eq!(total_variation(&["a", "a"], &["b"]), 1.0);
+        assert!((total_variation(&["a", "a", "a", "b"], &["a", "b"]) - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_compare_identical_rows_scores_one() {
+        let data = sample(
+            &["age", "plan", "joined"],
+            &[
+                &["30", "free", "2024-01-01"],
+                &["41", "pro", "2024-02-01"],
+                &["25", "free", ""],
+                &["30", "free", "2024-03-01"],
+            ],
+        );
+        let report = compare(&data, &data.rows);
+
+        assert_eq!(report.score, 1.0);
+        let measures: Vec<Measure> = report.columns.iter().map(|c| c.measure).collect();
+        assert_eq!(measures, vec![Measure::Ks, Measure::Tvd, Measure::Ks]);
+        assert_eq!(report.columns[2].sample_null_rate, 0.25);
+        assert_eq!(report.columns[2].null_rate_delta, 0.0);
+    }
+
+    #[test]
+    fn test_compare_reports_drift() {
+        let data = sample(
+            &["age", "note", "blank"],
+            &[&["20", "abc", ""], &["30", "defg", ""]],
+        );
+        let generated = rows(&[
+            &["60", "xyz", ""],
+            &["70", "", ""],
+            &["70", "hijk", ""],
+            &["80", "lmn", ""],
+        ]);
+        let report = compare(&data, &generated);
+
+        let age = &report.columns[0];
+        assert_eq!(age.statistic, 1.0);
+        assert_eq!((age.sample_distinct, age.generated_distinct), (2, 3));
+        assert_eq!(age.cardinality_delta, -0.25);
+        assert_eq!(age.score, 0.0);
+
+        let note = &report.columns[1];
+        assert_eq!(note.measure, Measure::KsLength);
+        assert!((note.statistic - 1.0 / 6.0).abs() < 1e-12);
+        assert_eq!(note.null_rate_delta, 0.25);
+        assert!((note.score - 5.0 / 6.0 * 0.75).abs() < 1e-12);
+
+        let blank = &report.columns[2];
+        assert_eq!(blank.measure, Measure::NullsOnly);
+        assert_eq!(blank.score, 1.0);
+
+        assert_eq!((report.sample_rows, report.generated_rows), (2, 4));
+        assert!((report.score - (note.score + 1.0) / 3.0).abs() < 1e-12);
+    }
+}
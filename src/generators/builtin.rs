@@ -0,0 +1,1878 @@
+use super::GeneratorError;
+use super::catalog;
+use super::expression::{Expr, Number, Value, check_date_format};
+use super::ids::Strategy;
+use super::locale::{self, Locale};
+use super::params;
+use super::pattern::Pattern;
+use super::registry::{ColumnGenerator, ColumnType, ParamKind, ParamSpec, Registry, RowValues};
+use crate::schema::ColumnSpec;
+use crate::value::ValueKind;
+use rand::distr::Distribution;
+use rand::distr::weighted::WeightedIndex;
+use rand::{Rng, RngCore};
+
+/// Registers the built-in types. "auto" columns are detected in this order,
+/// so a header like "paid_amount" is an id before it is money.
+pub(super) fn register_all(registry: &mut Registry) {
+    registry.register(catalog::UUID);
+    registry.register(catalog::Boolean);
+    registry.register(Id);
+    registry.register(catalog::URL);
+    registry.register(catalog::USER_AGENT);
+    registry.register(catalog::Percentage);
+    registry.register(catalog::DOMAIN);
+    registry.register(catalog::USERNAME);
+    registry.register(catalog::COMPANY);
+    registry.register(Name);
+    registry.register(Email);
+    registry.register(catalog::PASSWORD_HASH);
+    registry.register(Age);
+    registry.register(City);
+    registry.register(catalog::State);
+    registry.register(catalog::IPV6);
+    registry.register(catalog::IPV4);
+    registry.register(catalog::MAC_ADDRESS);
+    registry.register(Street);
+    registry.register(Postcode);
+    registry.register(Country);
+    registry.register(catalog::CURRENCY_CODE);
+    registry.register(Phone);
+    registry.register(catalog::Timestamp);
+    registry.register(catalog::Time);
+    registry.register(Date);
+    registry.register(catalog::LATITUDE);
+    registry.register(catalog::LONGITUDE);
+    registry.register(Money);
+    registry.register(Integer);
+    registry.register(Decimal);
+    registry.register(catalog::IBAN);
+    registry.register(catalog::CREDIT_CARD);
+    registry.register(catalog::ISBN);
+    registry.register(catalog::JOB_TITLE);
+    registry.register(catalog::Color);
+    registry.register(Text);
+    registry.register(Enum);
+    registry.register(Dictionary);
+    registry.register(Regex);
+    registry.register(Computed);
+}
+
+const LOCALE: ParamSpec = ParamSpec {
+    name: "locale",
+    kind: ParamKind::String,
+    required: false,
+    description: "Locale pack to draw from, e.g. \"de_DE\" (see /api/locales)",
+};
+
+const LOCALE_PARAMS: &[ParamSpec] = &[LOCALE];
+
+const ID_PARAMS: &[ParamSpec] = &[
+    ParamSpec {
+        name: "strategy",
+        kind: ParamKind::String,
+        required: false,
+        description: "sequence (default), random, uuid_v4, uuid_v7, ulid or snowflake",
+    },
+    ParamSpec {
+        name: "start",
+        kind: ParamKind::Integer,
+        required: false,
+        description: "First id of a sequence (default 1)",
+    },
+    ParamSpec {
+        name: "step",
+        kind: ParamKind::Integer,
+        required: false,
+        description: "Increment of a sequence (default 1)",
+    },
+    ParamSpec {
+        name: "min",
+        kind: ParamKind::Integer,
+        required: false,
+        description: "Smallest random id (default 1)",
+    },
+    ParamSpec {
+        name: "max",
+        kind: ParamKind::Integer,
+        required: false,
+        description: "Largest random id (default 2147483647)",
+    },
+    ParamSpec {
+        name: "prefix",
+        kind: ParamKind::String,
+        required: false,
+        description: "Text before each id, e.g. \"CUST-\"",
+    },
+    ParamSpec {
+        name: "width",
+        kind: ParamKind::Integer,
+        required: false,
+        description: "Digits a sequence or random id is zero-padded to",
+    },
+];
+
+const MIN_MAX_INTEGER: &[ParamSpec] = &[
+    ParamSpec {
+        name: "min",
+        kind: ParamKind::Integer,
+        required: false,
+        description: "Smallest value (default 18)",
+    },
+    ParamSpec {
+        name: "max",
+        kind: ParamKind::Integer,
+        required: false,
+        description: "Largest value (default 80)",
+    },
+    ParamSpec {
+        name: "min_column",
+        kind: ParamKind::Column,
+        required: false,
+        description: "Column whose value in the same row is the smallest allowed",
+    },
+    ParamSpec {
+        name: "max_column",
+        kind: ParamKind::Column,
+        required: false,
+        description: "Column whose value in the same row is the largest allowed",
+    },
+];
+
+const MONEY_PARAMS: &[ParamSpec] = &[
+    ParamSpec {
+        name: "min",
+        kind: ParamKind::Number,
+        required: false,
+        description: "Smallest amount (default 10)",
+    },
+    ParamSpec {
+        name: "max",
+        kind: ParamKind::Number,
+        required: false,
+        description: "Largest amount (default 10000)",
+    },
+    ParamSpec {
+        name: "precision",
+        kind: ParamKind::Integer,
+        required: false,
+        description: "Digits after the decimal point, 0 to 10 (default 2)",
+    },
+    ParamSpec {
+        name: "currency",
+        kind: ParamKind::String,
+        required: false,
+        description: "Symbol prefixed to every amount, e.g. \"$\"",
+    },
+    ParamSpec {
+        name: "min_column",
+        kind: ParamKind::Column,
+        required: false,
+        description: "Column whose amount in the same row is the smallest allowed",
+    },
+    ParamSpec {
+        name: "max_column",
+        kind: ParamKind::Column,
+        required: false,
+        description: "Column whose amount in the same row is the largest allowed",
+    },
+];
+
+const INTEGER_PARAMS: &[ParamSpec] = &[
+    ParamSpec {
+        name: "min",
+        kind: ParamKind::Integer,
+        required: false,
+        description: "Smallest value (default 0)",
+    },
+    ParamSpec {
+        name: "max",
+        kind: ParamKind::Integer,
+        required: false,
+        description: "Largest value (default 1000)",
+    },
+    ParamSpec {
+        name: "min_column",
+        kind: ParamKind::Column,
+        required: false,
+        description: "Column whose value in the same row is the smallest allowed",
+    },
+    ParamSpec {
+        name: "max_column",
+        kind: ParamKind::Column,
+        required: false,
+        description: "Column whose value in the same row is the largest allowed",
+    },
+];
+
+const DECIMAL_PARAMS: &[ParamSpec] = &[
+    ParamSpec {
+        name: "min",
+        kind: ParamKind::Number,
+        required: false,
+        description: "Smallest value (default 0)",
+    },
+    ParamSpec {
+        name: "max",
+        kind: ParamKind::Number,
+        required: false,
+        description: "Largest value (default 1000)",
+    },
+    ParamSpec {
+        name: "precision",
+        kind: ParamKind::Integer,
+        required: false,
+        description: "Digits after the decimal point, 0 to 10 (default 2)",
+    },
+    ParamSpec {
+        name: "min_column",
+        kind: ParamKind::Column,
+        required: false,
+        description: "Column whose value in the same row is the smallest allowed",
+    },
+    ParamSpec {
+        name: "max_column",
+        kind: ParamKind::Column,
+        required: false,
+        description: "Column whose value in the same row is the largest allowed",
+    },
+];
+
+const EMAIL_PARAMS: &[ParamSpec] = &[ParamSpec {
+    name: "from",
+    kind: ParamKind::Column,
+    required: false,
+    description: "Name column the address is built from, e.g. \"anna.smith@mail.com\"",
+}];
+
+const CITY_PARAMS: &[ParamSpec] = &[
+    ParamSpec {
+        name: "within",
+        kind: ParamKind::Column,
+        required: false,
+        description: "Country column whose cities are picked",
+    },
+    LOCALE,
+];
+
+const DICTIONARY_PARAMS: &[ParamSpec] = &[ParamSpec {
+    name: "dictionary",
+    kind: ParamKind::String,
+    required: true,
+    description: "Name of a saved dictionary (see /api/dictionaries)",
+}];
+
+const REGEX_PARAMS: &[ParamSpec] = &[ParamSpec {
+    name: "pattern",
+    kind: ParamKind::String,
+    required: true,
+    description: "Regular expression the values match, e.g. \"ORD-[0-9]{6}-[A-Z]{2}\"",
+}];
+
+const COMPUTED_PARAMS: &[ParamSpec] = &[
+    ParamSpec {
+        name: "expression",
+        kind: ParamKind::String,
+        required: true,
+        description: "The value in terms of other columns, e.g. \"price * quantity\"",
+    },
+    ParamSpec {
+        name: "precision",
+        kind: ParamKind::Integer,
+        required: false,
+        description: "Digits after the decimal point of numbers (default: the most of any operand)",
+    },
+    ParamSpec {
+        name: "currency",
+        kind: ParamKind::String,
+        required: false,
+        description: "Symbol prefixed to numbers, e.g. \"$\"",
+    },
+    ParamSpec {
+        name: "format",
+        kind: ParamKind::String,
+        required: false,
+        description: "strftime format of dates (default %Y-%m-%d)",
+    },
+];
+
+const DATE_PARAMS: &[ParamSpec] = &[
+    ParamSpec {
+        name: "min",
+        kind: ParamKind::Date,
+        required: false,
+        description: "Earliest date (default 2020-01-01)",
+    },
+    ParamSpec {
+        name: "max",
+        kind: ParamKind::Date,
+        required: false,
+        description: "Latest date (default 2025-12-31)",
+    },
+    ParamSpec {
+        name: "format",
+        kind: ParamKind::String,
+        required: false,
+        description: "strftime format of the values (default %Y-%m-%d)",
+    },
+    ParamSpec {
+        name: "min_column",
+        kind: ParamKind::Column,
+        required: false,
+        description: "Date column of the same row this date is never before",
+    },
+    ParamSpec {
+        name: "max_column",
+        kind: ParamKind::Column,
+        required: false,
+        description: "Date column of the same row this date is never after",
+    },
+    LOCALE,
+];
+
+const PHONE_PARAMS: &[ParamSpec] = &[
+    ParamSpec {
+        name: "format",
+        kind: ParamKind::String,
+        required: false,
+        description: "Pattern where each '#' becomes a digit, e.g. \"+44 #### ######\"",
+    },
+    LOCALE,
+];
+
+const TEXT_PARAMS: &[ParamSpec] = &[
+    ParamSpec {
+        name: "min_length",
+        kind: ParamKind::Integer,
+        required: false,
+        description: "Fewest characters (default 5)",
+    },
+    ParamSpec {
+        name: "max_length",
+        kind: ParamKind::Integer,
+        required: false,
+        description: "Most characters (default 12)",
+    },
+];
+
+const ENUM_PARAMS: &[ParamSpec] = &[
+    ParamSpec {
+        name: "values",
+        kind: ParamKind::StringList,
+        required: true,
+        description: "The values to pick from",
+    },
+    ParamSpec {
+        name: "weights",
+        kind: ParamKind::NumberList,
+        required: false,
+        description: "Relative frequency of each value, e.g. [80, 15, 5]",
+    },
+];
+
+pub(super) type Built = Result<Box<dyn ColumnGenerator>, GeneratorError>;
+
+struct Id;
+
+impl ColumnType for Id {
+    fn name(&self) -> &'static str {
+        "id"
+    }
+
+    fn description(&self) -> &'static str {
+        "The row number starting at 1, or another id strategy"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        ID_PARAMS
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        header.contains("id")
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        Ok(Box::new(params::id_params(column)?))
+    }
+}
+
+impl ColumnGenerator for params::IdParams {
+    fn generate(&self, index: usize, rng: &mut dyn RngCore) -> String {
+        self.format(&self.strategy.generate(index, rng))
+    }
+
+    fn cardinality(&self) -> Option<u64> {
+        match self.strategy {
+            Strategy::Random { min, max } => Some(params::IntRange { min, max }.cardinality()),
+            _ => None,
+        }
+    }
+
+    fn nth_value(&self, n: u64) -> Option<String> {
+        match self.strategy {
+            Strategy::Random { min, .. } => {
+                Some(self.format(&(min as i128 + n as i128).to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    fn distinct_by_construction(&self) -> bool {
+        matches!(self.strategy, Strategy::Sequence { .. })
+    }
+
+    fn always_unique(&self) -> bool {
+        matches!(self.strategy, Strategy::Random { .. })
+    }
+
+    /// Numeric ids are integers unless a prefix or padding makes them text.
+    fn kind(&self) -> Option<ValueKind> {
+        Some(match self.strategy {
+            Strategy::Sequence { .. } | Strategy::Random { .. } | Strategy::Snowflake => {
+                ValueKind::Int
+            }
+            _ => ValueKind::String,
+        })
+    }
+}
+
+struct Name;
+
+impl ColumnType for Name {
+    fn name(&self) -> &'static str {
+        "name"
+    }
+
+    fn description(&self) -> &'static str {
+        "A first and last name"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        LOCALE_PARAMS
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        header.contains("name")
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        match params::locale(column)? {
+            Some(locale) => Ok(Box::new(|_: usize, rng: &mut dyn RngCore| locale.name(rng))),
+            None => Ok(Box::new(|_: usize, rng: &mut dyn RngCore| {
+                generate_name(rng)
+            })),
+        }
+    }
+}
+
+struct Email;
+
+impl ColumnType for Email {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    fn description(&self) -> &'static str {
+        "An email address"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        EMAIL_PARAMS
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        header.contains("email") || header.contains("mail")
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        Ok(Box::new(EmailValues {
+            from: column.from.clone(),
+        }))
+    }
+}
+
+/// Random addresses, or ones built from the row's `from` name column.
+struct EmailValues {
+    from: Option<String>,
+}
+
+impl ColumnGenerator for EmailValues {
+    fn generate(&self, _: usize, rng: &mut dyn RngCore) -> String {
+        generate_email(rng)
+    }
+
+    fn depends_on(&self) -> Vec<String> {
+        self.from.iter().cloned().collect()
+    }
+
+    /// "Anna Smith" becomes "anna.smith@" a random domain. Names without any
+    /// ASCII letters or digits get a random address.
+    fn generate_in_row(&self, index: usize, row: &RowValues, rng: &mut dyn RngCore) -> String {
+        let name = self.from.as_deref().and_then(|column| row.get(column));
+        let user = name.map(email_user).unwrap_or_default();
+        if user.is_empty() {
+            return self.generate(index, rng);
+        }
+        format!("{}@{}", user, DOMAINS[rng.random_range(0..DOMAINS.len())])
+    }
+
+    /// Suffixes the local part, keeping the address valid.
+    fn with_suffix(&self, value: &str, suffix: u64) -> String {
+        match value.split_once('@') {
+            Some((user, domain)) => format!("{}{}@{}", user, suffix, domain),
+            None => format!("{}{}", value, suffix),
+        }
+    }
+}
+
+struct Age;
+
+impl ColumnType for Age {
+    fn name(&self) -> &'static str {
+        "age"
+    }
+
+    fn description(&self) -> &'static str {
+        "A whole number of years"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        MIN_MAX_INTEGER
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        header.contains("age")
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        Ok(Box::new(Bounded {
+            params: params::int_range(column, params::IntRange::AGE)?,
+            bounds: params::row_bounds(column),
+        }))
+    }
+}
+
+struct City;
+
+impl ColumnType for City {
+    fn name(&self) -> &'static str {
+        "city"
+    }
+
+    fn description(&self) -> &'static str {
+        "A city name"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        CITY_PARAMS
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        header.contains("city")
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        Ok(Box::new(CityValues {
+            within: column.within.clone(),
+            locale: params::locale(column)?,
+        }))
+    }
+}
+
+/// Random cities of the locale, or of the row's `within` country column.
+struct CityValues {
+    within: Option<String>,
+    locale: Option<&'static Locale>,
+}
+
+impl ColumnGenerator for CityValues {
+    fn generate(&self, _: usize, rng: &mut dyn RngCore) -> String {
+        match self.locale {
+            Some(locale) => locale.city(rng),
+            None => generate_city(rng),
+        }
+    }
+
+    fn depends_on(&self) -> Vec<String> {
+        self.within.iter().cloned().collect()
+    }
+
+    /// Countries without a city list, and null countries, get a city of the
+    /// locale.
+    fn generate_in_row(&self, index: usize, row: &RowValues, rng: &mut dyn RngCore) -> String {
+        let country = self.within.as_deref().and_then(|column| row.get(column));
+        match country.and_then(cities_of) {
+            Some(cities) => cities[rng.random_range(0..cities.len())].to_string(),
+            None => self.generate(index, rng),
+        }
+    }
+}
+
+struct Street;
+
+impl ColumnType for Street {
+    fn name(&self) -> &'static str {
+        "street"
+    }
+
+    fn description(&self) -> &'static str {
+        "A street address with a house number, en_US unless a locale is given"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        LOCALE_PARAMS
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        header.contains("street") || header.contains("address")
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        let locale = params::locale(column)?.unwrap_or_else(locale::default_locale);
+        Ok(Box::new(|_: usize, rng: &mut dyn RngCore| {
+            locale.street_address(rng)
+        }))
+    }
+}
+
+struct Postcode;
+
+impl ColumnType for Postcode {
+    fn name(&self) -> &'static str {
+        "postcode"
+    }
+
+    fn description(&self) -> &'static str {
+        "A postal code, en_US unless a locale is given"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        LOCALE_PARAMS
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        header.contains("postcode") || header.contains("postal") || header.contains("zip")
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        let locale = params::locale(column)?.unwrap_or_else(locale::default_locale);
+        Ok(Box::new(locale.postcode.clone()))
+    }
+}
+
+struct Country;
+
+impl ColumnType for Country {
+    fn name(&self) -> &'static str {
+        "country"
+    }
+
+    fn description(&self) -> &'static str {
+        "A country name"
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        header.contains("country")
+    }
+
+    fn build(&self, _: &ColumnSpec) -> Built {
+        Ok(Box::new(|_: usize, rng: &mut dyn RngCore| {
+            generate_country(rng)
+        }))
+    }
+}
+
+struct Phone;
+
+impl ColumnType for Phone {
+    fn name(&self) -> &'static str {
+        "phone"
+    }
+
+    fn description(&self) -> &'static str {
+        "A phone number, US-style unless a format or locale is given"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        PHONE_PARAMS
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        header.contains("phone") || header.contains("tel")
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        let values = match (params::phone_pattern(column)?, params::locale(column)?) {
+            (Some(pattern), _) => PhoneValues::Format(pattern),
+            (None, Some(locale)) => PhoneValues::Locale(&locale.phones),
+            (None, None) => PhoneValues::Us,
+        };
+        Ok(Box::new(values))
+    }
+}
+
+struct Date;
+
+impl ColumnType for Date {
+    fn name(&self) -> &'static str {
+        "date"
+    }
+
+    fn description(&self) -> &'static str {
+        "A calendar date"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        DATE_PARAMS
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        header.contains("date")
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        let date = params::date_params(column)?;
+        if column.unique {
+            params::check_unique_date_format(column, &date)?;
+        }
+        Ok(Box::new(Bounded {
+            params: date,
+            bounds: params::row_bounds(column),
+        }))
+    }
+}
+
+struct Money;
+
+impl ColumnType for Money {
+    fn name(&self) -> &'static str {
+        "money"
+    }
+
+    fn description(&self) -> &'static str {
+        "A decimal amount"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        MONEY_PARAMS
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        header.contains("price")
+            || header.contains("cost")
+            || header.contains("amount")
+            || header.contains("salary")
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        Ok(Box::new(Bounded {
+            params: params::money_params(column)?,
+            bounds: params::row_bounds(column),
+        }))
+    }
+}
+
+/// A whole number with no meaning attached, as inferred from uploaded
+/// values; never detected from a header.
+struct Integer;
+
+impl ColumnType for Integer {
+    fn name(&self) -> &'static str {
+        "integer"
+    }
+
+    fn description(&self) -> &'static str {
+        "A whole number"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        INTEGER_PARAMS
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        Ok(Box::new(Bounded {
+            params: params::int_range(column, params::IntRange::INTEGER)?,
+            bounds: params::row_bounds(column),
+        }))
+    }
+}
+
+/// A decimal number without a currency, as inferred from uploaded values;
+/// never detected from a header.
+struct Decimal;
+
+impl ColumnType for Decimal {
+    fn name(&self) -> &'static str {
+        "decimal"
+    }
+
+    fn description(&self) -> &'static str {
+        "A decimal number"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        DECIMAL_PARAMS
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        let default = params::MoneyParams {
+            min: 0.0,
+            max: 1000.0,
+            precision: 2,
+            currency: None,
+        };
+        Ok(Box::new(Bounded {
+            params: params::decimal_params(column, default)?,
+            bounds: params::row_bounds(column),
+        }))
+    }
+}
+
+/// Random alphanumeric text, and the fallback for undetected headers.
+struct Text;
+
+impl ColumnType for Text {
+    fn name(&self) -> &'static str {
+        "text"
+    }
+
+    fn description(&self) -> &'static str {
+        "Random alphanumeric text; used for headers no other type detects"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        TEXT_PARAMS
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        Ok(Box::new(params::length_range(column)?))
+    }
+}
+
+struct Enum;
+
+impl ColumnType for Enum {
+    fn name(&self) -> &'static str {
+        "enum"
+    }
+
+    fn description(&self) -> &'static str {
+        "One of a fixed list of values, by their weights if given"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        ENUM_PARAMS
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        let values = params::enum_values(column)?;
+        let weights = params::enum_weights(column, &values)?;
+        weighted_values(column, values, weights)
+    }
+}
+
+/// A value of a saved dictionary, loaded into the column spec beforehand.
+struct Dictionary;
+
+impl ColumnType for Dictionary {
+    fn name(&self) -> &'static str {
+        "dictionary"
+    }
+
+    fn description(&self) -> &'static str {
+        "One of the values of a saved dictionary, by its weights if it has any"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        DICTIONARY_PARAMS
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        let entries = params::dictionary_entries(column)?;
+        let values = entries.iter().map(|e| e.value.clone()).collect();
+        let weights = params::dictionary_weights(column, entries)?;
+        weighted_values(column, values, weights)
+    }
+}
+
+/// Picks from `values` by their `weights`, which are checked beforehand.
+/// Values weighted 0 never appear, not even to fill a unique column.
+fn weighted_values(column: &ColumnSpec, values: Vec<String>, weights: Option<Vec<f64>>) -> Built {
+    let Some(weights) = weights else {
+        return Ok(Box::new(EnumValues::new(values, None)));
+    };
+    let (values, weights): (Vec<String>, Vec<f64>) = values
+        .into_iter()
+        .zip(weights)
+        .filter(|(_, weight)| *weight > 0.0)
+        .unzip();
+    let index = WeightedIndex::new(weights).map_err(|e| GeneratorError::InvalidParameter {
+        column: column.name.clone(),
+        message: format!("invalid weights: {}", e),
+    })?;
+    Ok(Box::new(EnumValues::new(values, Some(index))))
+}
+
+/// Values picked from a list, equally likely unless weighted.
+struct EnumValues {
+    values: Vec<String>,
+    weights: Option<WeightedIndex<f64>>,
+    distinct: Vec<String>,
+}
+
+impl EnumValues {
+    fn new(values: Vec<String>, weights: Option<WeightedIndex<f64>>) -> Self {
+        let mut distinct = values.clone();
+        distinct.sort();
+        distinct.dedup();
+        Self {
+            values,
+            weights,
+            distinct,
+        }
+    }
+}
+
+impl ColumnGenerator for EnumValues {
+    fn generate(&self, _: usize, rng: &mut dyn RngCore) -> String {
+        let index = match &self.weights {
+            Some(weights) => weights.sample(rng),
+            None => rng.random_range(0..self.values.len()),
+        };
+        self.values[index].clone()
+    }
+
+    fn cardinality(&self) -> Option<u64> {
+        Some(self.distinct.len() as u64)
+    }
+
+    fn nth_value(&self, n: u64) -> Option<String> {
+        self.distinct.get(n as usize).cloned()
+    }
+}
+
+/// Strings matching a regular expression; see `pattern` for the subset
+/// supported.
+struct Regex;
+
+impl ColumnType for Regex {
+    fn name(&self) -> &'static str {
+        "regex"
+    }
+
+    fn description(&self) -> &'static str {
+        "Text matching a regular expression, e.g. order numbers or postcodes"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        REGEX_PARAMS
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        Ok(Box::new(params::regex_pattern(column)?))
+    }
+}
+
+impl ColumnGenerator for Pattern {
+    fn generate(&self, _: usize, rng: &mut dyn RngCore) -> String {
+        self.sample(rng)
+    }
+
+    fn cardinality(&self) -> Option<u64> {
+        Some(Pattern::cardinality(self))
+    }
+
+    fn nth_value(&self, n: u64) -> Option<String> {
+        Pattern::nth_value(self, n)
+    }
+}
+
+/// The value of an expression over other columns of the row; see
+/// `expression` for the language.
+struct Computed;
+
+impl ColumnType for Computed {
+    fn name(&self) -> &'static str {
+        "computed"
+    }
+
+    fn description(&self) -> &'static str {
+        "An expression over other columns of the same row and the row number"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        COMPUTED_PARAMS
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        let invalid = |message: String| GeneratorError::InvalidParameter {
+            column: column.name.clone(),
+            message,
+        };
+        let Some(text) = &column.expression else {
+            return Err(invalid(
+                "type 'computed' requires an 'expression'".to_string(),
+            ));
+        };
+        let expr = Expr::parse(text)
+            .map_err(|message| invalid(format!("invalid expression '{}': {}", text, message)))?;
+        if column.unique {
+            return Err(invalid("a computed column cannot be unique".to_string()));
+        }
+        if let Some(precision) = column.precision
+            && precision > 10
+        {
+            return Err(invalid(format!(
+                "precision must be at most 10, got {}",
+                precision
+            )));
+        }
+        if let Some(format) = &column.format {
+            check_date_format(format).map_err(invalid)?;
+        }
+        Ok(Box::new(ComputedValues {
+            expr,
+            precision: column.precision.map(|p| p as usize),
+            currency: column.currency.clone(),
+            date_format: column.format.clone(),
+        }))
+    }
+}
+
+struct ComputedValues {
+    expr: Expr,
+    /// Applied to number results.
+    precision: Option<usize>,
+    currency: Option<String>,
+    /// strftime format of date results.
+    date_format: Option<String>,
+}
+
+impl ColumnGenerator for ComputedValues {
+    /// The value as if every other column were null.
+    fn generate(&self, index: usize, rng: &mut dyn RngCore) -> String {
+        self.generate_in_row(index, &RowValues::new(&[], &[]), rng)
+    }
+
+    fn depends_on(&self) -> Vec<String> {
+        self.expr.columns()
+    }
+
+    fn generate_in_row(&self, index: usize, row: &RowValues, rng: &mut dyn RngCore) -> String {
+        self.generate_in_row_or_null(index, row, rng)
+            .unwrap_or_default()
+    }
+
+    fn generate_in_row_or_null(
+        &self,
+        index: usize,
+        row: &RowValues,
+        _: &mut dyn RngCore,
+    ) -> Option<String> {
+        Some(match self.expr.evaluate(index, row) {
+            Value::Null => return None,
+            Value::Number(number) => format!(
+                "{}{}",
+                self.currency.as_deref().unwrap_or(""),
+                number.format(self.precision.unwrap_or(number.decimals))
+            ),
+            Value::Date(date) => match &self.date_format {
+                Some(format) => date.format(format).to_string(),
+                None => Value::Date(date).to_text(),
+            },
+            value => value.to_text(),
+        })
+    }
+
+    fn kind(&self) -> Option<ValueKind> {
+        None
+    }
+}
+
+/// A range-based generator whose values also stay between the values of
+/// other columns of the row.
+struct Bounded<P> {
+    params: P,
+    bounds: params::RowBounds,
+}
+
+impl ColumnGenerator for Bounded<params::IntRange> {
+    fn generate(&self, index: usize, rng: &mut dyn RngCore) -> String {
+        self.params.generate(index, rng)
+    }
+
+    fn depends_on(&self) -> Vec<String> {
+        self.bounds.columns()
+    }
+
+    fn generate_in_row(&self, _: usize, row: &RowValues, rng: &mut dyn RngCore) -> String {
+        let (min, max) = self.bounds.narrow(
+            row,
+            |value| Number::parse(value).map(|n| n.value),
+            self.params.min as f64,
+            self.params.max as f64,
+        );
+        let min = min.ceil() as i64;
+        let range = params::IntRange {
+            min,
+            max: (max.floor() as i64).max(min),
+        };
+        range.sample(rng).to_string()
+    }
+
+    fn cardinality(&self) -> Option<u64> {
+        ColumnGenerator::cardinality(&self.params)
+    }
+
+    fn nth_value(&self, n: u64) -> Option<String> {
+        ColumnGenerator::nth_value(&self.params, n)
+    }
+
+    fn kind(&self) -> Option<ValueKind> {
+        Some(ValueKind::Int)
+    }
+}
+
+impl ColumnGenerator for Bounded<params::MoneyParams> {
+    fn generate(&self, index: usize, rng: &mut dyn RngCore) -> String {
+        self.params.generate(index, rng)
+    }
+
+    fn depends_on(&self) -> Vec<String> {
+        self.bounds.columns()
+    }
+
+    fn generate_in_row(&self, _: usize, row: &RowValues, rng: &mut dyn RngCore) -> String {
+        let (min, max) = self.bounds.narrow(
+            row,
+            |value| Number::parse(value).map(|n| n.value),
+            self.params.min,
+            self.params.max,
+        );
+        self.params.sample_between(min, max, rng)
+    }
+
+    fn cardinality(&self) -> Option<u64> {
+        ColumnGenerator::cardinality(&self.params)
+    }
+
+    fn nth_value(&self, n: u64) -> Option<String> {
+        ColumnGenerator::nth_value(&self.params, n)
+    }
+
+    fn kind(&self) -> Option<ValueKind> {
+        Some(ValueKind::Decimal)
+    }
+}
+
+impl ColumnGenerator for Bounded<params::DateParams> {
+    fn generate(&self, index: usize, rng: &mut dyn RngCore) -> String {
+        self.params.generate(index, rng)
+    }
+
+    fn depends_on(&self) -> Vec<String> {
+        self.bounds.columns()
+    }
+
+    fn generate_in_row(&self, _: usize, row: &RowValues, rng: &mut dyn RngCore) -> String {
+        let (min, max) = self.bounds.narrow(
+            row,
+            |value| self.params.parse(value),
+            self.params.min,
+            self.params.max,
+        );
+        self.params.sample_between(min, max, rng)
+    }
+
+    fn cardinality(&self) -> Option<u64> {
+        ColumnGenerator::cardinality(&self.params)
+    }
+
+    fn nth_value(&self, n: u64) -> Option<String> {
+        ColumnGenerator::nth_value(&self.params, n)
+    }
+
+    fn kind(&self) -> Option<ValueKind> {
+        Some(ValueKind::Date)
+    }
+}
+
+/// Numbers of a `#` format, of one of a locale's patterns, or US-style
+/// "+1-AAA-BBB-CCCC" numbers.
+enum PhoneValues {
+    Us,
+    Format(String),
+    Locale(&'static [Pattern]),
+}
+
+impl ColumnGenerator for PhoneValues {
+    fn generate(&self, _: usize, rng: &mut dyn RngCore) -> String {
+        match self {
+            PhoneValues::Us => generate_phone(rng),
+            PhoneValues::Format(pattern) => params::sample_phone_pattern(pattern, rng),
+            PhoneValues::Locale(patterns) => {
+                patterns[rng.random_range(0..patterns.len())].sample(rng)
+            }
+        }
+    }
+
+    fn cardinality(&self) -> Option<u64> {
+        Some(match self {
+            PhoneValues::Us => 800 * 800 * 9000,
+            PhoneValues::Format(pattern) => {
+                let digits = pattern.matches('#').count() as u32;
+                10u64.checked_pow(digits).unwrap_or(u64::MAX)
+            }
+            PhoneValues::Locale(patterns) => patterns
+                .iter()
+                .fold(0u64, |total, p| total.saturating_add(p.cardinality())),
+        })
+    }
+
+    fn nth_value(&self, mut n: u64) -> Option<String> {
+        match self {
+            PhoneValues::Us => Some(format!(
+                "+1-{:03}-{:03}-{:04}",
+                200 + n / (9000 * 800),
+                200 + n / 9000 % 800,
+                1000 + n % 9000
+            )),
+            PhoneValues::Format(pattern) => Some(params::nth_phone_pattern(pattern, n)),
+            PhoneValues::Locale(patterns) => {
+                for pattern in patterns.iter() {
+                    match pattern.nth_value(n) {
+                        Some(value) => return Some(value),
+                        None => n -= pattern.cardinality(),
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+impl ColumnGenerator for params::IntRange {
+    fn generate(&self, _: usize, rng: &mut dyn RngCore) -> String {
+        self.sample(rng).to_string()
+    }
+
+    fn cardinality(&self) -> Option<u64> {
+        Some(self.cardinality())
+    }
+
+    fn nth_value(&self, n: u64) -> Option<String> {
+        Some((self.min as i128 + n as i128).to_string())
+    }
+
+    fn kind(&self) -> Option<ValueKind> {
+        Some(ValueKind::Int)
+    }
+}
+
+impl ColumnGenerator for params::MoneyParams {
+    fn generate(&self, _: usize, rng: &mut dyn RngCore) -> String {
+        self.sample(rng)
+    }
+
+    fn cardinality(&self) -> Option<u64> {
+        Some(self.cardinality())
+    }
+
+    fn nth_value(&self, n: u64) -> Option<String> {
+        Some(self.nth_value(n))
+    }
+
+    fn kind(&self) -> Option<ValueKind> {
+        Some(ValueKind::Decimal)
+    }
+}
+
+impl ColumnGenerator for params::DateParams {
+    fn generate(&self, _: usize, rng: &mut dyn RngCore) -> String {
+        self.sample(rng)
+    }
+
+    fn cardinality(&self) -> Option<u64> {
+        Some((self.max - self.min).num_days() as u64 + 1)
+    }
+
+    fn nth_value(&self, n: u64) -> Option<String> {
+        self.nth_value(n)
+    }
+
+    fn kind(&self) -> Option<ValueKind> {
+        Some(ValueKind::Date)
+    }
+}
+
+impl ColumnGenerator for params::LengthRange {
+    fn generate(&self, _: usize, rng: &mut dyn RngCore) -> String {
+        generate_text_with_length(rng, self.min, self.max)
+    }
+
+    fn cardinality(&self) -> Option<u64> {
+        Some(self.cardinality())
+    }
+
+    fn nth_value(&self, n: u64) -> Option<String> {
+        self.nth_value(n)
+    }
+}
+
+pub(super) fn generate_name(rng: &mut (impl Rng + ?Sized)) -> String {
+    const FIRST_NAMES: &[&str] = &[
+        "James",
+        "Mary",
+        "John",
+        "Patricia",
+        "Robert",
+        "Jennifer",
+        "Michael",
+        "Linda",
+        "William",
+        "Elizabeth",
+        "David",
+        "Barbara",
+        "Richard",
+        "Susan",
+        "Joseph",
+        "Jessica",
+        "Thomas",
+        "Sarah",
+        "Charles",
+        "Karen",
+        "Emma",
+        "Oliver",
+        "Sophia",
+        "Liam",
+    ];
+    const LAST_NAMES: &[&str] = &[
+        "Smith",
+        "Johnson",
+        "Williams",
+        "Brown",
+        "Jones",
+        "Garcia",
+        "Miller",
+        "Davis",
+        "Rodriguez",
+        "Martinez",
+        "Hernandez",
+        "Lopez",
+        "Gonzalez",
+        "Wilson",
+        "Anderson",
+        "Thomas",
+        "Taylor",
+        "Moore",
+        "Jackson",
+        "Martin",
+        "Lee",
+        "Thompson",
+        "White",
+        "Harris",
+    ];
+
+    let first = FIRST_NAMES[rng.random_range(0..FIRST_NAMES.len())];
+    let last = LAST_NAMES[rng.random_range(0..LAST_NAMES.len())];
+    format!("{} {}", first, last)
+}
+
+const DOMAINS: &[&str] = &[
+    "gmail.com",
+    "yahoo.com",
+    "outlook.com",
+    "example.com",
+    "test.com",
+];
+
+fn generate_email(rng: &mut (impl Rng + ?Sized)) -> String {
+    let username: String = (0..8)
+        .map(|_| {
+            const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+            CHARSET[rng.random_range(0..CHARSET.len())] as char
+        })
+        .collect();
+
+    let domain = DOMAINS[rng.random_range(0..DOMAINS.len())];
+    format!("{}@{}", username, domain)
+}
+
+fn generate_city(rng: &mut (impl Rng + ?Sized)) -> String {
+    const CITIES: &[&str] = &[
+        "New York",
+        "Los Angeles",
+        "Chicago",
+        "Houston",
+        "Phoenix",
+        "Philadelphia",
+        "San Antonio",
+        "San Diego",
+        "Dallas",
+        "San Jose",
+        "Austin",
+        "Jacksonville",
+        "London",
+        "Paris",
+        "Tokyo",
+        "Berlin",
+        "Madrid",
+        "Rome",
+        "Sydney",
+        "Toronto",
+    ];
+
+    CITIES[rng.random_range(0..CITIES.len())].to_string()
+}
+
+const COUNTRIES: &[&str] = &[
+    "United States",
+    "Canada",
+    "United Kingdom",
+    "Germany",
+    "France",
+    "Japan",
+    "Australia",
+    "Spain",
+    "Italy",
+    "Brazil",
+    "Mexico",
+    "Netherlands",
+];
+
+fn generate_country(rng: &mut (impl Rng + ?Sized)) -> String {
+    COUNTRIES[rng.random_range(0..COUNTRIES.len())].to_string()
+}
+
+/// The cities `city` columns pick from for each of `COUNTRIES`.
+const CITIES_BY_COUNTRY: &[(&str, &[&str])] = &[
+    (
+        "United States",
+        &[
+            "New York",
+            "Los Angeles",
+            "Chicago",
+            "Houston",
+            "Phoenix",
+            "Philadelphia",
+            "San Antonio",
+            "San Diego",
+            "Dallas",
+            "Austin",
+        ],
+    ),
+    (
+        "Canada",
+        &["Toronto", "Montreal", "Vancouver", "Calgary", "Ottawa"],
+    ),
+    (
+        "United Kingdom",
+        &["London", "Manchester", "Birmingham", "Glasgow", "Liverpool"],
+    ),
+    (
+        "Germany",
+        &["Berlin", "Hamburg", "Munich", "Cologne", "Frankfurt"],
+    ),
+    (
+        "France",
+        &["Paris", "Marseille", "Lyon", "Toulouse", "Nice"],
+    ),
+    (
+        "Japan",
+        &["Tokyo", "Osaka", "Yokohama", "Nagoya", "Sapporo"],
+    ),
+    (
+        "Australia",
+        &["Sydney", "Melbourne", "Brisbane", "Perth", "Adelaide"],
+    ),
+    (
+        "Spain",
+        &["Madrid", "Barcelona", "Valencia", "Seville", "Bilbao"],
+    ),
+    ("Italy", &["Rome", "Milan", "Naples", "Turin", "Florence"]),
+    (
+        "Brazil",
+        &[
+            "São Paulo",
+            "Rio de Janeiro",
+            "Brasília",
+            "Salvador",
+            "Fortaleza",
+        ],
+    ),
+    (
+        "Mexico",
+        &[
+            "Mexico City",
+            "Guadalajara",
+            "Monterrey",
+            "Puebla",
+            "Tijuana",
+        ],
+    ),
+    (
+        "Netherlands",
+        &[
+            "Amsterdam",
+            "Rotterdam",
+            "The Hague",
+            "Utrecht",
+            "Eindhoven",
+        ],
+    ),
+];
+
+/// The known cities of a country, matched ignoring case.
+pub(super) fn cities_of(country: &str) -> Option<&'static [&'static str]> {
+    CITIES_BY_COUNTRY
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(country.trim()))
+        .map(|(_, cities)| *cities)
+}
+
+/// Accented Latin letters of the locale packs and how addresses spell them.
+const ACCENTS: &[(&str, &str)] = &[
+    ("àáâãäå", "a"),
+    ("ç", "c"),
+    ("èéêë", "e"),
+    ("ìíîï", "i"),
+    ("ñ", "n"),
+    ("òóôõöø", "o"),
+    ("ùúûü", "u"),
+    ("ýÿ", "y"),
+    ("ß", "ss"),
+];
+
+/// The local part of an address for a person's name: its ASCII letters and
+/// digits, lowercased, with accents dropped and a dot between words.
+pub(super) fn email_user(name: &str) -> String {
+    name.split_whitespace()
+        .map(|word| {
+            let mut user = String::new();
+            for c in word.chars().flat_map(char::to_lowercase) {
+                if c.is_ascii_alphanumeric() {
+                    user.push(c);
+                } else if let Some((_, ascii)) = ACCENTS.iter().find(|(from, _)| from.contains(c)) {
+                    user.push_str(ascii);
+                }
+            }
+            user
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn generate_phone(rng: &mut (impl Rng + ?Sized)) -> String {
+    format!(
+        "+1-{:03}-{:03}-{:04}",
+        rng.random_range(200..=999),
+        rng.random_range(200..=999),
+        rng.random_range(1000..=9999)
+    )
+}
+
+pub(super) const TEXT_CHARSET: &[u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+pub(super) fn generate_text_with_length(
+    rng: &mut (impl Rng + ?Sized),
+    min_len: usize,
+    max_len: usize,
+) -> String {
+    let length = rng.random_range(min_len..=max_len);
+
+    (0..length)
+        .map(|_| TEXT_CHARSET[rng.random_range(0..TEXT_CHARSET.len())] as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::registry::registry;
+    use crate::schema::Bound;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn detected(header: &str) -> &'static str {
+        registry().detect(header).unwrap().name()
+    }
+
+    fn generate(type_name: &str, index: usize, rng: &mut StdRng) -> String {
+        registry()
+            .get(type_name)
+            .unwrap()
+            .build(&ColumnSpec::new("column", type_name))
+            .unwrap()
+            .generate(index, rng)
+    }
+
+    #[test]
+    fn test_detect_data_type_id() {
+        assert_eq!(detected("id"), "id");
+        assert_eq!(detected("ID"), "id");
+        assert_eq!(detected("user_id"), "id");
+        assert_eq!(detected("product_id"), "id");
+    }
+
+    #[test]
+    fn test_detect_data_type_name() {
+        assert_eq!(detected("name"), "name");
+        assert_eq!(detected("Name"), "name");
+        assert_eq!(detected("first_name"), "name");
+        assert_eq!(detected("last_name"), "name");
+        assert_eq!(detected("full_name"), "name");
+        assert_eq!(detected("username"), "username");
+    }
+
+    #[test]
+    fn test_detect_data_type_email() {
+        assert_eq!(detected("email"), "email");
+        assert_eq!(detected("Email"), "email");
+        assert_eq!(detected("mail"), "email");
+        assert_eq!(detected("user_email"), "email");
+    }
+
+    #[test]
+    fn test_detect_data_type_age() {
+        assert_eq!(detected("age"), "age");
+        assert_eq!(detected("Age"), "age");
+        assert_eq!(detected("user_age"), "age");
+    }
+
+    #[test]
+    fn test_detect_data_type_city() {
+        assert_eq!(detected("city"), "city");
+        assert_eq!(detected("City"), "city");
+        assert_eq!(detected("home_city"), "city");
+    }
+
+    #[test]
+    fn test_detect_data_type_street_and_postcode() {
+        assert_eq!(detected("street"), "street");
+        assert_eq!(detected("home_address"), "street");
+        assert_eq!(detected("postcode"), "postcode");
+        assert_eq!(detected("zip_code"), "postcode");
+        assert_eq!(detected("postal_code"), "postcode");
+        assert_eq!(detected("email_address"), "email");
+    }
+
+    #[test]
+    fn test_detect_data_type_country() {
+        assert_eq!(detected("country"), "country");
+        assert_eq!(detected("Country"), "country");
+        assert_eq!(detected("home_country"), "country");
+    }
+
+    #[test]
+    fn test_detect_data_type_phone() {
+        assert_eq!(detected("phone"), "phone");
+        assert_eq!(detected("Phone"), "phone");
+        assert_eq!(detected("tel"), "phone");
+        assert_eq!(detected("telephone"), "phone");
+    }
+
+    #[test]
+    fn test_detect_data_type_date() {
+        assert_eq!(detected("date"), "date");
+        assert_eq!(detected("Date"), "date");
+        assert_eq!(detected("birth_date"), "date");
+        assert_eq!(detected("created_date"), "date");
+    }
+
+    #[test]
+    fn test_detect_data_type_money() {
+        assert_eq!(detected("price"), "money");
+        assert_eq!(detected("cost"), "money");
+        assert_eq!(detected("amount"), "money");
+        assert_eq!(detected("salary"), "money");
+        assert_eq!(detected("total_price"), "money");
+    }
+
+    #[test]
+    fn test_detect_data_type_text_fallback() {
+        assert_eq!(detected("description"), "text");
+        assert_eq!(detected("random"), "text");
+        assert_eq!(detected("xyz"), "text");
+    }
+
+    #[test]
+    fn test_generate_name() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let name = generate_name(&mut rng);
+        assert!(name.contains(' '));
+        assert!(name.len() > 3);
+        let parts: Vec<&str> = name.split(' ').collect();
+        assert_eq!(parts.len(), 2);
+    }
+
+    #[test]
+    fn test_generate_email() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let email = generate_email(&mut rng);
+        assert!(email.contains('@'));
+        assert!(email.contains('.'));
+        let parts: Vec<&str> = email.split('@').collect();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].len(), 8);
+    }
+
+    #[test]
+    fn test_generate_city() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let city = generate_city(&mut rng);
+        assert!(!city.is_empty());
+        assert!(city.chars().next().unwrap().is_uppercase());
+    }
+
+    #[test]
+    fn test_generate_country() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let country = generate_country(&mut rng);
+        assert!(!country.is_empty());
+        assert!(country.chars().next().unwrap().is_uppercase());
+    }
+
+    #[test]
+    fn test_generate_phone() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let phone = generate_phone(&mut rng);
+        assert!(phone.starts_with("+1-"));
+        assert!(phone.matches('-').count() == 3);
+        let parts: Vec<&str> = phone.split('-').collect();
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts[1].len(), 3);
+        assert_eq!(parts[2].len(), 3);
+        assert_eq!(parts[3].len(), 4);
+    }
+
+    #[test]
+    fn test_generate_date() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let date = generate("date", 0, &mut rng);
+        assert!(date.matches('-').count() == 2);
+        let parts: Vec<&str> = date.split('-').collect();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].len(), 4);
+        assert_eq!(parts[1].len(), 2);
+        assert_eq!(parts[2].len(), 2);
+        let year: i32 = parts[0].parse().unwrap();
+        assert!((2020..=2025).contains(&year));
+        let month: i32 = parts[1].parse().unwrap();
+        assert!((1..=12).contains(&month));
+        let day: i32 = parts[2].parse().unwrap();
+        assert!((1..=31).contains(&day));
+    }
+
+    #[test]
+    fn test_generate_random_text() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let text = generate("text", 0, &mut rng);
+        assert!(text.len() >= 5 && text.len() <= 12);
+        assert!(text.chars().all(|c| c.is_alphanumeric()));
+    }
+
+    #[test]
+    fn test_generate_value_id() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let value = generate("id", 5, &mut rng);
+        assert_eq!(value, "5");
+    }
+
+    #[test]
+    fn test_generate_value_age() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let value = generate("age", 0, &mut rng);
+        let age: i32 = value.parse().unwrap();
+        assert!((18..=80).contains(&age));
+    }
+
+    #[test]
+    fn test_generate_value_money() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let value = generate("money", 0, &mut rng);
+        assert!(value.contains('.'));
+        let parts: Vec<&str> = value.split('.').collect();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[1].len(), 2);
+        let amount: f64 = value.parse().unwrap();
+        assert!((10.0..=10000.0).contains(&amount));
+    }
+
+    #[test]
+    fn test_generate_value_integer_and_decimal() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut quantity = ColumnSpec::new("quantity", "integer");
+        quantity.min = Some(Bound::Number(4000.0));
+        quantity.max = Some(Bound::Number(6000.0));
+        let quantity = Integer.build(&quantity).unwrap();
+        for _ in 0..50 {
+            let value: i64 = quantity.generate(0, &mut rng).parse().unwrap();
+            assert!((4000..=6000).contains(&value));
+        }
+        assert_eq!(quantity.kind(), Some(ValueKind::Int));
+
+        let value = generate("decimal", 0, &mut rng);
+        assert_eq!(value.split_once('.').unwrap().1.len(), 2);
+        assert!((0.0..=1000.0).contains(&value.parse::<f64>().unwrap()));
+        assert_eq!(detected("quantity"), "text");
+    }
+
+    #[test]
+    fn test_locale_columns() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let build = |type_name: &str, locale: Option<&str>| {
+            let mut column = ColumnSpec::new("column", type_name);
+            column.locale = locale.map(String::from);
+            registry().get(type_name).unwrap().build(&column)
+        };
+        let french = locale::find("fr_FR").unwrap();
+
+        let city = build("city", Some("fr_FR")).unwrap().generate(0, &mut rng);
+        assert!(french.cities.contains(&city), "{}", city);
+        let name = build("name", Some("ja_JP")).unwrap().generate(0, &mut rng);
+        assert!(!name.is_ascii(), "{}", name);
+        let date = build("date", Some("fr_FR")).unwrap().generate(0, &mut rng);
+        assert!(chrono::NaiveDate::parse_from_str(&date, "%d/%m/%Y").is_ok());
+        let phone = build("phone", Some("pt_BR")).unwrap();
+        assert!(phone.generate(0, &mut rng).starts_with("+55 "));
+        assert!(phone.nth_value(phone.cardinality().unwrap() - 1).is_some());
+
+        // Street and postcode columns fall back to en_US.
+        let postcode = build("postcode", None).unwrap();
+        assert_eq!(postcode.cardinality(), Some(100_000));
+        assert_eq!(postcode.nth_value(42).unwrap(), "00042");
+        let street = build("street", None).unwrap().generate(0, &mut rng);
+        assert!(
+            street.starts_with(|c: char| c.is_ascii_digit()),
+            "{}",
+            street
+        );
+
+        match build("name", Some("xx_XX")) {
+            Err(GeneratorError::InvalidParameter { message, .. }) => assert_eq!(
+                message,
+                "unknown locale 'xx_XX' (expected one of: en_US, de_DE, fr_FR, ja_JP, pt_BR)"
+            ),
+            _ => panic!("expected an unknown locale error"),
+        }
+    }
+
+    #[test]
+    fn test_every_country_has_cities() {
+        for country in COUNTRIES {
+            assert!(cities_of(country).is_some(), "{}", country);
+        }
+        assert_eq!(cities_of(" japan").unwrap()[0], "Tokyo");
+        assert!(cities_of("Atlantis").is_none());
+    }
+
+    #[test]
+    fn test_email_user() {
+        assert_eq!(email_user("Anna Smith"), "anna.smith");
+        assert_eq!(email_user("  Mary-Jane  O'Neil "), "maryjane.oneil");
+        assert_eq!(email_user("Jürgen Schäfer"), "jurgen.schafer");
+        assert_eq!(email_user("名前"), "");
+    }
+}
@@ -0,0 +1,1037 @@
+//! The wider catalog of built-in types: internet, finance, commerce, places
+//! and times, each detected from typical header names so common schemas
+//! need no manual typing. `builtin::register_all` decides their detection
+//! order among the core types.
+use super::builtin::{Built, email_user, generate_name};
+use super::ids::Strategy;
+use super::locale;
+use super::params::{self, MoneyParams, TimeParams, TimestampParams};
+use super::registry::{ColumnGenerator, ColumnType, ParamKind, ParamSpec};
+use crate::schema::ColumnSpec;
+use crate::value::ValueKind;
+use rand::{Rng, RngCore};
+
+/// A parameterless type whose values need nothing but the random source.
+pub(super) struct Simple {
+    name: &'static str,
+    description: &'static str,
+    detects: fn(&str) -> bool,
+    generate: fn(&mut dyn RngCore) -> String,
+}
+
+impl ColumnType for Simple {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        (self.detects)(header)
+    }
+
+    fn build(&self, _: &ColumnSpec) -> Built {
+        let generate = self.generate;
+        Ok(Box::new(move |_: usize, rng: &mut dyn RngCore| {
+            generate(rng)
+        }))
+    }
+}
+
+/// Whether `header` has one of `words` between its punctuation, so "ip"
+/// matches "client_ip" but not "zip".
+fn has_word(header: &str, words: &[&str]) -> bool {
+    header
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| words.contains(&word))
+}
+
+fn contains_any(header: &str, parts: &[&str]) -> bool {
+    parts.iter().any(|part| header.contains(part))
+}
+
+fn pick<'a>(values: &[&'a str], rng: &mut (impl Rng + ?Sized)) -> &'a str {
+    values[rng.random_range(0..values.len())]
+}
+
+fn digits(count: usize, rng: &mut (impl Rng + ?Sized)) -> String {
+    (0..count)
+        .map(|_| char::from(b'0' + rng.random_range(0..10u8)))
+        .collect()
+}
+
+pub(super) const UUID: Simple = Simple {
+    name: "uuid",
+    description: "A random version 4 UUID",
+    detects: |header| contains_any(header, &["uuid", "guid"]),
+    generate: |rng| Strategy::UuidV4.generate(0, rng),
+};
+
+pub(super) struct Boolean;
+
+impl ColumnType for Boolean {
+    fn name(&self) -> &'static str {
+        "boolean"
+    }
+
+    fn description(&self) -> &'static str {
+        "true or false"
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        ["is_", "has_", "can_"]
+            .iter()
+            .any(|p| header.starts_with(p))
+            || has_word(
+                header,
+                &[
+                    "active", "enabled", "verified", "valid", "flag", "bool", "boolean",
+                ],
+            )
+    }
+
+    fn build(&self, _: &ColumnSpec) -> Built {
+        Ok(Box::new(BooleanValues))
+    }
+}
+
+struct BooleanValues;
+
+impl ColumnGenerator for BooleanValues {
+    fn generate(&self, _: usize, rng: &mut dyn RngCore) -> String {
+        rng.random_bool(0.5).to_string()
+    }
+
+    fn cardinality(&self) -> Option<u64> {
+        Some(2)
+    }
+
+    fn nth_value(&self, n: u64) -> Option<String> {
+        (n < 2).then(|| (n == 1).to_string())
+    }
+
+    fn kind(&self) -> Option<ValueKind> {
+        Some(ValueKind::Bool)
+    }
+}
+
+const DOMAIN_WORDS: &[&str] = &[
+    "acme", "bright", "cloud", "data", "blue", "nova", "pixel", "summit", "north", "river",
+    "stone", "swift", "quantum", "green", "orbit", "spark", "maple", "harbor", "silver", "atlas",
+];
+
+const TLDS: &[&str] = &["com", "net", "org", "io", "dev", "co", "app"];
+
+fn generate_domain(rng: &mut (impl Rng + ?Sized)) -> String {
+    format!(
+        "{}{}.{}",
+        pick(DOMAIN_WORDS, rng),
+        pick(DOMAIN_WORDS, rng),
+        pick(TLDS, rng)
+    )
+}
+
+pub(super) const URL: Simple = Simple {
+    name: "url",
+    description: "An https URL on a generated domain",
+    detects: |header| {
+        has_word(header, &["url", "uri", "link"]) || contains_any(header, &["website", "homepage"])
+    },
+    generate: |rng| {
+        const PATHS: &[&str] = &[
+            "", "about", "blog", "products", "docs", "pricing", "contact",
+        ];
+        format!("https://www.{}/{}", generate_domain(rng), pick(PATHS, rng))
+    },
+};
+
+pub(super) const USER_AGENT: Simple = Simple {
+    name: "user_agent",
+    description: "A desktop or mobile browser's User-Agent header",
+    detects: |header| contains_any(header, &["user_agent", "useragent"]),
+    generate: |rng| {
+        const USER_AGENTS: &[&str] = &[
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36",
+            "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Safari/605.1.15",
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:125.0) Gecko/20100101 Firefox/125.0",
+            "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36",
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36 Edg/124.0.0.0",
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1",
+            "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Mobile Safari/537.36",
+            "Mozilla/5.0 (iPad; CPU OS 17_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1",
+        ];
+        pick(USER_AGENTS, rng).to_string()
+    },
+};
+
+const PERCENTAGE_PARAMS: &[ParamSpec] = &[
+    ParamSpec {
+        name: "min",
+        kind: ParamKind::Number,
+        required: false,
+        description: "Smallest value (default 0)",
+    },
+    ParamSpec {
+        name: "max",
+        kind: ParamKind::Number,
+        required: false,
+        description: "Largest value (default 100)",
+    },
+    ParamSpec {
+        name: "precision",
+        kind: ParamKind::Integer,
+        required: false,
+        description: "Digits after the decimal point (default 2)",
+    },
+];
+
+pub(super) struct Percentage;
+
+impl ColumnType for Percentage {
+    fn name(&self) -> &'static str {
+        "percentage"
+    }
+
+    fn description(&self) -> &'static str {
+        "A number from 0 to 100"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        PERCENTAGE_PARAMS
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        header.contains("percent") || has_word(header, &["pct"])
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        let default = MoneyParams {
+            min: 0.0,
+            max: 100.0,
+            precision: 2,
+            currency: None,
+        };
+        Ok(Box::new(params::decimal_params(column, default)?))
+    }
+}
+
+pub(super) const DOMAIN: Simple = Simple {
+    name: "domain",
+    description: "A domain name such as \"bluesummit.io\"",
+    detects: |header| has_word(header, &["domain", "host", "hostname"]),
+    generate: |rng| generate_domain(rng),
+};
+
+pub(super) const USERNAME: Simple = Simple {
+    name: "username",
+    description: "A login name built from a person's name",
+    detects: |header| {
+        contains_any(
+            header,
+            &[
+                "username",
+                "user_name",
+                "login_name",
+                "screen_name",
+                "nickname",
+            ],
+        ) || has_word(header, &["handle"])
+    },
+    generate: |rng| {
+        let user = email_user(&generate_name(rng));
+        let user = match rng.random_range(0..3) {
+            0 => user,
+            1 => user.replace('.', "_"),
+            _ => user.replace('.', ""),
+        };
+        if rng.random_bool(0.5) {
+            format!("{}{}", user, rng.random_range(1..100))
+        } else {
+            user
+        }
+    },
+};
+
+pub(super) const COMPANY: Simple = Simple {
+    name: "company",
+    description: "A company name such as \"Brightstone Labs\"",
+    // Only a trailing company word or one followed by "name" counts, so
+    // "company_email" and "business_phone" stay emails and phones.
+    detects: |header| {
+        const WORDS: &[&str] = &[
+            "company",
+            "employer",
+            "organization",
+            "organisation",
+            "business",
+        ];
+        let is_company = |word: &str| {
+            WORDS.iter().any(|company| {
+                word.strip_prefix(company)
+                    .is_some_and(|rest| rest.is_empty() || rest == "name")
+            })
+        };
+        let words: Vec<&str> = header
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+        words
+            .iter()
+            .position(|word| is_company(word))
+            .is_some_and(|at| words[at + 1..].iter().all(|word| *word == "name"))
+    },
+    generate: |rng| {
+        const SUFFIXES: &[&str] = &[
+            "Inc.",
+            "LLC",
+            "Ltd.",
+            "Group",
+            "Holdings",
+            "Partners",
+            "Systems",
+            "Labs",
+            "Solutions",
+            "Technologies",
+        ];
+        let first = pick(DOMAIN_WORDS, rng);
+        let mut name = first[..1].to_uppercase() + &first[1..];
+        name.push_str(pick(DOMAIN_WORDS, rng));
+        format!("{} {}", name, pick(SUFFIXES, rng))
+    },
+};
+
+pub(super) const PASSWORD_HASH: Simple = Simple {
+    name: "password_hash",
+    description: "A bcrypt-formatted password hash (not of any real password)",
+    detects: |header| {
+        contains_any(header, &["password", "passwd", "pwd"]) || has_word(header, &["hash"])
+    },
+    generate: |rng| {
+        const BCRYPT_CHARSET: &[u8] =
+            b"./ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+        let hash: String = (0..53)
+            .map(|_| BCRYPT_CHARSET[rng.random_range(0..BCRYPT_CHARSET.len())] as char)
+            .collect();
+        format!("$2b$12${}", hash)
+    },
+};
+
+const LOCALE_PARAMS: &[ParamSpec] = &[ParamSpec {
+    name: "locale",
+    kind: ParamKind::String,
+    required: false,
+    description: "Locale pack to draw from (default \"en_US\")",
+}];
+
+pub(super) struct State;
+
+impl ColumnType for State {
+    fn name(&self) -> &'static str {
+        "state"
+    }
+
+    fn description(&self) -> &'static str {
+        "A state, province or region of the locale's country"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        LOCALE_PARAMS
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        has_word(header, &["state"]) || header.contains("province")
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        let locale = params::locale(column)?.unwrap_or_else(locale::default_locale);
+        Ok(Box::new(|_: usize, rng: &mut dyn RngCore| {
+            locale.state(rng)
+        }))
+    }
+}
+
+pub(super) const IPV6: Simple = Simple {
+    name: "ipv6",
+    description: "An IPv6 address in the 2001:db8::/32 documentation range",
+    // Separators dropped, so "ip_v6" and "ip-v6" read as "ipv6".
+    detects: |header| {
+        has_word(header, &["ip6"])
+            || header
+                .replace(|c: char| !c.is_alphanumeric(), "")
+                .contains("ipv6")
+    },
+    generate: |rng| {
+        let groups: Vec<String> = (0..6)
+            .map(|_| format!("{:x}", rng.random::<u16>()))
+            .collect();
+        format!("2001:db8:{}", groups.join(":"))
+    },
+};
+
+pub(super) const IPV4: Simple = Simple {
+    name: "ipv4",
+    description: "An IPv4 address",
+    detects: |header| {
+        has_word(header, &["ip", "ipv4"]) || contains_any(header, &["ipaddress", "ip_address"])
+    },
+    generate: |rng| {
+        format!(
+            "{}.{}.{}.{}",
+            rng.random_range(1..=223),
+            rng.random_range(0..=255),
+            rng.random_range(0..=255),
+            rng.random_range(1..=254)
+        )
+    },
+};
+
+pub(super) const MAC_ADDRESS: Simple = Simple {
+    name: "mac_address",
+    description: "A locally administered unicast MAC address",
+    detects: |header| {
+        has_word(header, &["mac"]) || contains_any(header, &["mac_address", "macaddress"])
+    },
+    generate: |rng| {
+        let mut bytes: [u8; 6] = rng.random();
+        bytes[0] = (bytes[0] & 0xfe) | 0x02;
+        let hex: Vec<String> = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        hex.join(":")
+    },
+};
+
+pub(super) const CURRENCY_CODE: Simple = Simple {
+    name: "currency_code",
+    description: "An ISO 4217 currency code such as \"EUR\"",
+    detects: |header| header.contains("currency"),
+    generate: |rng| {
+        const CURRENCIES: &[&str] = &[
+            "USD", "EUR", "GBP", "JPY", "CHF", "CAD", "AUD", "CNY", "INR", "BRL", "MXN", "SEK",
+            "NOK", "DKK", "PLN", "ZAR", "SGD", "HKD", "NZD", "KRW",
+        ];
+        pick(CURRENCIES, rng).to_string()
+    },
+};
+
+const TIMESTAMP_PARAMS: &[ParamSpec] = &[
+    ParamSpec {
+        name: "min",
+        kind: ParamKind::Date,
+        required: false,
+        description: "Earliest timestamp, YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS (default 2020-01-01)",
+    },
+    ParamSpec {
+        name: "max",
+        kind: ParamKind::Date,
+        required: false,
+        description: "Latest timestamp, YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS (default 2025-12-31)",
+    },
+    ParamSpec {
+        name: "format",
+        kind: ParamKind::String,
+        required: false,
+        description: "strftime format (default \"%Y-%m-%dT%H:%M:%SZ\")",
+    },
+];
+
+pub(super) struct Timestamp;
+
+impl ColumnType for Timestamp {
+    fn name(&self) -> &'static str {
+        "timestamp"
+    }
+
+    fn description(&self) -> &'static str {
+        "A date and time to the second, in UTC"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        TIMESTAMP_PARAMS
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        contains_any(header, &["timestamp", "datetime"]) || header.ends_with("_at")
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        let timestamps = params::timestamp_params(column)?;
+        if column.unique {
+            params::check_unique_timestamp_format(column, &timestamps)?;
+        }
+        Ok(Box::new(timestamps))
+    }
+}
+
+impl ColumnGenerator for TimestampParams {
+    fn generate(&self, _: usize, rng: &mut dyn RngCore) -> String {
+        self.sample(rng)
+    }
+
+    fn cardinality(&self) -> Option<u64> {
+        Some(self.cardinality())
+    }
+
+    fn nth_value(&self, n: u64) -> Option<String> {
+        self.nth_value(n)
+    }
+
+    fn kind(&self) -> Option<ValueKind> {
+        Some(ValueKind::DateTime)
+    }
+}
+
+const TIME_PARAMS: &[ParamSpec] = &[ParamSpec {
+    name: "format",
+    kind: ParamKind::String,
+    required: false,
+    description: "strftime format (default \"%H:%M:%S\")",
+}];
+
+pub(super) struct Time;
+
+impl ColumnType for Time {
+    fn name(&self) -> &'static str {
+        "time"
+    }
+
+    fn description(&self) -> &'static str {
+        "A time of day"
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        TIME_PARAMS
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        has_word(header, &["time"])
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        let times = params::time_params(column)?;
+        if column.unique {
+            params::check_unique_time_format(column, &times)?;
+        }
+        Ok(Box::new(times))
+    }
+}
+
+impl ColumnGenerator for TimeParams {
+    fn generate(&self, _: usize, rng: &mut dyn RngCore) -> String {
+        self.sample(rng)
+    }
+
+    fn cardinality(&self) -> Option<u64> {
+        Some(self.cardinality())
+    }
+
+    fn nth_value(&self, n: u64) -> Option<String> {
+        self.nth_value(n)
+    }
+}
+
+const COORDINATE_PARAMS: &[ParamSpec] = &[
+    ParamSpec {
+        name: "min",
+        kind: ParamKind::Number,
+        required: false,
+        description: "Smallest value in degrees",
+    },
+    ParamSpec {
+        name: "max",
+        kind: ParamKind::Number,
+        required: false,
+        description: "Largest value in degrees",
+    },
+    ParamSpec {
+        name: "precision",
+        kind: ParamKind::Integer,
+        required: false,
+        description: "Digits after the decimal point (default 6)",
+    },
+];
+
+/// Latitude or longitude in decimal degrees, up to `limit` either way.
+pub(super) struct Coordinate {
+    name: &'static str,
+    description: &'static str,
+    limit: f64,
+    words: &'static [&'static str],
+}
+
+pub(super) const LATITUDE: Coordinate = Coordinate {
+    name: "latitude",
+    description: "Degrees north (positive) or south, -90 to 90",
+    limit: 90.0,
+    words: &["latitude", "lat"],
+};
+
+pub(super) const LONGITUDE: Coordinate = Coordinate {
+    name: "longitude",
+    description: "Degrees east (positive) or west, -180 to 180",
+    limit: 180.0,
+    words: &["longitude", "lng", "lon"],
+};
+
+impl ColumnType for Coordinate {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        COORDINATE_PARAMS
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        has_word(header, self.words) || header.contains(self.words[0])
+    }
+
+    fn build(&self, column: &ColumnSpec) -> Built {
+        let default = MoneyParams {
+            min: -self.limit,
+            max: self.limit,
+            precision: 6,
+            currency: None,
+        };
+        let params = params::decimal_params(column, default)?;
+        if params.min < -self.limit || params.max > self.limit {
+            return Err(super::GeneratorError::InvalidParameter {
+                column: column.name.clone(),
+                message: format!(
+                    "{} must be between {} and {}",
+                    self.name, -self.limit, self.limit
+                ),
+            });
+        }
+        Ok(Box::new(params))
+    }
+}
+
+/// Countries whose IBANs are generated: code, then the letters and digits of
+/// the domestic account number (BBAN) in that order.
+const IBAN_FORMATS: &[(&str, usize, usize)] = &[
+    ("DE", 0, 18),
+    ("GB", 4, 14),
+    ("FR", 0, 23),
+    ("NL", 4, 10),
+    ("ES", 0, 20),
+];
+
+/// Remainder modulo 97 of an IBAN-style string, letters counting as 10 to 35.
+fn mod97(text: &str) -> u32 {
+    text.chars().fold(0, |remainder, c| match c.to_digit(36) {
+        Some(value) if value >= 10 => (remainder * 100 + value) % 97,
+        Some(value) => (remainder * 10 + value) % 97,
+        None => remainder,
+    })
+}
+
+fn generate_iban(rng: &mut (impl Rng + ?Sized)) -> String {
+    let (country, letters, digit_count) = IBAN_FORMATS[rng.random_range(0..IBAN_FORMATS.len())];
+    let mut bban: String = (0..letters)
+        .map(|_| char::from(b'A' + rng.random_range(0..26u8)))
+        .collect();
+    bban.push_str(&digits(digit_count, rng));
+    let check = 98 - mod97(&format!("{}{}00", bban, country));
+    format!("{}{:02}{}", country, check, bban)
+}
+
+/// The digit that makes `payload` followed by it pass the Luhn check.
+fn luhn_check_digit(payload: &str) -> u32 {
+    let sum: u32 = payload
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| {
+            if i % 2 == 0 {
+                let doubled = d * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                d
+            }
+        })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+fn generate_card_number(rng: &mut (impl Rng + ?Sized)) -> String {
+    // Visa, Mastercard, American Express and Discover prefixes and lengths.
+    const BRANDS: &[(&[&str], usize)] = &[
+        (&["4"], 16),
+        (&["51", "52", "53", "54", "55"], 16),
+        (&["34", "37"], 15),
+        (&["6011"], 16),
+    ];
+    let (prefixes, length) = BRANDS[rng.random_range(0..BRANDS.len())];
+    let mut number = pick(prefixes, rng).to_string();
+    number.push_str(&digits(length - number.len() - 1, rng));
+    let check = luhn_check_digit(&number);
+    format!("{}{}", number, check)
+}
+
+fn generate_isbn(rng: &mut (impl Rng + ?Sized)) -> String {
+    let mut isbn = pick(&["978", "979"], rng).to_string();
+    isbn.push_str(&digits(9, rng));
+    let sum: u32 = isbn
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { d } else { d * 3 })
+        .sum();
+    format!("{}{}", isbn, (10 - sum % 10) % 10)
+}
+
+pub(super) const IBAN: Simple = Simple {
+    name: "iban",
+    description: "An IBAN with valid check digits, from DE, GB, FR, NL or ES",
+    detects: |header| header.contains("iban"),
+    generate: |rng| generate_iban(rng),
+};
+
+pub(super) const CREDIT_CARD: Simple = Simple {
+    name: "credit_card",
+    description: "A Luhn-valid test card number (Visa, Mastercard, Amex or Discover)",
+    detects: |header| {
+        has_word(header, &["card"]) || contains_any(header, &["creditcard", "card_number"])
+    },
+    generate: |rng| generate_card_number(rng),
+};
+
+pub(super) const ISBN: Simple = Simple {
+    name: "isbn",
+    description: "An ISBN-13 with a valid check digit",
+    detects: |header| header.contains("isbn"),
+    generate: |rng| generate_isbn(rng),
+};
+
+pub(super) const JOB_TITLE: Simple = Simple {
+    name: "job_title",
+    description: "A job title such as \"Senior Data Analyst\"",
+    detects: |header| contains_any(header, &["job", "occupation", "profession"]),
+    generate: |rng| {
+        const LEVELS: &[&str] = &[
+            "",
+            "",
+            "Senior ",
+            "Junior ",
+            "Lead ",
+            "Principal ",
+            "Associate ",
+        ];
+        const FIELDS: &[&str] = &[
+            "Software",
+            "Data",
+            "Marketing",
+            "Sales",
+            "Product",
+            "Financial",
+            "Operations",
+            "Customer Success",
+            "Security",
+            "HR",
+        ];
+        const ROLES: &[&str] = &[
+            "Engineer",
+            "Analyst",
+            "Manager",
+            "Designer",
+            "Specialist",
+            "Consultant",
+            "Director",
+            "Coordinator",
+        ];
+        format!(
+            "{}{} {}",
+            pick(LEVELS, rng),
+            pick(FIELDS, rng),
+            pick(ROLES, rng)
+        )
+    },
+};
+
+pub(super) struct Color;
+
+impl ColumnType for Color {
+    fn name(&self) -> &'static str {
+        "color"
+    }
+
+    fn description(&self) -> &'static str {
+        "A hex colour such as \"#3a7bd5\""
+    }
+
+    fn detects(&self, header: &str) -> bool {
+        contains_any(header, &["color", "colour"])
+    }
+
+    fn build(&self, _: &ColumnSpec) -> Built {
+        Ok(Box::new(ColorValues))
+    }
+}
+
+struct ColorValues;
+
+impl ColumnGenerator for ColorValues {
+    fn generate(&self, _: usize, rng: &mut dyn RngCore) -> String {
+        format!("#{:06x}", rng.random_range(0..=0xffffffu32))
+    }
+
+    fn cardinality(&self) -> Option<u64> {
+        Some(0x1000000)
+    }
+
+    fn nth_value(&self, n: u64) -> Option<String> {
+        (n < 0x1000000).then(|| format!("#{:06x}", n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::registry::registry;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn detected(header: &str) -> &'static str {
+        registry().detect(header).unwrap().name()
+    }
+
+    fn values(type_name: &str, count: usize) -> Vec<String> {
+        let generator = registry()
+            .get(type_name)
+            .unwrap()
+            .build(&ColumnSpec::new("column", type_name))
+            .unwrap();
+        let mut rng = StdRng::seed_from_u64(11);
+        (1..=count)
+            .map(|i| generator.generate(i, &mut rng))
+            .collect()
+    }
+
+    #[test]
+    fn test_detection() {
+        let cases = [
+            ("uuid", "uuid"),
+            ("order_guid", "uuid"),
+            ("is_paid", "boolean"),
+            ("email_verified", "boolean"),
+            ("user_id", "id"),
+            ("avatar_url", "url"),
+            ("homepage", "url"),
+            ("user_agent", "user_agent"),
+            ("discount_percent", "percentage"),
+            ("hostname", "domain"),
+            ("username", "username"),
+            ("company_name", "company"),
+            ("employer", "company"),
+            ("CompanyName", "company"),
+            ("company_email", "email"),
+            ("organization_email", "email"),
+            ("business_phone", "phone"),
+            ("first_name", "name"),
+            ("password_hash", "password_hash"),
+            ("age", "age"),
+            ("state", "state"),
+            ("statement", "text"),
+            ("province", "state"),
+            ("client_ip", "ipv4"),
+            ("ipv6_address", "ipv6"),
+            ("ip_v6", "ipv6"),
+            ("ip6_address", "ipv6"),
+            ("client-ip-v6", "ipv6"),
+            ("mac_address", "mac_address"),
+            ("home_address", "street"),
+            ("zip", "postcode"),
+            ("currency", "currency_code"),
+            ("created_at", "timestamp"),
+            ("updated_at", "timestamp"),
+            ("event_datetime", "timestamp"),
+            ("start_time", "time"),
+            ("created_date", "date"),
+            ("lat", "latitude"),
+            ("longitude", "longitude"),
+            ("lng", "longitude"),
+            ("iban", "iban"),
+            ("card_number", "credit_card"),
+            ("isbn", "isbn"),
+            ("job_title", "job_title"),
+            ("favourite_colour", "color"),
+        ];
+        for (header, expected) in cases {
+            assert_eq!(detected(header), expected, "{}", header);
+        }
+    }
+
+    #[test]
+    fn test_checksummed_values() {
+        for iban in values("iban", 50) {
+            let (front, back) = iban.split_at(4);
+            assert_eq!(mod97(&format!("{}{}", back, front)), 1, "{}", iban);
+        }
+        for card in values("credit_card", 50) {
+            let (payload, check) = card.split_at(card.len() - 1);
+            assert_eq!(luhn_check_digit(payload).to_string(), check, "{}", card);
+            assert!(matches!(card.len(), 15 | 16));
+        }
+        assert_eq!(luhn_check_digit("411111111111111"), 1);
+        for isbn in values("isbn", 50) {
+            let sum: u32 = isbn
+                .chars()
+                .map(|c| c.to_digit(10).unwrap())
+                .enumerate()
+                .map(|(i, d)| if i % 2 == 0 { d } else { d * 3 })
+                .sum();
+            assert_eq!(sum % 10, 0, "{}", isbn);
+        }
+    }
+
+    #[test]
+    fn test_value_shapes() {
+        for ip in values("ipv4", 20) {
+            let octets: Vec<u8> = ip.split('.').map(|o| o.parse().unwrap()).collect();
+            assert_eq!(octets.len(), 4);
+        }
+        assert!(
+            values("ipv6", 20)
+                .iter()
+                .all(|ip| ip.starts_with("2001:db8:") && ip.split(':').count() == 8)
+        );
+        for mac in values("mac_address", 20) {
+            assert_eq!(mac.len(), 17);
+            assert_eq!(u8::from_str_radix(&mac[..2], 16).unwrap() & 0b11, 0b10);
+        }
+        assert!(values("uuid", 5).iter().all(|uuid| &uuid[14..15] == "4"));
+        assert!(
+            values("boolean", 20)
+                .iter()
+                .all(|b| b == "true" || b == "false")
+        );
+        assert!(
+            values("color", 20)
+                .iter()
+                .all(|c| c.len() == 7 && c.starts_with('#'))
+        );
+        assert!(
+            values("url", 20)
+                .iter()
+                .all(|u| u.starts_with("https://www."))
+        );
+        assert!(values("password_hash", 5).iter().all(|h| h.len() == 60));
+        assert!(values("username", 20).iter().all(|u| !u.contains(' ')));
+        for timestamp in values("timestamp", 20) {
+            assert!(
+                chrono::NaiveDateTime::parse_from_str(&timestamp, "%Y-%m-%dT%H:%M:%SZ").is_ok()
+            );
+        }
+        for time in values("time", 20) {
+            assert!(chrono::NaiveTime::parse_from_str(&time, "%H:%M:%S").is_ok());
+        }
+        for latitude in values("latitude", 20) {
+            let value: f64 = latitude.parse().unwrap();
+            assert!((-90.0..=90.0).contains(&value));
+            assert_eq!(latitude.split('.').nth(1).unwrap().len(), 6);
+        }
+    }
+
+    #[test]
+    fn test_params() {
+        let build = |column: ColumnSpec| {
+            registry()
+                .get(&column.type_name)
+                .unwrap()
+                .build(&column)
+                .map(|_| ())
+        };
+        let message = |column: ColumnSpec| match build(column) {
+            Err(super::super::GeneratorError::InvalidParameter { message, .. }) => message,
+            other => panic!("expected InvalidParameter, got {:?}", other.is_ok()),
+        };
+        assert_eq!(
+            message(ColumnSpec {
+                max: Some(crate::schema::Bound::Number(95.0)),
+                ..ColumnSpec::new("lat", "latitude")
+            }),
+            "latitude must be between -90 and 90"
+        );
+        assert_eq!(
+            message(ColumnSpec {
+                min: Some(crate::schema::Bound::Text("2024-13-01".to_string())),
+                ..ColumnSpec::new("created_at", "timestamp")
+            }),
+            "min must be a date or timestamp (YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS), got '2024-13-01'"
+        );
+        assert_eq!(
+            message(ColumnSpec {
+                format: Some("%Y-%m-%dT%H:%M:%S%z".to_string()),
+                ..ColumnSpec::new("created_at", "timestamp")
+            }),
+            "timestamp format '%Y-%m-%dT%H:%M:%S%z' asks for fields a timestamp does not have"
+        );
+        assert_eq!(
+            message(ColumnSpec {
+                format: Some("%Y %H:%M".to_string()),
+                ..ColumnSpec::new("start_time", "time")
+            }),
+            "time format '%Y %H:%M' asks for fields a time does not have"
+        );
+
+        let column = ColumnSpec {
+            min: Some(crate::schema::Bound::Text(
+                "2024-03-01T08:00:00".to_string(),
+            )),
+            max: Some(crate::schema::Bound::Text("2024-03-01".to_string())),
+            format: Some("%H:%M".to_string()),
+            ..ColumnSpec::new("created_at", "timestamp")
+        };
+        let generator = registry().get("timestamp").unwrap().build(&column).unwrap();
+        assert_eq!(generator.cardinality(), Some(16 * 60));
+        assert_eq!(generator.nth_value(0).unwrap(), "08:00");
+        assert_eq!(generator.nth_value(1).unwrap(), "08:01");
+        assert_eq!(generator.nth_value(959).unwrap(), "23:59");
+        assert_eq!(generator.nth_value(960), None);
+
+        let hours = ColumnSpec {
+            format: Some("%H".to_string()),
+            unique: true,
+            ..ColumnSpec::new("start_time", "time")
+        };
+        let generator = registry().get("time").unwrap().build(&hours).unwrap();
+        assert_eq!(generator.cardinality(), Some(24));
+        let all: Vec<String> = (0..24).map(|n| generator.nth_value(n).unwrap()).collect();
+        assert_eq!(all.first().unwrap(), "00");
+        assert_eq!(all.last().unwrap(), "23");
+        assert_eq!(generator.nth_value(24), None);
+
+        for (type_name, format) in [("time", "%M:%S"), ("time", "%I:%M"), ("timestamp", "%H:%M")] {
+            assert_eq!(
+                message(ColumnSpec {
+                    format: Some(format.to_string()),
+                    unique: true,
+                    ..ColumnSpec::new("start", type_name)
+                }),
+                format!(
+                    "{} format '{}' repeats values, so the column cannot be unique",
+                    type_name, format
+                )
+            );
+        }
+
+        let mut rng = StdRng::seed_from_u64(4);
+        let german = ColumnSpec {
+            locale: Some("de_DE".to_string()),
+            ..ColumnSpec::new("bundesland", "state")
+        };
+        let state = registry()
+            .get("state")
+            .unwrap()
+            .build(&german)
+            .unwrap()
+            .generate(1, &mut rng);
+        assert!(locale::find("de_DE").unwrap().states.contains(&state));
+    }
+}
@@ -0,0 +1,1069 @@
+//! The expression language of `computed` columns: a value in terms of other
+//! columns of the same row and the row number, e.g. `price * quantity`,
+//! `first_name + " " + last_name` or `if(age >= 18, "adult", "minor")`.
+//!
+//! Column names are bare words, or wrapped in backticks when they hold other
+//! characters or clash with a keyword (`` `unit price` * qty ``). Cells are
+//! text; operators read them as numbers or `YYYY-MM-DD` dates when they can.
+//! Anything that cannot be evaluated, such as `"abc" * 2`, is null, and null
+//! operands make the result null.
+
+use super::registry::RowValues;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{Datelike, Days, NaiveDate};
+use std::fmt::Write;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Largest `round()` precision and `{:.N}` placeholder.
+const MAX_DECIMALS: usize = 10;
+
+/// Widest `{:N}` placeholder, so a template cannot pad a cell to gigabytes.
+const MAX_WIDTH: usize = 10_000;
+
+/// A number and the decimal places it was written with, so a result can be
+/// shown as precisely as its inputs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Number {
+    pub value: f64,
+    pub decimals: usize,
+}
+
+impl Number {
+    fn whole(value: i64) -> Self {
+        Number {
+            value: value as f64,
+            decimals: 0,
+        }
+    }
+
+    /// Reads a cell as a number, ignoring a currency symbol around it, e.g.
+    /// "$12.50" or "12.50 €".
+    pub fn parse(text: &str) -> Option<Number> {
+        let symbol = |c: char| !(c.is_alphanumeric() || "+-.".contains(c));
+        let text = text.trim_start_matches(symbol).trim_end_matches(symbol);
+        let value: f64 = text.parse().ok()?;
+        let decimals = text
+            .split_once('.')
+            .map_or(0, |(_, fraction)| fraction.len());
+        value.is_finite().then_some(Number { value, decimals })
+    }
+
+    pub fn format(&self, decimals: usize) -> String {
+        format!("{:.*}", decimals, self.value)
+    }
+}
+
+/// The result of evaluating an expression.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Value {
+    Null,
+    Number(Number),
+    Text(String),
+    Date(NaiveDate),
+    Bool(bool),
+}
+
+impl Value {
+    fn number(&self) -> Option<Number> {
+        match self {
+            Value::Number(number) => Some(*number),
+            Value::Text(text) => Number::parse(text),
+            _ => None,
+        }
+    }
+
+    fn date(&self) -> Option<NaiveDate> {
+        match self {
+            Value::Date(date) => Some(*date),
+            Value::Text(text) => NaiveDate::parse_from_str(text.trim(), DATE_FORMAT).ok(),
+            _ => None,
+        }
+    }
+
+    fn is_true(&self) -> bool {
+        match self {
+            Value::Null => false,
+            Value::Number(number) => number.value != 0.0,
+            Value::Text(text) => !text.is_empty(),
+            Value::Date(_) => true,
+            Value::Bool(value) => *value,
+        }
+    }
+
+    /// The cell text of the value: numbers with their decimal places, dates
+    /// as `YYYY-MM-DD`, and an empty cell for null.
+    pub fn to_text(&self) -> String {
+        match self {
+            Value::Null => String::new(),
+            Value::Number(number) => number.format(number.decimals),
+            Value::Text(text) => text.clone(),
+            Value::Date(date) => date.format(DATE_FORMAT).to_string(),
+            Value::Bool(value) => value.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum UnaryOp {
+    Negate,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Function {
+    Upper,
+    Lower,
+    Trim,
+    Length,
+    Str,
+    Round,
+    Abs,
+    Min,
+    Max,
+    If,
+    Coalesce,
+    Date,
+    Year,
+    Month,
+    Day,
+    Row,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "upper" => Function::Upper,
+            "lower" => Function::Lower,
+            "trim" => Function::Trim,
+            "length" => Function::Length,
+            "str" => Function::Str,
+            "round" => Function::Round,
+            "abs" => Function::Abs,
+            "min" => Function::Min,
+            "max" => Function::Max,
+            "if" => Function::If,
+            "coalesce" => Function::Coalesce,
+            "date" => Function::Date,
+            "year" => Function::Year,
+            "month" => Function::Month,
+            "day" => Function::Day,
+            "row" => Function::Row,
+            _ => return None,
+        })
+    }
+
+    /// The fewest and most arguments, `None` for any number.
+    fn arity(self) -> (usize, Option<usize>) {
+        match self {
+            Function::Row => (0, Some(0)),
+            Function::Upper
+            | Function::Lower
+            | Function::Trim
+            | Function::Length
+            | Function::Str
+            | Function::Abs
+            | Function::Year
+            | Function::Month
+            | Function::Day => (1, Some(1)),
+            Function::Round | Function::Date => (1, Some(2)),
+            Function::If => (2, Some(3)),
+            Function::Min | Function::Max | Function::Coalesce => (1, None),
+        }
+    }
+}
+
+/// One part of a `format()` template.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Piece {
+    Literal(String),
+    Placeholder(Spec),
+}
+
+/// How a `format()` placeholder shows its value: `{}`, `{:5}` (width),
+/// `{:05}` (zero-padded), `{:.2}` (decimals) or `{:%d/%m/%Y}` (a date).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(super) struct Spec {
+    width: usize,
+    zero_pad: bool,
+    decimals: Option<usize>,
+    date_format: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Expr {
+    Literal(Value),
+    Column(String),
+    Unary(UnaryOp, Box<Expr>),
+    Binary {
+        op: BinaryOp,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    Call(Function, Vec<Expr>),
+    Format(Vec<Piece>, Vec<Expr>),
+}
+
+impl Expr {
+    pub fn parse(text: &str) -> Result<Expr, String> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            position: 0,
+        };
+        let expr = parser.or()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some((token, at)) => Err(format!("unexpected {} at position {}", token, at)),
+        }
+    }
+
+    /// The columns the expression reads, each once, in order of appearance.
+    pub fn columns(&self) -> Vec<String> {
+        let mut columns = Vec::new();
+        self.collect_columns(&mut columns);
+        columns
+    }
+
+    fn collect_columns(&self, columns: &mut Vec<String>) {
+        match self {
+            Expr::Literal(_) => {}
+            Expr::Column(name) => {
+                if !columns.contains(name) {
+                    columns.push(name.clone());
+                }
+            }
+            Expr::Unary(_, inner) => inner.collect_columns(columns),
+            Expr::Binary { left, right, .. } => {
+                left.collect_columns(columns);
+                right.collect_columns(columns);
+            }
+            Expr::Call(_, args) | Expr::Format(_, args) => {
+                for arg in args {
+                    arg.collect_columns(columns);
+                }
+            }
+        }
+    }
+
+    /// The value for row number `index`, whose cells are in `row`.
+    pub fn evaluate(&self, index: usize, row: &RowValues) -> Value {
+        match self {
+            Expr::Literal(value) => value.clone(),
+            Expr::Column(name) => row
+                .get(name)
+                .map_or(Value::Null, |cell| Value::Text(cell.to_string())),
+            Expr::Unary(UnaryOp::Not, inner) => Value::Bool(!inner.evaluate(index, row).is_true()),
+            Expr::Unary(UnaryOp::Negate, inner) => match inner.evaluate(index, row).number() {
+                Some(number) => Value::Number(Number {
+                    value: -number.value,
+                    ..number
+                }),
+                None => Value::Null,
+            },
+            Expr::Binary {
+                op: BinaryOp::And,
+                left,
+                right,
+            } => Value::Bool(
+                left.evaluate(index, row).is_true() && right.evaluate(index, row).is_true(),
+            ),
+            Expr::Binary {
+                op: BinaryOp::Or,
+                left,
+                right,
+            } => Value::Bool(
+                left.evaluate(index, row).is_true() || right.evaluate(index, row).is_true(),
+            ),
+            Expr::Binary { op, left, right } => {
+                binary(*op, left.evaluate(index, row), right.evaluate(index, row))
+            }
+            Expr::Call(function, args) => call(*function, args, index, row),
+            Expr::Format(pieces, args) => {
+                let mut args = args.iter().map(|arg| arg.evaluate(index, row));
+                let mut text = String::new();
+                for piece in pieces {
+                    match piece {
+                        Piece::Literal(literal) => text.push_str(literal),
+                        Piece::Placeholder(spec) => {
+                            match spec.apply(&args.next().unwrap_or(Value::Null)) {
+                                Some(formatted) => text.push_str(&formatted),
+                                None => return Value::Null,
+                            }
+                        }
+                    }
+                }
+                Value::Text(text)
+            }
+        }
+    }
+}
+
+fn binary(op: BinaryOp, left: Value, right: Value) -> Value {
+    use BinaryOp::*;
+
+    if matches!(op, Equal | NotEqual) {
+        let equal = match (&left, &right) {
+            (Value::Null, Value::Null) => true,
+            (Value::Null, _) | (_, Value::Null) => false,
+            _ => compare(&left, &right).is_eq(),
+        };
+        return Value::Bool(equal == (op == Equal));
+    }
+    if left == Value::Null || right == Value::Null {
+        return Value::Null;
+    }
+    if matches!(op, Less | LessOrEqual | Greater | GreaterOrEqual) {
+        let ordering = compare(&left, &right);
+        return Value::Bool(match op {
+            Less => ordering.is_lt(),
+            LessOrEqual => ordering.is_le(),
+            Greater => ordering.is_gt(),
+            _ => ordering.is_ge(),
+        });
+    }
+
+    // Dates move by whole days, and two dates subtract to the days between.
+    if let (Some(date), Some(days)) = (left.date(), right.number())
+        && matches!(op, Add | Subtract)
+    {
+        if days.value.fract() != 0.0 {
+            return Value::Null;
+        }
+        let days = if op == Add { days.value } else { -days.value };
+        let moved = if days >= 0.0 {
+            date.checked_add_days(Days::new(days as u64))
+        } else {
+            date.checked_sub_days(Days::new(-days as u64))
+        };
+        return moved.map_or(Value::Null, Value::Date);
+    }
+    if op == Subtract
+        && let (Some(later), Some(earlier)) = (left.date(), right.date())
+    {
+        return Value::Number(Number::whole((later - earlier).num_days()));
+    }
+
+    match (left.number(), right.number()) {
+        (Some(left), Some(right)) => arithmetic(op, left, right),
+        _ if op == Add => Value::Text(left.to_text() + &right.to_text()),
+        _ => Value::Null,
+    }
+}
+
+/// Results keep the most decimal places of any operand, and at least two
+/// after a division.
+fn arithmetic(op: BinaryOp, left: Number, right: Number) -> Value {
+    let decimals = left.decimals.max(right.decimals);
+    let (value, decimals) = match op {
+        BinaryOp::Add => (left.value + right.value, decimals),
+        BinaryOp::Subtract => (left.value - right.value, decimals),
+        BinaryOp::Multiply => (left.value * right.value, decimals),
+        BinaryOp::Divide | BinaryOp::Remainder if right.value == 0.0 => return Value::Null,
+        BinaryOp::Divide => (left.value / right.value, decimals.max(2)),
+        BinaryOp::Remainder => (left.value % right.value, decimals),
+        _ => return Value::Null,
+    };
+    if value.is_finite() {
+        Value::Number(Number { value, decimals })
+    } else {
+        Value::Null
+    }
+}
+
+/// Numbers compare as numbers and dates as dates; anything else as text.
+fn compare(left: &Value, right: &Value) -> std::cmp::Ordering {
+    if let (Some(left), Some(right)) = (left.number(), right.number()) {
+        return left.value.total_cmp(&right.value);
+    }
+    if let (Some(left), Some(right)) = (left.date(), right.date()) {
+        return left.cmp(&right);
+    }
+    left.to_text().cmp(&right.to_text())
+}
+
+fn call(function: Function, args: &[Expr], index: usize, row: &RowValues) -> Value {
+    let arg = |n: usize| {
+        args.get(n)
+            .map_or(Value::Null, |arg| arg.evaluate(index, row))
+    };
+    let text = |map: fn(&str) -> String| match arg(0) {
+        Value::Null => Value::Null,
+        value => Value::Text(map(&value.to_text())),
+    };
+    let date_part = |part: fn(NaiveDate) -> i64| {
+        arg(0)
+            .date()
+            .map_or(Value::Null, |date| Value::Number(Number::whole(part(date))))
+    };
+
+    match function {
+        Function::Upper => text(str::to_uppercase),
+        Function::Lower => text(str::to_lowercase),
+        Function::Trim => text(|s| s.trim().to_string()),
+        Function::Str => text(str::to_string),
+        Function::Length => match arg(0) {
+            Value::Null => Value::Null,
+            value => Value::Number(Number::whole(value.to_text().chars().count() as i64)),
+        },
+        Function::Abs => arg(0).number().map_or(Value::Null, |n| {
+            Value::Number(Number {
+                value: n.value.abs(),
+                ..n
+            })
+        }),
+        Function::Round => {
+            let decimals = match args.len() {
+                1 => 0,
+                _ => match arg(1).number() {
+                    Some(digits) => (digits.value.max(0.0) as usize).min(MAX_DECIMALS),
+                    None => return Value::Null,
+                },
+            };
+            let scale = 10f64.powi(decimals as i32);
+            arg(0).number().map_or(Value::Null, |n| {
+                Value::Number(Number {
+                    value: (n.value * scale).round() / scale,
+                    decimals,
+                })
+            })
+        }
+        Function::Min | Function::Max => {
+            let mut best: Option<Number> = None;
+            for n in 0..args.len() {
+                let Some(number) = arg(n).number() else {
+                    return Value::Null;
+                };
+                let better = best.is_none_or(|best| {
+                    if function == Function::Min {
+                        number.value < best.value
+                    } else {
+                        number.value > best.value
+                    }
+                });
+                if better {
+                    best = Some(number);
+                }
+            }
+            best.map_or(Value::Null, Value::Number)
+        }
+        Function::If => {
+            if arg(0).is_true() {
+                arg(1)
+            } else {
+                arg(2)
+            }
+        }
+        Function::Coalesce => (0..args.len())
+            .map(arg)
+            .find(|value| *value != Value::Null)
+            .unwrap_or(Value::Null),
+        Function::Date => {
+            let value = arg(0);
+            let parsed = match args.len() {
+                1 => value.date(),
+                _ => match arg(1) {
+                    Value::Text(format) => {
+                        NaiveDate::parse_from_str(value.to_text().trim(), &format).ok()
+                    }
+                    _ => None,
+                },
+            };
+            parsed.map_or(Value::Null, Value::Date)
+        }
+        Function::Year => date_part(|date| date.year() as i64),
+        Function::Month => date_part(|date| date.month() as i64),
+        Function::Day => date_part(|date| date.day() as i64),
+        Function::Row => Value::Number(Number::whole(index as i64)),
+    }
+}
+
+impl Spec {
+    fn parse(spec: &str) -> Result<Spec, String> {
+        if spec.starts_with('%') {
+            check_date_format(spec)?;
+            return Ok(Spec {
+                date_format: Some(spec.to_string()),
+                ..Spec::default()
+            });
+        }
+        let invalid = || format!("invalid placeholder '{{:{}}}'", spec);
+        let (width, decimals) = match spec.split_once('.') {
+            Some((width, decimals)) => {
+                let decimals: usize = decimals.parse().map_err(|_| invalid())?;
+                if decimals > MAX_DECIMALS {
+                    return Err(invalid());
+                }
+                (width, Some(decimals))
+            }
+            None => (spec, None),
+        };
+        let zero_pad = width.starts_with('0');
+        let width = match width {
+            "" => 0,
+            width => width.parse().map_err(|_| invalid())?,
+        };
+        if width > MAX_WIDTH {
+            return Err(format!(
+                "placeholder '{{:{}}}' is wider than {} characters",
+                spec, MAX_WIDTH
+            ));
+        }
+        Ok(Spec {
+            width,
+            zero_pad,
+            decimals,
+            date_format: None,
+        })
+    }
+
+    /// The value as the placeholder shows it, or `None` when it is null or
+    /// of the wrong kind for the placeholder.
+    fn apply(&self, value: &Value) -> Option<String> {
+        let text = match (value, &self.date_format, self.decimals) {
+            (Value::Null, _, _) => return None,
+            (value, Some(format), _) => value.date()?.format(format).to_string(),
+            (value, None, Some(decimals)) => value.number()?.format(decimals),
+            (value, None, None) => value.to_text(),
+        };
+        let padding = self.width.saturating_sub(text.chars().count());
+        if padding == 0 {
+            return Some(text);
+        }
+        if !self.zero_pad {
+            return Some(" ".repeat(padding) + &text);
+        }
+        let (sign, digits) = match text.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", text.as_str()),
+        };
+        Some(format!("{}{}{}", sign, "0".repeat(padding), digits))
+    }
+}
+
+/// Splits a `format()` template into text and `{}` placeholders. `{{` and
+/// `}}` stand for literal braces.
+fn parse_template(template: &str) -> Result<Vec<Piece>, String> {
+    let mut pieces = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut inner = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => inner.push(c),
+                        None => return Err("unclosed '{' in format template".to_string()),
+                    }
+                }
+                let spec = match inner.strip_prefix(':') {
+                    Some(spec) => Spec::parse(spec)?,
+                    None if inner.is_empty() => Spec::default(),
+                    None => return Err(format!("invalid placeholder '{{{}}}'", inner)),
+                };
+                if !literal.is_empty() {
+                    pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+                }
+                pieces.push(Piece::Placeholder(spec));
+            }
+            '}' => return Err("unmatched '}' in format template".to_string()),
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        pieces.push(Piece::Literal(literal));
+    }
+    Ok(pieces)
+}
+
+/// Fails on a malformed strftime format, or one that asks for fields a date
+/// lacks, such as `%H`, which would make formatting panic.
+pub(super) fn check_date_format(format: &str) -> Result<(), String> {
+    if format.is_empty() || StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(format!("invalid date format '{}'", format));
+    }
+    if write!(String::new(), "{}", NaiveDate::MIN.format(format)).is_err() {
+        return Err(format!(
+            "date format '{}' asks for fields a date does not have",
+            format
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(Number),
+    Text(String),
+    Word(String),
+    Quoted(String),
+    Symbol(&'static str),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(number) => write!(f, "number {}", number.format(number.decimals)),
+            Token::Text(text) => write!(f, "string \"{}\"", text),
+            Token::Word(name) | Token::Quoted(name) => write!(f, "column '{}'", name),
+            Token::Symbol(symbol) => write!(f, "'{}'", symbol),
+        }
+    }
+}
+
+/// Two-character symbols first, so "<=" is not read as "<" and "=".
+const SYMBOLS: &[&str] = &[
+    "==", "!=", "<=", ">=", "&&", "||", "+", "-", "*", "/", "%", "<", ">", "!", "(", ")", ",",
+];
+
+/// Tokens with their 1-based character positions.
+fn tokenize(text: &str) -> Result<Vec<(Token, usize)>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let at = i + 1;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let literal: String = chars[start..i].iter().collect();
+            let number = Number::parse(&literal)
+                .filter(|_| literal.matches('.').count() <= 1 && !literal.ends_with('.'))
+                .ok_or_else(|| format!("invalid number '{}' at position {}", literal, at))?;
+            tokens.push((Token::Number(number), at));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Word(chars[start..i].iter().collect()), at));
+        } else if c == '`' {
+            let Some(length) = chars[i + 1..].iter().position(|&c| c == '`') else {
+                return Err(format!("unclosed '`' at position {}", at));
+            };
+            let name: String = chars[i + 1..i + 1 + length].iter().collect();
+            if name.is_empty() {
+                return Err(format!("empty column name at position {}", at));
+            }
+            tokens.push((Token::Quoted(name), at));
+            i += length + 2;
+        } else if c == '"' || c == '\'' {
+            let mut text = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err(format!("unclosed string at position {}", at)),
+                    Some(&quote) if quote == c => break,
+                    Some('\\') => {
+                        match chars.get(i + 1) {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some(&escaped) => text.push(escaped),
+                            None => return Err(format!("unclosed string at position {}", at)),
+                        }
+                        i += 1;
+                    }
+                    Some(&other) => text.push(other),
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push((Token::Text(text), at));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) else {
+                return Err(format!("unexpected '{}' at position {}", c, at));
+            };
+            tokens.push((Token::Symbol(symbol), at));
+            i += symbol.len();
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent, loosest binding first: `||`, `&&`, comparisons,
+/// `+ -`, `* / %`, then unary `-` and `!`.
+struct Parser<'a> {
+    tokens: &'a [(Token, usize)],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&(Token, usize)> {
+        self.tokens.get(self.position)
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some((Token::Symbol(s), _)) if *s == symbol) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// One left-associative level: operands from `next`, joined by any of
+    /// `ops`.
+    fn level(
+        &mut self,
+        ops: &[(&str, BinaryOp)],
+        next: fn(&mut Self) -> Result<Expr, String>,
+    ) -> Result<Expr, String> {
+        let mut expr = next(self)?;
+        'operators: loop {
+            for (symbol, op) in ops {
+                if self.eat(symbol) {
+                    expr = Expr::Binary {
+                        op: *op,
+                        left: Box::new(expr),
+                        right: Box::new(next(self)?),
+                    };
+                    continue 'operators;
+                }
+            }
+            return Ok(expr);
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        self.level(&[("||", BinaryOp::Or)], Self::and)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        self.level(&[("&&", BinaryOp::And)], Self::comparison)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        self.level(
+            &[
+                ("==", BinaryOp::Equal),
+                ("!=", BinaryOp::NotEqual),
+                ("<=", BinaryOp::LessOrEqual),
+                (">=", BinaryOp::GreaterOrEqual),
+                ("<", BinaryOp::Less),
+                (">", BinaryOp::Greater),
+            ],
+            Self::sum,
+        )
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        self.level(
+            &[("+", BinaryOp::Add), ("-", BinaryOp::Subtract)],
+            Self::product,
+        )
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        self.level(
+            &[
+                ("*", BinaryOp::Multiply),
+                ("/", BinaryOp::Divide),
+                ("%", BinaryOp::Remainder),
+            ],
+            Self::unary,
+        )
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat("-") {
+            return Ok(Expr::Unary(UnaryOp::Negate, Box::new(self.unary()?)));
+        }
+        if self.eat("!") {
+            return Ok(Expr::Unary(UnaryOp::Not, Box::new(self.unary()?)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        let Some((token, at)) = self.peek().cloned() else {
+            return Err("unexpected end of expression".to_string());
+        };
+        self.position += 1;
+        match token {
+            Token::Number(number) => Ok(Expr::Literal(Value::Number(number))),
+            Token::Text(text) => Ok(Expr::Literal(Value::Text(text))),
+            Token::Quoted(name) => Ok(Expr::Column(name)),
+            Token::Word(word) if self.eat("(") => self.call(&word, at),
+            Token::Word(word) => Ok(match word.as_str() {
+                "true" => Expr::Literal(Value::Bool(true)),
+                "false" => Expr::Literal(Value::Bool(false)),
+                "null" => Expr::Literal(Value::Null),
+                _ => Expr::Column(word),
+            }),
+            Token::Symbol("(") => {
+                let expr = self.or()?;
+                if self.eat(")") {
+                    Ok(expr)
+                } else {
+                    Err(format!("missing ')' for the '(' at position {}", at))
+                }
+            }
+            token => Err(format!("unexpected {} at position {}", token, at)),
+        }
+    }
+
+    /// A function call, after its opening parenthesis.
+    fn call(&mut self, name: &str, at: usize) -> Result<Expr, String> {
+        let mut args = Vec::new();
+        if !self.eat(")") {
+            loop {
+                args.push(self.or()?);
+                if self.eat(")") {
+                    break;
+                }
+                if !self.eat(",") {
+                    return Err(format!("missing ')' for the call at position {}", at));
+                }
+            }
+        }
+
+        if name == "format" {
+            let Some(Expr::Literal(Value::Text(template))) = args.first() else {
+                return Err(format!(
+                    "format() at position {} needs a string template first",
+                    at
+                ));
+            };
+            let pieces = parse_template(template)?;
+            let placeholders = pieces
+                .iter()
+                .filter(|piece| matches!(piece, Piece::Placeholder(_)))
+                .count();
+            if placeholders != args.len() - 1 {
+                return Err(format!(
+                    "format() at position {} has {} placeholders but {} values",
+                    at,
+                    placeholders,
+                    args.len() - 1
+                ));
+            }
+            return Ok(Expr::Format(pieces, args.split_off(1)));
+        }
+
+        let function = Function::from_name(name)
+            .ok_or_else(|| format!("unknown function '{}' at position {}", name, at))?;
+        let (min, max) = function.arity();
+        if args.len() < min || max.is_some_and(|max| args.len() > max) {
+            let expected = match max {
+                Some(max) if max == min => min.to_string(),
+                Some(max) => format!("{} to {}", min, max),
+                None => format!("at least {}", min),
+            };
+            let noun = if expected.ends_with(" 1") || expected == "1" {
+                "argument"
+            } else {
+                "arguments"
+            };
+            return Err(format!(
+                "{}() takes {} {}, got {}",
+                name,
+                expected,
+                noun,
+                args.len()
+            ));
+        }
+        Ok(Expr::Call(function, args))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate_at(text: &str, index: usize, row: &[(&str, &str)]) -> String {
+        let headers: Vec<String> = row.iter().map(|(name, _)| name.to_string()).collect();
+        let cells: Vec<Option<String>> = row.iter().map(|(_, v)| Some(v.to_string())).collect();
+        Expr::parse(text)
+            .unwrap()
+            .evaluate(index, &RowValues::new(&headers, &cells))
+            .to_text()
+    }
+
+    fn evaluate(text: &str, row: &[(&str, &str)]) -> String {
+        evaluate_at(text, 1, row)
+    }
+
+    #[test]
+    fn test_evaluate_arithmetic() {
+        let row = [("price", "$12.50"), ("quantity", "3"), ("unit price", "2")];
+        assert_eq!(evaluate("price * quantity", &row), "37.50");
+        assert_eq!(evaluate("1 + 2 * 3", &row), "7");
+        assert_eq!(evaluate("(1 + 2) * -3", &row), "-9");
+        assert_eq!(evaluate("10 / 4", &row), "2.50");
+        assert_eq!(evaluate("10 / 3.000", &row), "3.333");
+        assert_eq!(evaluate("10 % 4", &row), "2");
+        assert_eq!(evaluate("`unit price` - quantity", &row), "-1");
+        assert_eq!(evaluate("price / (quantity - 3)", &row), "");
+        assert_eq!(evaluate("price + missing", &row), "");
+    }
+
+    #[test]
+    fn test_evaluate_text() {
+        let row = [("first", "Anna"), ("last", "Smith"), ("zip", "01234")];
+        assert_eq!(evaluate(r#"first + " " + last"#, &row), "Anna Smith");
+        assert_eq!(
+            evaluate("upper(last) + ', ' + lower(first)", &row),
+            "SMITH, anna"
+        );
+        assert_eq!(evaluate(r#"zip + "-" + 9"#, &row), "01234-9");
+        assert_eq!(evaluate("length(trim('  ab '))", &row), "2");
+        assert_eq!(evaluate(r#""say \"hi\"""#, &row), "say \"hi\"");
+        assert_eq!(evaluate("first * 2", &row), "");
+    }
+
+    #[test]
+    fn test_evaluate_dates() {
+        let row = [("ordered", "2024-02-27"), ("shipped", "2024-03-02")];
+        assert_eq!(evaluate("ordered + 3", &row), "2024-03-01");
+        assert_eq!(evaluate("ordered - -3", &row), "2024-03-01");
+        assert_eq!(evaluate("shipped - 2", &row), "2024-02-29");
+        assert_eq!(evaluate("shipped - ordered", &row), "4");
+        assert_eq!(
+            evaluate("year(ordered) * 100 + month(ordered)", &row),
+            "202402"
+        );
+        assert_eq!(
+            evaluate("date('27/02/2024', '%d/%m/%Y') == ordered", &row),
+            "true"
+        );
+        assert_eq!(evaluate("ordered + 1.5", &row), "");
+    }
+
+    #[test]
+    fn test_evaluate_conditionals() {
+        let row = [("age", "17"), ("plan", "pro"), ("note", "")];
+        assert_eq!(evaluate("if(age >= 18, 'adult', 'minor')", &row), "minor");
+        assert_eq!(evaluate("if(plan == 'pro' && age > 10, 1, 0)", &row), "1");
+        assert_eq!(
+            evaluate("if(!(plan != 'pro') || false, 'yes')", &row),
+            "yes"
+        );
+        assert_eq!(evaluate("if(age > 18, 'adult')", &row), "");
+        assert_eq!(evaluate("coalesce(note, plan)", &row), "pro");
+        assert_eq!(evaluate("note == null", &row), "true");
+        assert_eq!(evaluate("'9' < '10'", &row), "true");
+        assert_eq!(evaluate("'b' > 'a'", &row), "true");
+        assert_eq!(evaluate("min(age, 3, 40) + max(1, 2.5)", &row), "5.5");
+        assert_eq!(evaluate("round(2 / 3, 3) + abs(-1)", &row), "1.667");
+    }
+
+    #[test]
+    fn test_evaluate_format_and_row() {
+        let row = [("price", "3.5"), ("joined", "2024-07-04")];
+        assert_eq!(
+            evaluate_at("format('ORD-{:05}', row())", 42, &row),
+            "ORD-00042"
+        );
+        assert_eq!(
+            evaluate("format('{} costs {:.2}', 'tea', price)", &row),
+            "tea costs 3.50"
+        );
+        assert_eq!(
+            evaluate("format('{:%d/%m/%Y}', joined)", &row),
+            "04/07/2024"
+        );
+        assert_eq!(evaluate("format('[{:4}] {{x}}', 7)", &row), "[   7] {x}");
+        assert_eq!(evaluate("format('{:06.1}', -2)", &row), "-002.0");
+        assert_eq!(evaluate("format('{}', missing)", &row), "");
+    }
+
+    #[test]
+    fn test_columns_in_order() {
+        let expr = Expr::parse("b * (a + b) - `c d` + if(row() > 1, e, 'x')").unwrap();
+        assert_eq!(expr.columns(), vec!["b", "a", "c d", "e"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |text: &str| Expr::parse(text).unwrap_err();
+        assert_eq!(error("price *"), "unexpected end of expression");
+        assert_eq!(
+            error("price quantity"),
+            "unexpected column 'quantity' at position 7"
+        );
+        assert_eq!(error("(price + 1"), "missing ')' for the '(' at position 1");
+        assert_eq!(error("price ^ 2"), "unexpected '^' at position 7");
+        assert_eq!(error("1.2.3"), "invalid number '1.2.3' at position 1");
+        assert_eq!(error("`unit price"), "unclosed '`' at position 1");
+        assert_eq!(error("'abc"), "unclosed string at position 1");
+        assert_eq!(
+            error("shout(name)"),
+            "unknown function 'shout' at position 1"
+        );
+        assert_eq!(error("upper(a, b)"), "upper() takes 1 argument, got 2");
+        assert_eq!(error("min()"), "min() takes at least 1 argument, got 0");
+        assert_eq!(error("if(a)"), "if() takes 2 to 3 arguments, got 1");
+        assert_eq!(
+            error("format(name)"),
+            "format() at position 1 needs a string template first"
+        );
+        assert_eq!(
+            error("format('{} {}', a)"),
+            "format() at position 1 has 2 placeholders but 1 values"
+        );
+        assert_eq!(error("format('{:x}', a)"), "invalid placeholder '{:x}'");
+        assert_eq!(
+            error("format('{:10001}', row())"),
+            "placeholder '{:10001}' is wider than 10000 characters"
+        );
+        assert_eq!(
+            error("format('{:99999999999999999}', row())"),
+            "placeholder '{:99999999999999999}' is wider than 10000 characters"
+        );
+        assert!(Expr::parse("format('{:010000.2}', row())").is_ok());
+        assert_eq!(error("format('{:%Q}', a)"), "invalid date format '%Q'");
+        assert_eq!(
+            error("format('{:%d %H:%M}', a)"),
+            "date format '%d %H:%M' asks for fields a date does not have"
+        );
+        assert_eq!(error("format('{', a)"), "unclosed '{' in format template");
+    }
+
+    #[test]
+    fn test_parse_number_ignores_currency() {
+        assert_eq!(
+            Number::parse("$1234.5"),
+            Some(Number {
+                value: 1234.5,
+                decimals: 1
+            })
+        );
+        assert_eq!(Number::parse("12,50 €"), None);
+        assert_eq!(Number::parse("-3").unwrap().value, -3.0);
+        assert_eq!(Number::parse("n/a"), None);
+        assert_eq!(Number::parse("Suite 5"), None);
+    }
+}
@@ -0,0 +1,192 @@
+//! Identifier strategies of `id` columns: sequences, random unique integers,
+//! UUIDs, ULIDs and Snowflake-style numbers.
+//!
+//! Time-based identifiers (UUID v7, ULID, Snowflake) do not read the clock,
+//! so a seed reproduces them: row `n` is stamped `n` seconds after
+//! 2024-01-01T00:00:00Z plus a random millisecond offset, which keeps them
+//! sorted by row.
+use rand::Rng;
+
+/// Milliseconds since the Unix epoch of row 0.
+const BASE_MILLIS: u64 = 1_704_067_200_000;
+
+/// The Twitter Snowflake epoch, 2010-11-04T01:42:54.657Z.
+const SNOWFLAKE_EPOCH: u64 = 1_288_834_974_657;
+
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// The strategy names, for error messages and listings.
+pub(super) const STRATEGIES: &[&str] = &[
+    "sequence",
+    "random",
+    "uuid_v4",
+    "uuid_v7",
+    "ulid",
+    "snowflake",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Strategy {
+    /// `start`, `start + step`, ... by row.
+    Sequence {
+        start: i64,
+        step: i64,
+    },
+    /// Integers drawn from `min..=max` that never repeat within a table.
+    Random {
+        min: i64,
+        max: i64,
+    },
+    UuidV4,
+    UuidV7,
+    Ulid,
+    Snowflake,
+}
+
+impl Strategy {
+    /// Looks up a strategy by name, ignoring case and accepting `-` for `_`.
+    /// Sequences start at 1 and random integers span 1 to 2^31 - 1 until
+    /// their parameters are applied.
+    pub fn named(name: &str) -> Option<Self> {
+        let name = name.trim().replace('-', "_").to_ascii_lowercase();
+        let strategy = match name.as_str() {
+            "sequence" => Strategy::Sequence { start: 1, step: 1 },
+            "random" => Strategy::Random {
+                min: 1,
+                max: i32::MAX as i64,
+            },
+            "uuid_v4" => Strategy::UuidV4,
+            "uuid_v7" => Strategy::UuidV7,
+            "ulid" => Strategy::Ulid,
+            "snowflake" => Strategy::Snowflake,
+            _ => return None,
+        };
+        Some(strategy)
+    }
+
+    /// The identifier of 1-based row `index`, before any prefix or padding.
+    pub fn generate(&self, index: usize, rng: &mut (impl Rng + ?Sized)) -> String {
+        match self {
+            Strategy::Sequence { start, step } => {
+                (*start as i128 + (index as i128 - 1) * *step as i128).to_string()
+            }
+            Strategy::Random { min, max } => rng.random_range(*min..=*max).to_string(),
+            Strategy::UuidV4 => {
+                let mut bytes: [u8; 16] = rng.random();
+                bytes[6] = (bytes[6] & 0x0f) | 0x40;
+                bytes[8] = (bytes[8] & 0x3f) | 0x80;
+                format_uuid(&bytes)
+            }
+            Strategy::UuidV7 => {
+                let mut bytes: [u8; 16] = rng.random();
+                bytes[..6].copy_from_slice(&row_millis(index, rng).to_be_bytes()[2..]);
+                bytes[6] = (bytes[6] & 0x0f) | 0x70;
+                bytes[8] = (bytes[8] & 0x3f) | 0x80;
+                format_uuid(&bytes)
+            }
+            Strategy::Ulid => {
+                let millis = row_millis(index, rng) as u128;
+                let random = rng.random::<u128>() & ((1 << 80) - 1);
+                format_ulid((millis << 80) | random)
+            }
+            Strategy::Snowflake => {
+                let millis = row_millis(index, rng) - SNOWFLAKE_EPOCH;
+                // 10 worker bits and a 12-bit sequence, both random.
+                let low = rng.random_range(0..1u64 << 22);
+                ((millis << 22) | low).to_string()
+            }
+        }
+    }
+}
+
+/// The timestamp of row `index`: `index` seconds after the base, plus up to
+/// 999 random milliseconds.
+fn row_millis(index: usize, rng: &mut (impl Rng + ?Sized)) -> u64 {
+    BASE_MILLIS + index as u64 * 1000 + rng.random_range(0..1000)
+}
+
+fn format_uuid(bytes: &[u8; 16]) -> String {
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// 26 Crockford base32 digits, most significant first.
+fn format_ulid(value: u128) -> String {
+    (0..26)
+        .map(|i| CROCKFORD[((value >> (125 - 5 * i)) & 31) as usize] as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_named() {
+        assert_eq!(Strategy::named("UUID-v4"), Some(Strategy::UuidV4));
+        assert_eq!(
+            Strategy::named("sequence"),
+            Some(Strategy::Sequence { start: 1, step: 1 })
+        );
+        assert!(Strategy::named("guid").is_none());
+        for name in STRATEGIES {
+            assert!(Strategy::named(name).is_some(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_sequence() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let strategy = Strategy::Sequence {
+            start: 1000,
+            step: -5,
+        };
+        let values: Vec<String> = (1..=3).map(|i| strategy.generate(i, &mut rng)).collect();
+        assert_eq!(values, vec!["1000", "995", "990"]);
+    }
+
+    #[test]
+    fn test_uuid_versions() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let v4 = Strategy::UuidV4.generate(1, &mut rng);
+        assert_eq!(v4.len(), 36);
+        assert_eq!(&v4[14..15], "4");
+        assert!(matches!(&v4[19..20], "8" | "9" | "a" | "b"), "{}", v4);
+
+        let first = Strategy::UuidV7.generate(1, &mut rng);
+        let second = Strategy::UuidV7.generate(2, &mut rng);
+        assert_eq!(&first[14..15], "7");
+        // 2024-01-01T00:00:01Z is 0x018cc251f7e8 in milliseconds.
+        assert!(first.starts_with("018cc251"), "{}", first);
+        assert!(first < second);
+    }
+
+    #[test]
+    fn test_ulid_and_snowflake_sort_by_row() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let ulids: Vec<String> = (1..=50)
+            .map(|i| Strategy::Ulid.generate(i, &mut rng))
+            .collect();
+        assert!(ulids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(ulids.iter().all(|ulid| ulid.len() == 26));
+        assert!(ulids[0].starts_with("01HK"), "{}", ulids[0]);
+        assert_eq!(format_ulid(0), "00000000000000000000000000");
+        assert_eq!(format_ulid(u128::MAX), "7ZZZZZZZZZZZZZZZZZZZZZZZZZ");
+
+        let snowflakes: Vec<u64> = (1..=50)
+            .map(|i| Strategy::Snowflake.generate(i, &mut rng).parse().unwrap())
+            .collect();
+        assert!(snowflakes.windows(2).all(|pair| pair[0] < pair[1]));
+        let millis = (snowflakes[0] >> 22) + SNOWFLAKE_EPOCH;
+        assert_eq!(millis / 1000, BASE_MILLIS / 1000 + 1);
+    }
+}
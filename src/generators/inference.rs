@@ -0,0 +1,422 @@
+//! Column types inferred from the values of an uploaded CSV rather than from
+//! its header names, each with a spec that generates values like the ones seen.
+use super::sample_generator::{DATE_FORMATS, MAX_CATEGORIES};
+use crate::schema::{Bound, ColumnSpec};
+use crate::value::is_decimal;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Share of a column's values that must fit a kind for the column to take
+/// it, so a few stray values do not turn a number column into text.
+const MIN_CONFIDENCE: f64 = 0.9;
+
+const TIMESTAMP_FORMATS: &[&str] = &["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S"];
+
+/// What a column's values turned out to hold, tried in this order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InferredKind {
+    /// No values to infer from; the type is detected from the header.
+    Empty,
+    Boolean,
+    Uuid,
+    Integer,
+    Decimal,
+    Date,
+    Timestamp,
+    Email,
+    Enum,
+    Text,
+}
+
+impl InferredKind {
+    /// The kind as JSON names it, e.g. "integer".
+    pub fn name(self) -> &'static str {
+        match self {
+            InferredKind::Empty => "empty",
+            InferredKind::Boolean => "boolean",
+            InferredKind::Uuid => "uuid",
+            InferredKind::Integer => "integer",
+            InferredKind::Decimal => "decimal",
+            InferredKind::Date => "date",
+            InferredKind::Timestamp => "timestamp",
+            InferredKind::Email => "email",
+            InferredKind::Enum => "enum",
+            InferredKind::Text => "text",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InferredColumn {
+    pub name: String,
+    pub kind: InferredKind,
+    /// Share of the non-empty values that fit `kind`, from 0 to 1. For enums,
+    /// the share of values repeating an earlier one.
+    pub confidence: f64,
+    /// A column spec generating values like the ones seen, e.g. an integer
+    /// column's range or a date column's format, ready to save.
+    pub column: ColumnSpec,
+}
+
+/// Infers every column of `rows` from its values. Missing cells of short
+/// rows count as blanks.
+pub fn infer_columns(headers: &[String], rows: &[Vec<String>]) -> Vec<InferredColumn> {
+    headers
+        .iter()
+        .enumerate()
+        .map(|(index, header)| {
+            let values: Vec<&str> = rows
+                .iter()
+                .map(|row| row.get(index).map_or("", |value| value.trim()))
+                .collect();
+            infer_column(header, &values)
+        })
+        .collect()
+}
+
+fn infer_column(name: &str, values: &[&str]) -> InferredColumn {
+    let present: Vec<&str> = values.iter().copied().filter(|v| !v.is_empty()).collect();
+    let (kind, confidence, mut column) = if present.is_empty() {
+        (InferredKind::Empty, 0.0, ColumnSpec::new(name, "auto"))
+    } else {
+        infer_kind(name, &present)
+    };
+    if present.len() < values.len() {
+        column.null_rate = Some((values.len() - present.len()) as f64 / values.len() as f64);
+    }
+
+    InferredColumn {
+        name: name.to_string(),
+        kind,
+        confidence,
+        column,
+    }
+}
+
+fn infer_kind(name: &str, values: &[&str]) -> (InferredKind, f64, ColumnSpec) {
+    let (_, share) = fitting(values, |v| {
+        (v.eq_ignore_ascii_case("true") || v.eq_ignore_ascii_case("false")).then_some(())
+    });
+    if share >= MIN_CONFIDENCE {
+        return (
+            InferredKind::Boolean,
+            share,
+            ColumnSpec::new(name, "boolean"),
+        );
+    }
+
+    let (_, share) = fitting(values, |v| is_uuid(v).then_some(()));
+    if share >= MIN_CONFIDENCE {
+        return (InferredKind::Uuid, share, ColumnSpec::new(name, "uuid"));
+    }
+
+    // Leading zeros mark codes such as "0042", which stay text; so do
+    // decimals with them.
+    let (ints, share) = fitting(values, |v| {
+        v.parse::<i64>().ok().filter(|n| n.to_string() == v)
+    });
+    if share >= MIN_CONFIDENCE {
+        return (InferredKind::Integer, share, integer_column(name, &ints));
+    }
+
+    let (decimals, share) = fitting(values, |v| {
+        (is_decimal(v) && !has_leading_zero(v)).then_some(v)
+    });
+    if share >= MIN_CONFIDENCE {
+        return (
+            InferredKind::Decimal,
+            share,
+            decimal_column(name, &decimals),
+        );
+    }
+
+    if let Some((format, dates, share)) = best_format(values, DATE_FORMATS, |v, format| {
+        NaiveDate::parse_from_str(v, format).ok()
+    }) {
+        let mut column = ColumnSpec::new(name, "date");
+        column.min = dates.iter().min().map(|d| Bound::Text(d.to_string()));
+        column.max = dates.iter().max().map(|d| Bound::Text(d.to_string()));
+        column.format = (format != "%Y-%m-%d").then(|| format.to_string());
+        return (InferredKind::Date, share, column);
+    }
+
+    if let Some((format, times, share)) = best_format(values, TIMESTAMP_FORMATS, |v, format| {
+        NaiveDateTime::parse_from_str(v, format).ok()
+    }) {
+        let mut column = ColumnSpec::new(name, "timestamp");
+        column.min = times.iter().min().map(timestamp_bound);
+        column.max = times.iter().max().map(timestamp_bound);
+        column.format = Some(format.to_string());
+        return (InferredKind::Timestamp, share, column);
+    }
+
+    let (_, share) = fitting(values, |v| is_email(v).then_some(()));
+    if share >= MIN_CONFIDENCE {
+        return (InferredKind::Email, share, ColumnSpec::new(name, "email"));
+    }
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for value in values {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+    if counts.len() <= MAX_CATEGORIES && counts.len() * 2 <= values.len() {
+        let mut categories: Vec<(&str, usize)> = counts.into_iter().collect();
+        categories.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        let mut column = ColumnSpec::new(name, "enum");
+        column.values = Some(categories.iter().map(|(v, _)| v.to_string()).collect());
+        if categories
+            .iter()
+            .any(|(_, count)| *count != categories[0].1)
+        {
+            column.weights = Some(categories.iter().map(|(_, c)| Some(*c as f64)).collect());
+        }
+        let confidence = 1.0 - categories.len() as f64 / values.len() as f64;
+        return (InferredKind::Enum, confidence, column);
+    }
+
+    let lengths = values.iter().map(|v| v.chars().count());
+    let mut column = ColumnSpec::new(name, "text");
+    column.min_length = lengths.clone().min();
+    column.max_length = lengths.max();
+    (InferredKind::Text, 1.0, column)
+}
+
+/// Evenly stepped, increasing integers number rows like a sequence id. Any
+/// other integers become an `integer` range between the smallest and largest
+/// value seen.
+fn integer_column(name: &str, ints: &[i64]) -> ColumnSpec {
+    if let [first, second, ..] = ints
+        && let Some(step) = second.checked_sub(*first).filter(|step| *step > 0)
+        && ints
+            .windows(2)
+            .all(|w| w[1].checked_sub(w[0]) == Some(step))
+    {
+        let mut column = ColumnSpec::new(name, "id");
+        column.start = (*first != 1).then_some(*first);
+        column.step = (step != 1).then_some(step);
+        return column;
+    }
+
+    let mut column = ColumnSpec::new(name, "integer");
+    column.min = ints.iter().min().map(|&n| Bound::Number(n as f64));
+    column.max = ints.iter().max().map(|&n| Bound::Number(n as f64));
+    column
+}
+
+/// Decimals between the smallest and largest value seen, with as many
+/// decimal places as the most precise one.
+fn decimal_column(name: &str, decimals: &[&str]) -> ColumnSpec {
+    let numbers: Vec<f64> = decimals.iter().filter_map(|v| v.parse().ok()).collect();
+    let precision = decimals
+        .iter()
+        .map(|v| v.split_once('.').map_or(0, |(_, fraction)| fraction.len()))
+        .max()
+        .unwrap_or(0);
+
+    let mut column = ColumnSpec::new(name, "decimal");
+    column.min = Some(Bound::Number(
+        numbers.iter().copied().fold(f64::INFINITY, f64::min),
+    ));
+    column.max = Some(Bound::Number(
+        numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+    ));
+    column.precision = Some(precision.min(10) as u32);
+    column
+}
+
+fn timestamp_bound(time: &NaiveDateTime) -> Bound {
+    Bound::Text(time.format("%Y-%m-%dT%H:%M:%S").to_string())
+}
+
+/// The values `parse` accepts and their share of all values.
+fn fitting<'a, T>(values: &[&'a str], parse: impl Fn(&'a str) -> Option<T>) -> (Vec<T>, f64) {
+    let fit: Vec<T> = values.iter().filter_map(|v| parse(v)).collect();
+    let share = fit.len() as f64 / values.len() as f64;
+    (fit, share)
+}
+
+/// The format most values parse with, if enough do. Ties go to the earlier
+/// format, so "01/02/2024" reads day first.
+fn best_format<T>(
+    values: &[&str],
+    formats: &[&'static str],
+    parse: impl Fn(&str, &str) -> Option<T>,
+) -> Option<(&'static str, Vec<T>, f64)> {
+    let mut best: Option<(&'static str, Vec<T>, f64)> = None;
+    for &format in formats {
+        let (parsed, share) = fitting(values, |v| parse(v, format));
+        if share >= MIN_CONFIDENCE && best.as_ref().is_none_or(|(_, _, best)| share > *best) {
+            best = Some((format, parsed, share));
+        }
+    }
+    best
+}
+
+pub(super) fn has_leading_zero(number: &str) -> bool {
+    let digits = number.strip_prefix('-').unwrap_or(number);
+    digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.")
+}
+
+fn is_uuid(value: &str) -> bool {
+    value.len() == 36
+        && value.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+fn is_email(value: &str) -> bool {
+    let Some((user, domain)) = value.split_once('@') else {
+        return false;
+    };
+    !user.is_empty()
+        && !value.contains(char::is_whitespace)
+        && !domain.contains('@')
+        && domain
+            .split_once('.')
+            .is_some_and(|(host, tld)| !host.is_empty() && !tld.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::SmartGenerator;
+
+    fn infer(header: &str, values: &[&str]) -> InferredColumn {
+        let rows: Vec<Vec<String>> = values.iter().map(|v| vec![v.to_string()]).collect();
+        infer_columns(&[header.to_string()], &rows).remove(0)
+    }
+
+    #[test]
+    fn test_infer_numbers() {
+        let id = infer("code", &["10", "20", "30", "40"]);
+        assert_eq!(id.kind, InferredKind::Integer);
+        assert_eq!(id.column.type_name, "id");
+        assert_eq!((id.column.start, id.column.step), (Some(10), Some(10)));
+
+        let quantity = infer("quantity", &["3", "1", "12", "3", ""]);
+        assert_eq!(quantity.column.type_name, "integer");
+        assert_eq!(quantity.column.min, Some(Bound::Number(1.0)));
+        assert_eq!(quantity.column.max, Some(Bound::Number(12.0)));
+        assert_eq!(quantity.column.null_rate, Some(0.2));
+
+        let price = infer("price", &["12.50", "3", "7.125"]);
+        assert_eq!(price.kind, InferredKind::Decimal);
+        assert_eq!(price.column.type_name, "decimal");
+        assert_eq!(price.column.precision, Some(3));
+        assert_eq!(price.column.max, Some(Bound::Number(12.5)));
+
+        let zips = infer("zip", &["02134", "10001", "94105"]);
+        assert_eq!(zips.kind, InferredKind::Text);
+    }
+
+    #[test]
+    fn test_infer_confidence_tolerates_stray_values() {
+        let mut values = vec!["7"; 19];
+        values.extend(["8", "n/a"]);
+        let column = infer("score", &values);
+        assert_eq!(column.kind, InferredKind::Integer);
+        assert!((column.confidence - 20.0 / 21.0).abs() < 1e-9);
+
+        let mixed = infer("score", &["1", "2", "n/a", "x", "5"]);
+        assert_eq!(mixed.kind, InferredKind::Text);
+        assert_eq!(mixed.confidence, 1.0);
+    }
+
+    #[test]
+    fn test_infer_dates_and_timestamps() {
+        let joined = infer("joined", &["31/01/2024", "15/03/2023", "01/02/2024"]);
+        assert_eq!(joined.kind, InferredKind::Date);
+        assert_eq!(joined.column.format.as_deref(), Some("%d/%m/%Y"));
+        assert_eq!(joined.column.min, Some(Bound::Text("2023-03-15".into())));
+        assert_eq!(joined.column.max, Some(Bound::Text("2024-02-01".into())));
+
+        let iso = infer("day", &["2024-01-31", "2024-02-01"]);
+        assert_eq!(iso.column.format, None);
+
+        let seen = infer("seen", &["2024-01-31 08:00:00", "2024-02-01 17:30:00"]);
+        assert_eq!(seen.kind, InferredKind::Timestamp);
+        assert_eq!(seen.column.type_name, "timestamp");
+        assert_eq!(seen.column.format.as_deref(), Some("%Y-%m-%d %H:%M:%S"));
+        assert_eq!(
+            seen.column.max,
+            Some(Bound::Text("2024-02-01T17:30:00".into()))
+        );
+    }
+
+    #[test]
+    fn test_infer_strings() {
+        let flag = infer("flag", &["TRUE", "false", "true"]);
+        assert_eq!(flag.kind, InferredKind::Boolean);
+
+        let key = infer("key", &["9b2f6a1e-3c4d-4e8f-a1b2-c3d4e5f6a7b8"]);
+        assert_eq!(key.column.type_name, "uuid");
+
+        let contact = infer("contact", &["ann@example.com", "bo@mail.co.uk"]);
+        assert_eq!(contact.kind, InferredKind::Email);
+
+        let status = infer("status", &["open", "closed", "open", "open"]);
+        assert_eq!(status.kind, InferredKind::Enum);
+        assert_eq!(
+            status.column.values,
+            Some(vec!["open".into(), "closed".into()])
+        );
+        assert_eq!(status.column.weights, Some(vec![Some(3.0), Some(1.0)]));
+        assert_eq!(status.confidence, 0.5);
+
+        let note = infer("note", &["a", "bcd", "ef"]);
+        assert_eq!(note.kind, InferredKind::Text);
+        assert_eq!(
+            (note.column.min_length, note.column.max_length),
+            (Some(1), Some(3))
+        );
+
+        let blank = infer("email", &["", ""]);
+        assert_eq!(blank.kind, InferredKind::Empty);
+        assert_eq!(blank.column.type_name, "auto");
+        assert_eq!(blank.column.null_rate, Some(1.0));
+    }
+
+    #[test]
+    fn test_inferred_columns_build() {
+        let headers: Vec<String> = ["id", "qty", "price", "day", "seen", "plan", "note"]
+            .map(String::from)
+            .to_vec();
+        let rows: Vec<Vec<String>> = (1..=6)
+            .map(|n| {
+                vec![
+                    n.to_string(),
+                    (n % 3).to_string(),
+                    format!("{}.5", n),
+                    format!("0{}.02.2024", n),
+                    format!("2024-02-0{}T10:00:00", n),
+                    if n % 2 == 0 { "a" } else { "b" }.to_string(),
+                    "x".repeat(n),
+                ]
+            })
+            .collect();
+        let specs: Vec<ColumnSpec> = infer_columns(&headers, &rows)
+            .into_iter()
+            .map(|c| c.column)
+            .collect();
+        assert_eq!(
+            crate::schema::column_type_map(&specs),
+            HashMap::from(
+                [
+                    ("id", "id"),
+                    ("qty", "integer"),
+                    ("price", "decimal"),
+                    ("day", "date"),
+                    ("seen", "timestamp"),
+                    ("plan", "enum"),
+                    ("note", "text"),
+                ]
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+            )
+        );
+        assert!(crate::schema::dataset_columns(&headers, None, &specs).is_ok());
+        assert!(SmartGenerator::from_columns(&specs).is_ok());
+    }
+}
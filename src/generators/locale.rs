@@ -0,0 +1,223 @@
+//! Locale packs: the names, places, address and phone formats of one locale,
+//! bundled as TOML files under `locales/` and embedded in the binary.
+use super::pattern::Pattern;
+use rand::Rng;
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+/// The locale of `street` and `postcode` columns that set none.
+pub(super) const DEFAULT_LOCALE: &str = "en_US";
+
+const PACKS: &[(&str, &str)] = &[
+    ("en_US", include_str!("locales/en_US.toml")),
+    ("de_DE", include_str!("locales/de_DE.toml")),
+    ("fr_FR", include_str!("locales/fr_FR.toml")),
+    ("ja_JP", include_str!("locales/ja_JP.toml")),
+    ("pt_BR", include_str!("locales/pt_BR.toml")),
+];
+
+/// A pack file as written.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PackFile {
+    name: String,
+    first_names: Vec<String>,
+    last_names: Vec<String>,
+    #[serde(default)]
+    family_name_first: bool,
+    cities: Vec<String>,
+    states: Vec<String>,
+    streets: Vec<String>,
+    address_format: String,
+    house_number: String,
+    postcode: String,
+    phones: Vec<String>,
+    date_format: String,
+}
+
+pub(super) struct Locale {
+    pub code: &'static str,
+    /// The locale's own name for itself, e.g. "Deutsch (Deutschland)".
+    pub name: String,
+    first_names: Vec<String>,
+    last_names: Vec<String>,
+    family_name_first: bool,
+    pub cities: Vec<String>,
+    /// First-level divisions: states, Länder, régions, prefectures.
+    pub states: Vec<String>,
+    streets: Vec<String>,
+    /// Street address with `{street}` and `{number}` placeholders.
+    address_format: String,
+    house_number: Pattern,
+    pub postcode: Pattern,
+    pub phones: Vec<Pattern>,
+    /// strftime format of `date` columns without their own `format`.
+    pub date_format: String,
+}
+
+impl Locale {
+    fn load(code: &'static str, text: &str) -> Result<Self, String> {
+        let file: PackFile = toml::from_str(text).map_err(|e| e.to_string())?;
+        let lists = [
+            ("first_names", &file.first_names),
+            ("last_names", &file.last_names),
+            ("cities", &file.cities),
+            ("states", &file.states),
+            ("streets", &file.streets),
+            ("phones", &file.phones),
+        ];
+        if let Some((list, _)) = lists.iter().find(|(_, values)| values.is_empty()) {
+            return Err(format!("'{}' must not be empty", list));
+        }
+        let pattern = |text: &str| {
+            Pattern::parse(text).map_err(|e| format!("invalid pattern '{}': {}", text, e))
+        };
+        Ok(Self {
+            code,
+            house_number: pattern(&file.house_number)?,
+            postcode: pattern(&file.postcode)?,
+            phones: file
+                .phones
+                .iter()
+                .map(|phone| pattern(phone))
+                .collect::<Result<_, _>>()?,
+            name: file.name,
+            first_names: file.first_names,
+            last_names: file.last_names,
+            family_name_first: file.family_name_first,
+            cities: file.cities,
+            states: file.states,
+            streets: file.streets,
+            address_format: file.address_format,
+            date_format: file.date_format,
+        })
+    }
+
+    pub fn name(&self, rng: &mut (impl Rng + ?Sized)) -> String {
+        let first = pick(&self.first_names, rng);
+        let last = pick(&self.last_names, rng);
+        if self.family_name_first {
+            format!("{} {}", last, first)
+        } else {
+            format!("{} {}", first, last)
+        }
+    }
+
+    pub fn city(&self, rng: &mut (impl Rng + ?Sized)) -> String {
+        pick(&self.cities, rng).to_string()
+    }
+
+    pub fn state(&self, rng: &mut (impl Rng + ?Sized)) -> String {
+        pick(&self.states, rng).to_string()
+    }
+
+    pub fn street_address(&self, rng: &mut (impl Rng + ?Sized)) -> String {
+        self.address_format
+            .replace("{street}", pick(&self.streets, rng))
+            .replace("{number}", &self.house_number.sample(rng))
+    }
+}
+
+fn pick<'a>(values: &'a [String], rng: &mut (impl Rng + ?Sized)) -> &'a str {
+    &values[rng.random_range(0..values.len())]
+}
+
+static LOCALES: OnceLock<Vec<Locale>> = OnceLock::new();
+
+/// Every bundled locale, in code order of `PACKS`. A pack that fails to load
+/// is a build mistake, caught by the tests below.
+pub(super) fn locales() -> &'static [Locale] {
+    LOCALES.get_or_init(|| {
+        PACKS
+            .iter()
+            .map(|(code, text)| {
+                Locale::load(code, text)
+                    .unwrap_or_else(|e| panic!("invalid locale pack {}: {}", code, e))
+            })
+            .collect()
+    })
+}
+
+/// Looks up a locale by code, ignoring case and accepting `-` for `_`, so
+/// "de-de" finds de_DE.
+pub(super) fn find(code: &str) -> Option<&'static Locale> {
+    let code = code.trim().replace('-', "_");
+    locales()
+        .iter()
+        .find(|locale| locale.code.eq_ignore_ascii_case(&code))
+}
+
+pub(super) fn default_locale() -> &'static Locale {
+    find(DEFAULT_LOCALE).expect("the default locale is bundled")
+}
+
+/// The bundled locale codes, for error messages and listings.
+pub fn locale_codes() -> Vec<&'static str> {
+    PACKS.iter().map(|(code, _)| *code).collect()
+}
+
+/// The code and own name of every bundled locale.
+pub fn locale_names() -> Vec<(&'static str, &'static str)> {
+    locales()
+        .iter()
+        .map(|locale| (locale.code, locale.name.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_every_pack_loads() {
+        assert_eq!(locales().len(), PACKS.len());
+        for (code, text) in PACKS {
+            let locale = Locale::load(code, text).unwrap();
+            assert!(locale.address_format.contains("{street}"), "{}", code);
+            assert!(locale.address_format.contains("{number}"), "{}", code);
+            assert!(
+                chrono::format::StrftimeItems::new(&locale.date_format)
+                    .all(|item| !matches!(item, chrono::format::Item::Error)),
+                "{}",
+                code
+            );
+        }
+        assert_eq!(
+            locale_codes(),
+            vec!["en_US", "de_DE", "fr_FR", "ja_JP", "pt_BR"]
+        );
+    }
+
+    #[test]
+    fn test_find_ignores_case_and_separator() {
+        assert_eq!(find("de-de").unwrap().code, "de_DE");
+        assert_eq!(find(" pt_BR ").unwrap().code, "pt_BR");
+        assert!(find("xx_XX").is_none());
+    }
+
+    #[test]
+    fn test_locale_values() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let japanese = find("ja_JP").unwrap();
+        let name = japanese.name(&mut rng);
+        let (family, _) = name.split_once(' ').unwrap();
+        assert!(japanese.last_names.iter().any(|n| n == family));
+
+        let german = find("de_DE").unwrap();
+        for _ in 0..20 {
+            let address = german.street_address(&mut rng);
+            let (street, number) = address.rsplit_once(' ').unwrap();
+            assert!(german.streets.iter().any(|s| s == street), "{}", address);
+            assert!(number.starts_with(|c: char| c.is_ascii_digit()));
+            let postcode = german.postcode.sample(&mut rng);
+            assert_eq!(postcode.len(), 5);
+            assert!(!postcode.starts_with("00"));
+        }
+
+        let brazil = find("pt_BR").unwrap();
+        assert_eq!(brazil.postcode.nth_value(0).unwrap(), "00000-000");
+        assert!(brazil.phones[0].sample(&mut rng).starts_with("+55 "));
+    }
+}
@@ -1,10 +1,182 @@
+mod builtin;
+mod catalog;
+mod expression;
+mod ids;
+mod inference;
+mod locale;
+mod params;
+mod pattern;
+pub mod registry;
+mod sample_generator;
 mod smart_generator;
 
-pub use smart_generator::SmartGenerator;
+pub use inference::{InferredColumn, InferredKind, infer_columns};
+pub use locale::{locale_codes, locale_names};
+pub use registry::registry;
+pub(crate) use sample_generator::DATE_FORMATS;
+pub use sample_generator::SampleGenerator;
+pub use smart_generator::{SmartGenerator, is_auto_type};
 
+use crate::db::models::GenerationSource;
+use crate::schema::ColumnSpec;
+use crate::value::Value;
 use rand::Rng;
 
+/// Returns the requested seed, or a fresh one when none was given. Fresh
+/// seeds stay below 2^53 so they survive a round trip through JavaScript.
+pub fn resolve_seed(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| rand::rng().random_range(0..1u64 << 53))
+}
+
 pub trait DataGenerator {
     fn headers(&self) -> Vec<String>;
-    fn generate_row(&self, index: usize, rng: &mut impl Rng) -> Vec<String>;
+    fn generate_row(&self, index: usize, rng: &mut impl Rng) -> Vec<Value>;
+
+    /// Fails when `row_count` rows cannot be generated, e.g. because a unique
+    /// column has fewer possible values.
+    fn check_row_count(&self, _row_count: usize) -> Result<(), GeneratorError> {
+        Ok(())
+    }
 }
+
+/// The generator behind a saved dataset: learned from its sample rows when it
+/// has any, otherwise driven by header names and column types.
+pub enum DatasetGenerator {
+    Smart(SmartGenerator),
+    Sample(SampleGenerator),
+}
+
+impl DatasetGenerator {
+    /// Learns from the source's sample rows when `use_sample_data` is set and
+    /// there are any; otherwise generates from the column specs alone.
+    pub fn from_source(
+        source: &GenerationSource,
+        use_sample_data: bool,
+    ) -> Result<Self, GeneratorError> {
+        let columns = match &source.columns {
+            Some(columns) => columns.clone(),
+            None => ColumnSpec::from_column_types(&source.sample.headers, &source.column_types),
+        };
+        let columns = with_locale(&columns, source.dataset.locale.as_deref());
+
+        if use_sample_data && !source.sample.rows.is_empty() {
+            SampleGenerator::from_sample(&columns, &source.sample.rows)
+                .map(DatasetGenerator::Sample)
+        } else {
+            SmartGenerator::from_columns(&columns).map(DatasetGenerator::Smart)
+        }
+    }
+}
+
+/// The columns with a dataset-wide `locale` filled in where a column names
+/// none. Types without locale data ignore it.
+pub fn with_locale(columns: &[ColumnSpec], locale: Option<&str>) -> Vec<ColumnSpec> {
+    columns
+        .iter()
+        .map(|column| ColumnSpec {
+            locale: column.locale.clone().or_else(|| locale.map(String::from)),
+            ..column.clone()
+        })
+        .collect()
+}
+
+/// Whether `code` names a bundled locale pack, e.g. "de_DE".
+pub fn is_locale(code: &str) -> bool {
+    locale::find(code).is_some()
+}
+
+/// Checks that a column's type and parameters can be generated, without
+/// keeping the generator. Columns it refers to are not looked up; see
+/// `check_columns`.
+pub fn check_column(column: &ColumnSpec) -> Result<(), GeneratorError> {
+    smart_generator::build_column(registry(), column).map(|_| ())
+}
+
+/// Checks that the columns of one table can be generated together: every
+/// column referred to by another exists, and none depend on each other in a
+/// cycle.
+pub fn check_columns(columns: &[ColumnSpec]) -> Result<(), GeneratorError> {
+    SmartGenerator::from_columns(columns).map(|_| ())
+}
+
+impl DataGenerator for DatasetGenerator {
+    fn headers(&self) -> Vec<String> {
+        match self {
+            DatasetGenerator::Smart(generator) => generator.headers(),
+            DatasetGenerator::Sample(generator) => generator.headers(),
+        }
+    }
+
+    fn generate_row(&self, index: usize, rng: &mut impl Rng) -> Vec<Value> {
+        match self {
+            DatasetGenerator::Smart(generator) => generator.generate_row(index, rng),
+            DatasetGenerator::Sample(generator) => generator.generate_row(index, rng),
+        }
+    }
+
+    fn check_row_count(&self, row_count: usize) -> Result<(), GeneratorError> {
+        match self {
+            DatasetGenerator::Smart(generator) => generator.check_row_count(row_count),
+            DatasetGenerator::Sample(generator) => generator.check_row_count(row_count),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum GeneratorError {
+    UnknownDataType {
+        column: String,
+        type_name: String,
+    },
+    InvalidParameter {
+        column: String,
+        message: String,
+    },
+    NotEnoughUniqueValues {
+        column: String,
+        available: u64,
+        requested: usize,
+    },
+    /// A unique column remembers every value it hands out, so its row count
+    /// is capped to bound memory.
+    TooManyUniqueRows {
+        column: String,
+        limit: usize,
+        requested: usize,
+    },
+}
+
+impl std::fmt::Display for GeneratorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GeneratorError::UnknownDataType { column, type_name } => write!(
+                f,
+                "Unknown data type '{}' for column '{}'",
+                type_name, column
+            ),
+            GeneratorError::InvalidParameter { column, message } => {
+                write!(f, "Invalid parameters for column '{}': {}", column, message)
+            }
+            GeneratorError::NotEnoughUniqueValues {
+                column,
+                available,
+                requested,
+            } => write!(
+                f,
+                "Unique column '{}' has only {} possible values, but {} rows were requested",
+                column, available, requested
+            ),
+            GeneratorError::TooManyUniqueRows {
+                column,
+                limit,
+                requested,
+            } => write!(
+                f,
+                "Unique column '{}' can fill at most {} rows, but {} rows were requested",
+                column, limit, requested
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GeneratorError {}
@@ -0,0 +1,1279 @@
+use super::GeneratorError;
+use super::builtin::TEXT_CHARSET;
+use super::ids::{self, Strategy};
+use super::locale::{self, Locale};
+use super::pattern::Pattern;
+use super::registry::RowValues;
+use crate::dictionary::DictionaryEntry;
+use crate::schema::{Bound, ColumnSpec};
+use chrono::format::{DelayedFormat, Fixed, Item, Numeric, StrftimeItems};
+use chrono::{Duration, Months, NaiveDate, NaiveDateTime, NaiveTime};
+use rand::Rng;
+use std::fmt::Write;
+
+/// Whole-number bounds, used for ages.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct IntRange {
+    pub min: i64,
+    pub max: i64,
+}
+
+impl IntRange {
+    pub const AGE: IntRange = IntRange { min: 18, max: 80 };
+    pub const INTEGER: IntRange = IntRange { min: 0, max: 1000 };
+
+    pub fn sample(&self, rng: &mut (impl Rng + ?Sized)) -> i64 {
+        rng.random_range(self.min..=self.max)
+    }
+
+    pub fn cardinality(&self) -> u64 {
+        let count = self.max as i128 - self.min as i128 + 1;
+        u64::try_from(count).unwrap_or(u64::MAX)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct MoneyParams {
+    pub min: f64,
+    pub max: f64,
+    /// Digits after the decimal point.
+    pub precision: usize,
+    /// Prefixed to every value, e.g. "$".
+    pub currency: Option<String>,
+}
+
+impl Default for MoneyParams {
+    fn default() -> Self {
+        Self {
+            min: 10.0,
+            max: 10000.0,
+            precision: 2,
+            currency: None,
+        }
+    }
+}
+
+impl MoneyParams {
+    pub fn sample(&self, rng: &mut (impl Rng + ?Sized)) -> String {
+        self.sample_between(self.min, self.max, rng)
+    }
+
+    /// An amount in `min..=max` instead of the configured range.
+    pub fn sample_between(&self, min: f64, max: f64, rng: &mut (impl Rng + ?Sized)) -> String {
+        let value = rng.random_range(min..=max);
+        format!(
+            "{}{:.*}",
+            self.currency.as_deref().unwrap_or(""),
+            self.precision,
+            value
+        )
+    }
+
+    fn step(&self) -> f64 {
+        10f64.powi(-(self.precision as i32))
+    }
+
+    /// Amounts `step` apart from `min`, e.g. 0.01 apart at precision 2.
+    pub fn cardinality(&self) -> u64 {
+        ((self.max - self.min) / self.step()).floor() as u64 + 1
+    }
+
+    pub fn nth_value(&self, n: u64) -> String {
+        let value = self.min + n as f64 * self.step();
+        format!(
+            "{}{:.*}",
+            self.currency.as_deref().unwrap_or(""),
+            self.precision,
+            value
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct DateParams {
+    pub min: NaiveDate,
+    pub max: NaiveDate,
+    /// strftime format of the generated values.
+    pub format: String,
+}
+
+impl Default for DateParams {
+    fn default() -> Self {
+        Self {
+            min: NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(),
+            max: NaiveDate::from_ymd_opt(2025, 12, 31).unwrap(),
+            format: "%Y-%m-%d".to_string(),
+        }
+    }
+}
+
+impl DateParams {
+    pub fn sample(&self, rng: &mut (impl Rng + ?Sized)) -> String {
+        self.sample_between(self.min, self.max, rng)
+    }
+
+    /// A date in `min..=max` instead of the configured range.
+    pub fn sample_between(
+        &self,
+        min: NaiveDate,
+        max: NaiveDate,
+        rng: &mut (impl Rng + ?Sized),
+    ) -> String {
+        let days = (max - min).num_days();
+        let date = min + Duration::days(rng.random_range(0..=days));
+        date.format(&self.format).to_string()
+    }
+
+    /// Reads a date in this column's format, or as `YYYY-MM-DD`.
+    pub fn parse(&self, value: &str) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(value, &self.format)
+            .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d"))
+            .ok()
+    }
+
+    pub fn nth_value(&self, n: u64) -> Option<String> {
+        let date = self.min.checked_add_days(chrono::Days::new(n))?;
+        (date <= self.max).then(|| date.format(&self.format).to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct TimestampParams {
+    pub min: NaiveDateTime,
+    pub max: NaiveDateTime,
+    /// strftime format of the generated values.
+    pub format: String,
+    /// Seconds between two values the format tells apart; see `format_step`.
+    pub step: i64,
+}
+
+impl Default for TimestampParams {
+    fn default() -> Self {
+        let dates = DateParams::default();
+        Self {
+            min: dates.min.and_time(NaiveTime::MIN),
+            max: dates.max.and_hms_opt(23, 59, 59).unwrap(),
+            format: "%Y-%m-%dT%H:%M:%SZ".to_string(),
+            step: 1,
+        }
+    }
+}
+
+impl TimestampParams {
+    fn seconds(&self) -> i64 {
+        (self.max - self.min).num_seconds()
+    }
+
+    pub fn sample(&self, rng: &mut (impl Rng + ?Sized)) -> String {
+        let timestamp = self.min + Duration::seconds(rng.random_range(0..=self.seconds()));
+        timestamp.format(&self.format).to_string()
+    }
+
+    /// Steps of the format's finest unit from `min` to `max`.
+    pub fn cardinality(&self) -> u64 {
+        (self.seconds() / self.step) as u64 + 1
+    }
+
+    pub fn nth_value(&self, n: u64) -> Option<String> {
+        let seconds = i64::try_from(n).ok()?.checked_mul(self.step)?;
+        let timestamp = self.min.checked_add_signed(Duration::seconds(seconds))?;
+        (timestamp <= self.max).then(|| timestamp.format(&self.format).to_string())
+    }
+}
+
+/// Times of day, as finely as the format shows them.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct TimeParams {
+    pub format: String,
+    /// Seconds between two values the format tells apart; see `format_step`.
+    pub step: i64,
+}
+
+impl TimeParams {
+    const SECONDS: i64 = 24 * 60 * 60;
+
+    pub fn sample(&self, rng: &mut (impl Rng + ?Sized)) -> String {
+        self.nth_value(rng.random_range(0..self.cardinality()))
+            .expect("a time of the day")
+    }
+
+    pub fn cardinality(&self) -> u64 {
+        (Self::SECONDS / self.step) as u64
+    }
+
+    pub fn nth_value(&self, n: u64) -> Option<String> {
+        let seconds = u32::try_from(n.checked_mul(self.step as u64)?).ok()?;
+        let time = NaiveTime::from_num_seconds_from_midnight_opt(seconds, 0)?;
+        Some(time.format(&self.format).to_string())
+    }
+}
+
+/// Seconds between the closest two times `format` tells apart: a second
+/// when it shows seconds, a minute with minutes, an hour with hours and a
+/// day otherwise.
+fn format_step(format: &str) -> i64 {
+    StrftimeItems::new(format)
+        .map(|item| match item {
+            Item::Numeric(Numeric::Second | Numeric::Timestamp, _)
+            | Item::Fixed(
+                Fixed::Nanosecond
+                | Fixed::Nanosecond3
+                | Fixed::Nanosecond6
+                | Fixed::Nanosecond9
+                | Fixed::RFC2822
+                | Fixed::RFC3339,
+            ) => 1,
+            Item::Numeric(Numeric::Minute, _) => 60,
+            Item::Numeric(Numeric::Hour | Numeric::Hour12, _) => 60 * 60,
+            _ => 24 * 60 * 60,
+        })
+        .min()
+        .unwrap_or(24 * 60 * 60)
+}
+
+/// Columns of the same row that bound a column's values, given as
+/// `min_column` and `max_column`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(super) struct RowBounds {
+    pub min_column: Option<String>,
+    pub max_column: Option<String>,
+}
+
+impl RowBounds {
+    pub fn columns(&self) -> Vec<String> {
+        self.min_column
+            .iter()
+            .chain(&self.max_column)
+            .cloned()
+            .collect()
+    }
+
+    /// Narrows `min..=max` to the row's values of the bound columns, read
+    /// with `parse`. Null or unreadable values leave the range as it is; a
+    /// bound column's value wins when it falls outside `min..=max`.
+    pub fn narrow<T: PartialOrd + Copy>(
+        &self,
+        row: &RowValues,
+        parse: impl Fn(&str) -> Option<T>,
+        min: T,
+        max: T,
+    ) -> (T, T) {
+        let read =
+            |column: &Option<String>| column.as_deref().and_then(|c| row.get(c)).and_then(&parse);
+        let lower = read(&self.min_column);
+        let upper = read(&self.max_column);
+
+        let low = lower.filter(|l| *l > min).unwrap_or(min);
+        let high = upper.filter(|u| *u < max).unwrap_or(max);
+        if low <= high {
+            return (low, high);
+        }
+        match lower {
+            Some(lower) => (lower, upper.filter(|u| *u >= lower).unwrap_or(lower)),
+            None => (high, high),
+        }
+    }
+}
+
+/// Character count bounds for free text.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct LengthRange {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl LengthRange {
+    pub const TEXT: LengthRange = LengthRange { min: 5, max: 12 };
+
+    /// Texts of each length from `min` to `max`, saturating at `u64::MAX`.
+    pub fn cardinality(&self) -> u64 {
+        (self.min..=self.max).fold(0u64, |total, length| {
+            total.saturating_add(texts_of_length(length))
+        })
+    }
+
+    /// Enumerates the shortest texts first.
+    pub fn nth_value(&self, mut n: u64) -> Option<String> {
+        let base = TEXT_CHARSET.len() as u64;
+        for length in self.min..=self.max {
+            let count = texts_of_length(length);
+            if n < count {
+                let mut chars = vec![TEXT_CHARSET[0]; length];
+                for slot in chars.iter_mut().rev() {
+                    *slot = TEXT_CHARSET[(n % base) as usize];
+                    n /= base;
+                }
+                return Some(String::from_utf8(chars).unwrap());
+            }
+            n -= count;
+        }
+        None
+    }
+}
+
+fn texts_of_length(length: usize) -> u64 {
+    let base = TEXT_CHARSET.len() as u64;
+    u32::try_from(length)
+        .ok()
+        .and_then(|length| base.checked_pow(length))
+        .unwrap_or(u64::MAX)
+}
+
+/// Fills each `#` in a phone pattern like "+44 #### ######" with a digit.
+pub(super) fn sample_phone_pattern(pattern: &str, rng: &mut (impl Rng + ?Sized)) -> String {
+    pattern
+        .chars()
+        .map(|c| {
+            if c == '#' {
+                char::from(b'0' + rng.random_range(0..10u8))
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// The `n`th number of a phone pattern, counting up from all zeros.
+pub(super) fn nth_phone_pattern(pattern: &str, mut n: u64) -> String {
+    let mut chars: Vec<char> = pattern.chars().collect();
+    for c in chars.iter_mut().rev() {
+        if *c == '#' {
+            *c = char::from(b'0' + (n % 10) as u8);
+            n /= 10;
+        }
+    }
+    chars.into_iter().collect()
+}
+
+fn invalid(column: &ColumnSpec, message: impl Into<String>) -> GeneratorError {
+    GeneratorError::InvalidParameter {
+        column: column.name.clone(),
+        message: message.into(),
+    }
+}
+
+/// Fails when `min` ends up above `max`. Each side is `(value, given)`, so the
+/// message can say which bound came from the type's defaults.
+fn check_order<T: PartialOrd + std::fmt::Display>(
+    column: &ColumnSpec,
+    min: (T, bool),
+    max: (T, bool),
+    relation: &str,
+) -> Result<(), GeneratorError> {
+    if min.0 <= max.0 {
+        return Ok(());
+    }
+    let describe = |(value, given): (T, bool)| {
+        if given {
+            value.to_string()
+        } else {
+            format!("{}, the default", value)
+        }
+    };
+    Err(invalid(
+        column,
+        format!(
+            "min ({}) is {} max ({})",
+            describe(min),
+            relation,
+            describe(max)
+        ),
+    ))
+}
+
+fn number_bound(
+    column: &ColumnSpec,
+    param: &str,
+    bound: &Option<Bound>,
+) -> Result<Option<f64>, GeneratorError> {
+    match bound {
+        None => Ok(None),
+        Some(Bound::Number(n)) if n.is_finite() => Ok(Some(*n)),
+        Some(other) => Err(invalid(
+            column,
+            format!("{} must be a number, got {}", param, other),
+        )),
+    }
+}
+
+fn whole_bound(
+    column: &ColumnSpec,
+    param: &str,
+    bound: &Option<Bound>,
+) -> Result<Option<i64>, GeneratorError> {
+    match number_bound(column, param, bound)? {
+        Some(n) if n.fract() != 0.0 => Err(invalid(
+            column,
+            format!("{} must be a whole number, got {}", param, n),
+        )),
+        n => Ok(n.map(|n| n as i64)),
+    }
+}
+
+/// A timestamp bound: `YYYY-MM-DDTHH:MM:SS`, or a date meaning its first
+/// second for `min` and its last for `max`.
+fn timestamp_bound(
+    column: &ColumnSpec,
+    param: &str,
+    bound: &Option<Bound>,
+) -> Result<Option<NaiveDateTime>, GeneratorError> {
+    let Some(bound) = bound else {
+        return Ok(None);
+    };
+    let parsed = match bound {
+        Bound::Text(s) => ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+            .or_else(|| {
+                let date = NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?;
+                match param {
+                    "min" => Some(date.and_time(NaiveTime::MIN)),
+                    _ => date.and_hms_opt(23, 59, 59),
+                }
+            }),
+        Bound::Number(_) => None,
+    };
+    parsed.map(Some).ok_or_else(|| {
+        invalid(
+            column,
+            format!(
+                "{} must be a date or timestamp (YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS), got {}",
+                param, bound
+            ),
+        )
+    })
+}
+
+fn date_bound(
+    column: &ColumnSpec,
+    param: &str,
+    bound: &Option<Bound>,
+) -> Result<Option<NaiveDate>, GeneratorError> {
+    let Some(bound) = bound else {
+        return Ok(None);
+    };
+    let parsed = match bound {
+        Bound::Text(s) => NaiveDate::parse_from_str(s, "%Y-%m-%d").ok(),
+        Bound::Number(_) => None,
+    };
+    parsed.map(Some).ok_or_else(|| {
+        invalid(
+            column,
+            format!("{} must be a date (YYYY-MM-DD), got {}", param, bound),
+        )
+    })
+}
+
+pub(super) fn int_range(
+    column: &ColumnSpec,
+    default: IntRange,
+) -> Result<IntRange, GeneratorError> {
+    let range = IntRange {
+        min: whole_bound(column, "min", &column.min)?.unwrap_or(default.min),
+        max: whole_bound(column, "max", &column.max)?.unwrap_or(default.max),
+    };
+    check_order(
+        column,
+        (range.min, column.min.is_some()),
+        (range.max, column.max.is_some()),
+        "greater than",
+    )?;
+    Ok(range)
+}
+
+/// Longest zero-padded width of an id, enough for any 64-bit number.
+const MAX_ID_WIDTH: usize = 20;
+
+/// How an `id` column numbers its rows, and how each id is written.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct IdParams {
+    pub strategy: Strategy,
+    pub prefix: String,
+    /// Digits numeric ids are zero-padded to.
+    pub width: usize,
+}
+
+impl IdParams {
+    /// Prefixes an id, zero-padding its digits to `width`.
+    pub fn format(&self, id: &str) -> String {
+        let (sign, digits) = id.strip_prefix('-').map_or(("", id), |d| ("-", d));
+        format!(
+            "{}{}{:0>width$}",
+            self.prefix,
+            sign,
+            digits,
+            width = self.width
+        )
+    }
+}
+
+pub(super) fn id_params(column: &ColumnSpec) -> Result<IdParams, GeneratorError> {
+    let name = column.strategy.as_deref().unwrap_or("sequence");
+    let mut strategy = Strategy::named(name).ok_or_else(|| {
+        invalid(
+            column,
+            format!(
+                "unknown id strategy '{}' (expected one of: {})",
+                name,
+                ids::STRATEGIES.join(", ")
+            ),
+        )
+    })?;
+
+    let not_for = |param: &str, given: bool| {
+        if given {
+            Err(invalid(
+                column,
+                format!("'{}' does not apply to the '{}' strategy", param, name),
+            ))
+        } else {
+            Ok(())
+        }
+    };
+    match &mut strategy {
+        Strategy::Sequence { start, step } => {
+            *start = column.start.unwrap_or(*start);
+            *step = column.step.unwrap_or(*step);
+            if *step == 0 {
+                return Err(invalid(column, "step must not be 0"));
+            }
+        }
+        Strategy::Random { min, max } => {
+            let range = int_range(
+                column,
+                IntRange {
+                    min: *min,
+                    max: *max,
+                },
+            )?;
+            (*min, *max) = (range.min, range.max);
+        }
+        _ => not_for("width", column.width.is_some())?,
+    }
+    if !matches!(strategy, Strategy::Sequence { .. }) {
+        not_for("start", column.start.is_some())?;
+        not_for("step", column.step.is_some())?;
+    }
+    if !matches!(strategy, Strategy::Random { .. }) {
+        not_for("min", column.min.is_some())?;
+        not_for("max", column.max.is_some())?;
+    }
+
+    let width = column.width.unwrap_or(0);
+    if width > MAX_ID_WIDTH {
+        return Err(invalid(
+            column,
+            format!("width must be at most {}, got {}", MAX_ID_WIDTH, width),
+        ));
+    }
+    Ok(IdParams {
+        strategy,
+        prefix: column.prefix.clone().unwrap_or_default(),
+        width,
+    })
+}
+
+pub(super) fn money_params(column: &ColumnSpec) -> Result<MoneyParams, GeneratorError> {
+    decimal_params(column, MoneyParams::default())
+}
+
+/// Decimal numbers in `min..=max`, as money without a currency unless one is
+/// given. `default` supplies whatever the column leaves out.
+pub(super) fn decimal_params(
+    column: &ColumnSpec,
+    default: MoneyParams,
+) -> Result<MoneyParams, GeneratorError> {
+    let params = MoneyParams {
+        min: number_bound(column, "min", &column.min)?.unwrap_or(default.min),
+        max: number_bound(column, "max", &column.max)?.unwrap_or(default.max),
+        precision: column.precision.map_or(default.precision, |p| p as usize),
+        currency: column.currency.clone(),
+    };
+    check_order(
+        column,
+        (params.min, column.min.is_some()),
+        (params.max, column.max.is_some()),
+        "greater than",
+    )?;
+    if params.precision > 10 {
+        return Err(invalid(
+            column,
+            format!("precision must be at most 10, got {}", params.precision),
+        ));
+    }
+    Ok(params)
+}
+
+pub(super) fn date_params(column: &ColumnSpec) -> Result<DateParams, GeneratorError> {
+    let default = DateParams::default();
+    let params = DateParams {
+        min: date_bound(column, "min", &column.min)?.unwrap_or(default.min),
+        max: date_bound(column, "max", &column.max)?.unwrap_or(default.max),
+        format: match (&column.format, locale(column)?) {
+            (Some(format), _) => format.clone(),
+            (None, Some(locale)) => locale.date_format.clone(),
+            (None, None) => default.format,
+        },
+    };
+    check_order(
+        column,
+        (params.min, column.min.is_some()),
+        (params.max, column.max.is_some()),
+        "after",
+    )?;
+    check_format(column, "date", &params.format, |format| {
+        params.min.format(format)
+    })?;
+    Ok(params)
+}
+
+pub(super) fn timestamp_params(column: &ColumnSpec) -> Result<TimestampParams, GeneratorError> {
+    let default = TimestampParams::default();
+    let params = TimestampParams {
+        min: timestamp_bound(column, "min", &column.min)?.unwrap_or(default.min),
+        max: timestamp_bound(column, "max", &column.max)?.unwrap_or(default.max),
+        format: column.format.clone().unwrap_or(default.format),
+        step: 1,
+    };
+    check_order(
+        column,
+        (params.min, column.min.is_some()),
+        (params.max, column.max.is_some()),
+        "after",
+    )?;
+    check_format(column, "timestamp", &params.format, |format| {
+        params.min.format(format)
+    })?;
+    Ok(TimestampParams {
+        step: format_step(&params.format),
+        ..params
+    })
+}
+
+pub(super) fn time_params(column: &ColumnSpec) -> Result<TimeParams, GeneratorError> {
+    let format = column
+        .format
+        .clone()
+        .unwrap_or_else(|| "%H:%M:%S".to_string());
+    check_format(column, "time", &format, |format| {
+        NaiveTime::MIN.format(format)
+    })?;
+    Ok(TimeParams {
+        step: format_step(&format),
+        format,
+    })
+}
+
+/// Fails on an empty or malformed strftime format, or on one that asks for
+/// fields the value lacks, such as the hour of a date: formatting `sample`
+/// with it must succeed, as chrono's `to_string()` panics otherwise.
+fn check_format<'a>(
+    column: &ColumnSpec,
+    what: &str,
+    format: &'a str,
+    sample: impl FnOnce(&'a str) -> DelayedFormat<StrftimeItems<'a>>,
+) -> Result<(), GeneratorError> {
+    if format.is_empty() || StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(invalid(
+            column,
+            format!("invalid {} format '{}'", what, format),
+        ));
+    }
+    if write!(String::new(), "{}", sample(format)).is_err() {
+        return Err(invalid(
+            column,
+            format!(
+                "{} format '{}' asks for fields a {} does not have",
+                what, format, what
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// A unique date column needs a format that tells every day in its range
+/// apart, or its value space would be smaller than the range suggests.
+pub(super) fn check_unique_date_format(
+    column: &ColumnSpec,
+    params: &DateParams,
+) -> Result<(), GeneratorError> {
+    // Formats repeat within a year at most, so a year and a day are enough
+    // to see whether any two days collide.
+    let end = params.max.min(params.min + Duration::days(366));
+    let mut seen = std::collections::HashSet::new();
+    for day in params.min.iter_days().take_while(|day| *day <= end) {
+        if !seen.insert(day.format(&params.format).to_string()) {
+            return Err(invalid(
+                column,
+                format!(
+                    "date format '{}' repeats values, so the column cannot be unique",
+                    params.format
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// A unique timestamp column needs a format that tells apart values an
+/// hour, half a day, a day, a week, a month, a year or a century apart, or
+/// its value space would be smaller than `cardinality` suggests.
+pub(super) fn check_unique_timestamp_format(
+    column: &ColumnSpec,
+    params: &TimestampParams,
+) -> Result<(), GeneratorError> {
+    let min = params.min;
+    let later = [
+        min.checked_add_signed(Duration::hours(1)),
+        min.checked_add_signed(Duration::hours(12)),
+        min.checked_add_signed(Duration::days(1)),
+        min.checked_add_signed(Duration::days(7)),
+        min.checked_add_months(Months::new(1)),
+        min.checked_add_months(Months::new(12)),
+        min.checked_add_months(Months::new(1200)),
+    ];
+    let first = min.format(&params.format).to_string();
+    let repeats = later
+        .into_iter()
+        .flatten()
+        .filter(|timestamp| *timestamp <= params.max)
+        .any(|timestamp| timestamp.format(&params.format).to_string() == first);
+    if repeats {
+        return Err(repeating_format(column, "timestamp", &params.format));
+    }
+    Ok(())
+}
+
+/// A unique time column needs a format that tells apart times an hour and
+/// half a day apart, such as `%H` rather than `%M` or `%I` without `%p`.
+pub(super) fn check_unique_time_format(
+    column: &ColumnSpec,
+    params: &TimeParams,
+) -> Result<(), GeneratorError> {
+    let format = |hour| {
+        NaiveTime::from_hms_opt(hour, 0, 0)
+            .expect("an hour of the day")
+            .format(&params.format)
+            .to_string()
+    };
+    if format(1) == format(0) || format(12) == format(0) {
+        return Err(repeating_format(column, "time", &params.format));
+    }
+    Ok(())
+}
+
+fn repeating_format(column: &ColumnSpec, what: &str, format: &str) -> GeneratorError {
+    invalid(
+        column,
+        format!(
+            "{} format '{}' repeats values, so the column cannot be unique",
+            what, format
+        ),
+    )
+}
+
+pub(super) fn row_bounds(column: &ColumnSpec) -> RowBounds {
+    RowBounds {
+        min_column: column.min_column.clone(),
+        max_column: column.max_column.clone(),
+    }
+}
+
+pub(super) fn length_range(column: &ColumnSpec) -> Result<LengthRange, GeneratorError> {
+    let default = LengthRange::TEXT;
+    let range = LengthRange {
+        min: column.min_length.unwrap_or(default.min),
+        max: column.max_length.unwrap_or(default.max),
+    };
+    check_order(
+        column,
+        (range.min, column.min_length.is_some()),
+        (range.max, column.max_length.is_some()),
+        "greater than",
+    )?;
+    Ok(range)
+}
+
+pub(super) fn phone_pattern(column: &ColumnSpec) -> Result<Option<String>, GeneratorError> {
+    match &column.format {
+        Some(pattern) if !pattern.contains('#') => Err(invalid(
+            column,
+            format!(
+                "phone format '{}' needs at least one '#' digit placeholder",
+                pattern
+            ),
+        )),
+        pattern => Ok(pattern.clone()),
+    }
+}
+
+/// The column's locale pack, if it names one.
+pub(super) fn locale(column: &ColumnSpec) -> Result<Option<&'static Locale>, GeneratorError> {
+    let Some(code) = &column.locale else {
+        return Ok(None);
+    };
+    match locale::find(code) {
+        Some(locale) => Ok(Some(locale)),
+        None => Err(invalid(
+            column,
+            format!(
+                "unknown locale '{}' (expected one of: {})",
+                code,
+                locale::locale_codes().join(", ")
+            ),
+        )),
+    }
+}
+
+pub(super) fn regex_pattern(column: &ColumnSpec) -> Result<Pattern, GeneratorError> {
+    let Some(pattern) = &column.pattern else {
+        return Err(invalid(column, "type 'regex' requires a 'pattern'"));
+    };
+    Pattern::parse(pattern).map_err(|message| {
+        invalid(
+            column,
+            format!("invalid pattern '{}': {}", pattern, message),
+        )
+    })
+}
+
+pub(super) fn dictionary_entries(
+    column: &ColumnSpec,
+) -> Result<&[DictionaryEntry], GeneratorError> {
+    match (&column.dictionary, &column.dictionary_entries) {
+        (None, _) => Err(invalid(
+            column,
+            "type 'dictionary' requires a 'dictionary' name",
+        )),
+        (Some(name), None) => Err(invalid(column, format!("no dictionary named '{}'", name))),
+        (Some(_), Some(entries)) => Ok(entries),
+    }
+}
+
+pub(super) fn enum_values(column: &ColumnSpec) -> Result<Vec<String>, GeneratorError> {
+    match &column.values {
+        Some(values) if !values.is_empty() => Ok(values.clone()),
+        Some(_) => Err(invalid(column, "'values' must not be empty")),
+        None => Err(invalid(column, "type 'enum' requires a 'values' list")),
+    }
+}
+
+/// The weights of an enum's `values`: one non-negative number per value, at
+/// least one of them above 0.
+pub(super) fn enum_weights(
+    column: &ColumnSpec,
+    values: &[String],
+) -> Result<Option<Vec<f64>>, GeneratorError> {
+    let Some(weights) = &column.weights else {
+        return Ok(None);
+    };
+    if weights.len() != values.len() {
+        return Err(invalid(
+            column,
+            format!(
+                "'weights' has {} numbers but there are {} values",
+                weights.len(),
+                values.len()
+            ),
+        ));
+    }
+    let weighted = values
+        .iter()
+        .map(String::as_str)
+        .zip(weights.iter().copied());
+    checked_weights(column, weighted).map(Some)
+}
+
+/// The weights of a dictionary's entries, held to the same rules as an
+/// enum's: every entry weighted or none.
+pub(super) fn dictionary_weights(
+    column: &ColumnSpec,
+    entries: &[DictionaryEntry],
+) -> Result<Option<Vec<f64>>, GeneratorError> {
+    if entries.iter().all(|entry| entry.weight.is_none()) {
+        return Ok(None);
+    }
+    let weighted = entries
+        .iter()
+        .map(|entry| (entry.value.as_str(), entry.weight));
+    checked_weights(column, weighted).map(Some)
+}
+
+/// Non-negative weights, one per value, at least one of them above 0.
+fn checked_weights<'a>(
+    column: &ColumnSpec,
+    weighted: impl Iterator<Item = (&'a str, Option<f64>)>,
+) -> Result<Vec<f64>, GeneratorError> {
+    let mut checked = Vec::new();
+    for (value, weight) in weighted {
+        let Some(weight) = weight else {
+            return Err(invalid(
+                column,
+                format!(
+                    "'{}' has no weight; give every value a weight or none",
+                    value
+                ),
+            ));
+        };
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(invalid(
+                column,
+                format!(
+                    "weight of '{}' must be a number of at least 0, got {}",
+                    value, weight
+                ),
+            ));
+        }
+        checked.push(weight);
+    }
+    if checked.iter().all(|w| *w == 0.0) {
+        return Err(invalid(column, "at least one weight must be above 0"));
+    }
+    Ok(checked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn spec(type_name: &str) -> ColumnSpec {
+        ColumnSpec::new("column", type_name)
+    }
+
+    fn message(err: GeneratorError) -> String {
+        match err {
+            GeneratorError::InvalidParameter { message, .. } => message,
+            other => panic!("unexpected error {}", other),
+        }
+    }
+
+    #[test]
+    fn test_int_range_defaults_and_overrides() {
+        assert_eq!(
+            int_range(&spec("age"), IntRange::AGE).unwrap(),
+            IntRange::AGE
+        );
+
+        let mut column = spec("age");
+        column.min = Some(Bound::Number(30.0));
+        assert_eq!(
+            int_range(&column, IntRange::AGE).unwrap(),
+            IntRange { min: 30, max: 80 }
+        );
+    }
+
+    #[test]
+    fn test_int_range_errors() {
+        let mut column = spec("age");
+        column.min = Some(Bound::Number(90.0));
+        assert_eq!(
+            message(int_range(&column, IntRange::AGE).unwrap_err()),
+            "min (90) is greater than max (80, the default)"
+        );
+
+        column.min = Some(Bound::Number(20.5));
+        assert_eq!(
+            message(int_range(&column, IntRange::AGE).unwrap_err()),
+            "min must be a whole number, got 20.5"
+        );
+
+        column.min = Some(Bound::Text("young".to_string()));
+        assert_eq!(
+            message(int_range(&column, IntRange::AGE).unwrap_err()),
+            "min must be a number, got 'young'"
+        );
+    }
+
+    #[test]
+    fn test_money_params_sample() {
+        let mut column = spec("money");
+        column.min = Some(Bound::Number(1.0));
+        column.max = Some(Bound::Number(2.0));
+        column.precision = Some(3);
+        column.currency = Some("€".to_string());
+        let params = money_params(&column).unwrap();
+
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..50 {
+            let value = params.sample(&mut rng);
+            let amount = value.strip_prefix('€').unwrap();
+            assert_eq!(amount.split('.').nth(1).unwrap().len(), 3);
+            assert!((1.0..=2.0).contains(&amount.parse::<f64>().unwrap()));
+        }
+
+        column.precision = Some(11);
+        assert_eq!(
+            message(money_params(&column).unwrap_err()),
+            "precision must be at most 10, got 11"
+        );
+    }
+
+    #[test]
+    fn test_date_params_sample_covers_whole_months() {
+        let mut column = spec("date");
+        column.min = Some(Bound::Text("2024-01-29".to_string()));
+        column.max = Some(Bound::Text("2024-01-31".to_string()));
+        column.format = Some("%d/%m/%Y".to_string());
+        let params = date_params(&column).unwrap();
+
+        let mut rng = StdRng::seed_from_u64(3);
+        let values: Vec<String> = (0..100).map(|_| params.sample(&mut rng)).collect();
+        assert!(values.iter().all(|v| v.ends_with("/01/2024")));
+        assert!(values.contains(&"31/01/2024".to_string()));
+    }
+
+    #[test]
+    fn test_date_params_errors() {
+        let mut column = spec("date");
+        column.min = Some(Bound::Text("2030-01-01".to_string()));
+        assert_eq!(
+            message(date_params(&column).unwrap_err()),
+            "min (2030-01-01) is after max (2025-12-31, the default)"
+        );
+
+        column.min = Some(Bound::Text("01/02/2020".to_string()));
+        assert_eq!(
+            message(date_params(&column).unwrap_err()),
+            "min must be a date (YYYY-MM-DD), got '01/02/2020'"
+        );
+
+        column.min = None;
+        column.format = Some("%Y-%Q".to_string());
+        assert_eq!(
+            message(date_params(&column).unwrap_err()),
+            "invalid date format '%Y-%Q'"
+        );
+
+        column.format = Some("%Y-%m-%d %H:%M".to_string());
+        assert_eq!(
+            message(date_params(&column).unwrap_err()),
+            "date format '%Y-%m-%d %H:%M' asks for fields a date does not have"
+        );
+    }
+
+    #[test]
+    fn test_row_bounds_narrow() {
+        let headers = vec!["low".to_string(), "high".to_string()];
+        let bounds = RowBounds {
+            min_column: Some("low".to_string()),
+            max_column: Some("high".to_string()),
+        };
+        let narrow = |low: &str, high: &str| {
+            let cells = vec![Some(low.to_string()), Some(high.to_string())];
+            let row = RowValues::new(&headers, &cells);
+            bounds.narrow(&row, |v| v.parse::<i64>().ok(), 0, 100)
+        };
+
+        assert_eq!(narrow("10", "20"), (10, 20));
+        assert_eq!(narrow("", "n/a"), (0, 100));
+        assert_eq!(narrow("-5", "200"), (0, 100));
+        // Row values win over the column's own range.
+        assert_eq!(narrow("150", ""), (150, 150));
+        assert_eq!(narrow("", "-10"), (-10, -10));
+        assert_eq!(narrow("150", "160"), (150, 160));
+        assert_eq!(narrow("30", "20"), (30, 30));
+    }
+
+    #[test]
+    fn test_length_range() {
+        let mut column = spec("text");
+        column.min_length = Some(2);
+        column.max_length = Some(3);
+        assert_eq!(
+            length_range(&column).unwrap(),
+            LengthRange { min: 2, max: 3 }
+        );
+
+        column.max_length = None;
+        column.min_length = Some(20);
+        assert_eq!(
+            message(length_range(&column).unwrap_err()),
+            "min (20) is greater than max (12, the default)"
+        );
+    }
+
+    #[test]
+    fn test_phone_pattern() {
+        let mut column = spec("phone");
+        assert_eq!(phone_pattern(&column).unwrap(), None);
+
+        column.format = Some("+44 #### ######".to_string());
+        let pattern = phone_pattern(&column).unwrap().unwrap();
+        let mut rng = StdRng::seed_from_u64(9);
+        let phone = sample_phone_pattern(&pattern, &mut rng);
+        assert!(phone.starts_with("+44 "));
+        assert_eq!(phone.len(), 15);
+        assert!(phone[4..].chars().all(|c| c.is_ascii_digit() || c == ' '));
+
+        column.format = Some("+44".to_string());
+        assert!(phone_pattern(&column).is_err());
+    }
+
+    #[test]
+    fn test_enumerated_values() {
+        assert_eq!(IntRange { min: 18, max: 20 }.cardinality(), 3);
+
+        let money = MoneyParams {
+            min: 1.0,
+            max: 2.0,
+            precision: 1,
+            ..MoneyParams::default()
+        };
+        assert_eq!(money.cardinality(), 11);
+        assert_eq!(money.nth_value(0), "1.0");
+        assert_eq!(money.nth_value(10), "2.0");
+
+        let date = DateParams::default();
+        assert_eq!(date.nth_value(0).unwrap(), "2020-01-01");
+        assert_eq!(date.nth_value(31).unwrap(), "2020-02-01");
+
+        let text = LengthRange { min: 1, max: 2 };
+        assert_eq!(text.cardinality(), 62 + 62 * 62);
+        let texts: std::collections::HashSet<_> = (0..text.cardinality())
+            .map(|n| text.nth_value(n).unwrap())
+            .collect();
+        assert_eq!(texts.len() as u64, text.cardinality());
+        assert!(text.nth_value(text.cardinality()).is_none());
+
+        assert_eq!(nth_phone_pattern("+1 ###", 0), "+1 000");
+        assert_eq!(nth_phone_pattern("+1 ###", 42), "+1 042");
+    }
+
+    #[test]
+    fn test_id_params() {
+        let id = |strategy: Option<&str>| ColumnSpec {
+            strategy: strategy.map(String::from),
+            ..spec("id")
+        };
+        let params = id_params(&ColumnSpec {
+            step: Some(10),
+            prefix: Some("INV-".to_string()),
+            width: Some(4),
+            ..id(None)
+        })
+        .unwrap();
+        assert_eq!(params.strategy, Strategy::Sequence { start: 1, step: 10 });
+        assert_eq!(params.format("42"), "INV-0042");
+
+        let error = |column: ColumnSpec| message(id_params(&column).unwrap_err());
+        assert_eq!(
+            error(id(Some("guid"))),
+            "unknown id strategy 'guid' (expected one of: sequence, random, uuid_v4, uuid_v7, ulid, snowflake)"
+        );
+        assert_eq!(
+            error(ColumnSpec {
+                step: Some(0),
+                ..id(None)
+            }),
+            "step must not be 0"
+        );
+        assert_eq!(
+            error(ColumnSpec {
+                start: Some(5),
+                ..id(Some("random"))
+            }),
+            "'start' does not apply to the 'random' strategy"
+        );
+        assert_eq!(
+            error(ColumnSpec {
+                width: Some(8),
+                ..id(Some("uuid_v7"))
+            }),
+            "'width' does not apply to the 'uuid_v7' strategy"
+        );
+        assert_eq!(
+            error(ColumnSpec {
+                max: Some(Bound::Number(5.0)),
+                ..id(None)
+            }),
+            "'max' does not apply to the 'sequence' strategy"
+        );
+        assert_eq!(
+            error(ColumnSpec {
+                min: Some(Bound::Number(5.0)),
+                max: Some(Bound::Number(1.0)),
+                ..id(Some("random"))
+            }),
+            "min (5) is greater than max (1)"
+        );
+        assert_eq!(
+            error(ColumnSpec {
+                width: Some(21),
+                ..id(None)
+            }),
+            "width must be at most 20, got 21"
+        );
+    }
+
+    #[test]
+    fn test_enum_weights() {
+        let values: Vec<String> = ["active", "deleted"].map(String::from).to_vec();
+        let weighted = |weights: Vec<Option<f64>>| {
+            let mut column = spec("enum");
+            column.weights = Some(weights);
+            enum_weights(&column, &values)
+        };
+        assert_eq!(enum_weights(&spec("enum"), &values).unwrap(), None);
+        assert_eq!(
+            weighted(vec![Some(95.0), Some(5.0)]).unwrap(),
+            Some(vec![95.0, 5.0])
+        );
+        assert_eq!(
+            message(weighted(vec![Some(1.0)]).unwrap_err()),
+            "'weights' has 1 numbers but there are 2 values"
+        );
+        assert_eq!(
+            message(weighted(vec![Some(1.0), Some(-1.0)]).unwrap_err()),
+            "weight of 'deleted' must be a number of at least 0, got -1"
+        );
+        assert_eq!(
+            message(weighted(vec![None, Some(1.0)]).unwrap_err()),
+            "'active' has no weight; give every value a weight or none"
+        );
+        assert_eq!(
+            message(weighted(vec![Some(0.0), Some(0.0)]).unwrap_err()),
+            "at least one weight must be above 0"
+        );
+    }
+
+    #[test]
+    fn test_dictionary_weights() {
+        let entry = |value: &str, weight: Option<f64>| DictionaryEntry {
+            value: value.to_string(),
+            weight,
+        };
+        let column = spec("dictionary");
+        assert_eq!(
+            dictionary_weights(&column, &[entry("ENG", None), entry("OPS", None)]).unwrap(),
+            None
+        );
+        assert_eq!(
+            dictionary_weights(&column, &[entry("ENG", Some(3.0)), entry("OPS", Some(1.0))])
+                .unwrap(),
+            Some(vec![3.0, 1.0])
+        );
+        assert_eq!(
+            message(
+                dictionary_weights(&column, &[entry("ENG", Some(3.0)), entry("OPS", None)])
+                    .unwrap_err()
+            ),
+            "'OPS' has no weight; give every value a weight or none"
+        );
+    }
+
+    #[test]
+    fn test_unique_date_format() {
+        let mut column = spec("date");
+        column.unique = true;
+        assert!(check_unique_date_format(&column, &DateParams::default()).is_ok());
+
+        column.format = Some("%Y-%m".to_string());
+        let params = date_params(&column).unwrap();
+        assert_eq!(
+            message(check_unique_date_format(&column, &params).unwrap_err()),
+            "date format '%Y-%m' repeats values, so the column cannot be unique"
+        );
+    }
+}
@@ -0,0 +1,692 @@
+//! Strings matching a subset of regular expression syntax, for `regex`
+//! columns, e.g. `ORD-[0-9]{6}-[A-Z]{2}`.
+//!
+//! Supported: literals and escaped punctuation, `.`, classes with ranges and
+//! negation (`[A-Z0-9_]`, `[^aeiou]`), POSIX classes inside them
+//! (`[[:alpha:]_]`), `\d \w \s` and their negations, groups (`(...)`,
+//! `(?:...)`, `(?<name>...)`), `|`, the quantifiers `? * + {n} {n,} {n,m}`
+//! (lazy forms too), and `^`/`$` around the whole pattern. Anything else,
+//! such as lookaround, backreferences or word boundaries, is rejected with its
+//! position. `.` and negated classes draw from printable ASCII, and `\s` and
+//! `[:space:]` are a space.
+use rand::Rng;
+
+/// Extra repetitions allowed by `*`, `+` and `{n,}`.
+const UNBOUNDED_EXTRA: u32 = 8;
+
+/// Largest repetition count a quantifier may give.
+const MAX_REPEAT: u32 = 1000;
+
+/// Longest value a pattern may generate. Nested repeats multiply, so each
+/// quantifier staying under `MAX_REPEAT` is not enough on its own.
+const MAX_LENGTH: u64 = 10_000;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Literal(char),
+    /// One of the characters, sorted and distinct.
+    Class(Vec<char>),
+    Concat(Vec<Node>),
+    Alternation(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: u32,
+        max: u32,
+    },
+}
+
+/// A parsed pattern that generates and enumerates the strings it matches.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Pattern {
+    root: Node,
+}
+
+impl Pattern {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        if text.is_empty() {
+            return Err("pattern must not be empty".to_string());
+        }
+        let mut chars: Vec<char> = text.chars().collect();
+        // Every generated value is a whole match, so outer anchors are no-ops.
+        let mut offset = 0;
+        if chars.first() == Some(&'^') {
+            chars.remove(0);
+            offset = 1;
+        }
+        if chars.last() == Some(&'$') && !ends_escaped(&chars) {
+            chars.pop();
+        }
+
+        let mut parser = Parser {
+            chars: &chars,
+            position: 0,
+            offset,
+        };
+        let root = parser.alternation()?;
+        if let Some(c) = parser.peek() {
+            return Err(format!("unmatched '{}' at position {}", c, parser.at()));
+        }
+        let length = root.max_length();
+        if length > MAX_LENGTH {
+            return Err(format!(
+                "pattern generates values of up to {} characters, above {}",
+                length, MAX_LENGTH
+            ));
+        }
+        Ok(Pattern { root })
+    }
+
+    pub fn sample(&self, rng: &mut (impl Rng + ?Sized)) -> String {
+        let mut out = String::new();
+        self.root.sample(rng, &mut out);
+        out
+    }
+
+    /// How many strings the pattern matches, saturating at `u64::MAX`.
+    /// Alternatives matching the same string are counted once each.
+    pub fn cardinality(&self) -> u64 {
+        self.root.count()
+    }
+
+    /// The `n`th matching string, enumerating the leftmost parts slowest.
+    pub fn nth_value(&self, n: u64) -> Option<String> {
+        if n >= self.cardinality() {
+            return None;
+        }
+        let mut out = String::new();
+        self.root.write_nth(n, &mut out);
+        Some(out)
+    }
+}
+
+/// Whether the last character is escaped by an odd run of backslashes.
+fn ends_escaped(chars: &[char]) -> bool {
+    let backslashes = chars[..chars.len() - 1]
+        .iter()
+        .rev()
+        .take_while(|&&c| c == '\\')
+        .count();
+    backslashes % 2 == 1
+}
+
+impl Node {
+    fn sample(&self, rng: &mut (impl Rng + ?Sized), out: &mut String) {
+        match self {
+            Node::Literal(c) => out.push(*c),
+            Node::Class(chars) => out.push(chars[rng.random_range(0..chars.len())]),
+            Node::Concat(nodes) => {
+                for node in nodes {
+                    node.sample(rng, out);
+                }
+            }
+            Node::Alternation(branches) => {
+                branches[rng.random_range(0..branches.len())].sample(rng, out)
+            }
+            Node::Repeat { node, min, max } => {
+                for _ in 0..rng.random_range(*min..=*max) {
+                    node.sample(rng, out);
+                }
+            }
+        }
+    }
+
+    fn count(&self) -> u64 {
+        match self {
+            Node::Literal(_) => 1,
+            Node::Class(chars) => chars.len() as u64,
+            Node::Concat(nodes) => nodes
+                .iter()
+                .fold(1u64, |total, node| total.saturating_mul(node.count())),
+            Node::Alternation(branches) => branches
+                .iter()
+                .fold(0u64, |total, node| total.saturating_add(node.count())),
+            Node::Repeat { node, min, max } => {
+                let each = node.count();
+                (*min..=*max).fold(0u64, |total, times| {
+                    total.saturating_add(each.saturating_pow(times))
+                })
+            }
+        }
+    }
+
+    /// Characters in the longest string, saturating at `u64::MAX`.
+    fn max_length(&self) -> u64 {
+        match self {
+            Node::Literal(_) | Node::Class(_) => 1,
+            Node::Concat(nodes) => nodes
+                .iter()
+                .fold(0u64, |total, node| total.saturating_add(node.max_length())),
+            Node::Alternation(branches) => branches.iter().map(Node::max_length).max().unwrap_or(0),
+            Node::Repeat { node, max, .. } => node.max_length().saturating_mul(*max as u64),
+        }
+    }
+
+    /// Writes the `n`th string, `n` below `count()`.
+    fn write_nth(&self, mut n: u64, out: &mut String) {
+        match self {
+            Node::Literal(c) => out.push(*c),
+            Node::Class(chars) => out.push(chars[n as usize]),
+            Node::Concat(nodes) => write_digits(nodes.iter(), n, out),
+            Node::Alternation(branches) => {
+                for branch in branches {
+                    let count = branch.count();
+                    if n < count {
+                        return branch.write_nth(n, out);
+                    }
+                    n -= count;
+                }
+            }
+            Node::Repeat { node, min, max } => {
+                let each = node.count();
+                for times in *min..=*max {
+                    let count = each.saturating_pow(times);
+                    if n < count {
+                        let copies = std::iter::repeat_n(node.as_ref(), times as usize);
+                        return write_digits(copies, n, out);
+                    }
+                    n -= count;
+                }
+            }
+        }
+    }
+}
+
+/// Writes a sequence of nodes as the digits of `n` in a mixed radix, the
+/// first node being the most significant.
+fn write_digits<'a>(
+    nodes: impl DoubleEndedIterator<Item = &'a Node>,
+    mut n: u64,
+    out: &mut String,
+) {
+    let mut digits: Vec<(&Node, u64)> = nodes
+        .rev()
+        .map(|node| {
+            let count = node.count().max(1);
+            let digit = n % count;
+            n /= count;
+            (node, digit)
+        })
+        .collect();
+    digits.reverse();
+    for (node, digit) in digits {
+        node.write_nth(digit, out);
+    }
+}
+
+/// `.` and negated classes draw from printable ASCII.
+fn printable() -> impl Iterator<Item = char> {
+    ' '..='~'
+}
+
+fn shorthand_class(letter: char) -> Option<Vec<char>> {
+    let chars: Vec<char> = match letter.to_ascii_lowercase() {
+        'd' => ('0'..='9').collect(),
+        'w' => ('0'..='9')
+            .chain('A'..='Z')
+            .chain(std::iter::once('_'))
+            .chain('a'..='z')
+            .collect(),
+        's' => vec![' '],
+        _ => return None,
+    };
+    if letter.is_ascii_uppercase() {
+        Some(printable().filter(|c| !chars.contains(c)).collect())
+    } else {
+        Some(chars)
+    }
+}
+
+/// The characters of a POSIX class such as `[:alpha:]`, within printable
+/// ASCII.
+fn posix_class(name: &str) -> Option<Vec<char>> {
+    let keep = |test: fn(&char) -> bool| Some(printable().filter(test).collect());
+    match name {
+        "alpha" => keep(char::is_ascii_alphabetic),
+        "digit" => keep(char::is_ascii_digit),
+        "alnum" => keep(char::is_ascii_alphanumeric),
+        "upper" => keep(char::is_ascii_uppercase),
+        "lower" => keep(char::is_ascii_lowercase),
+        "punct" => keep(char::is_ascii_punctuation),
+        "xdigit" => keep(char::is_ascii_hexdigit),
+        "graph" => keep(char::is_ascii_graphic),
+        "print" => Some(printable().collect()),
+        "space" | "blank" => Some(vec![' ']),
+        "word" => shorthand_class('w'),
+        _ => None,
+    }
+}
+
+/// Constructs that are valid regex but cannot be generated from.
+fn unsupported_escape(letter: char) -> Option<&'static str> {
+    Some(match letter {
+        'b' | 'B' => "word boundary",
+        '1'..='9' | 'k' => "backreference",
+        'p' | 'P' => "Unicode class",
+        'A' | 'z' | 'Z' | 'G' => "anchor",
+        _ => return None,
+    })
+}
+
+struct Parser<'a> {
+    chars: &'a [char],
+    position: usize,
+    /// Characters dropped from the front, for 1-based positions in the
+    /// original pattern.
+    offset: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn at(&self) -> usize {
+        self.position + self.offset + 1
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn unsupported(&self, construct: &str, what: &str) -> String {
+        format!(
+            "unsupported {} '{}' at position {}",
+            what,
+            construct,
+            self.at()
+        )
+    }
+
+    fn alternation(&mut self) -> Result<Node, String> {
+        let mut branches = vec![self.concat()?];
+        while self.eat('|') {
+            branches.push(self.concat()?);
+        }
+        Ok(if branches.len() == 1 {
+            branches.pop().unwrap()
+        } else {
+            Node::Alternation(branches)
+        })
+    }
+
+    fn concat(&mut self) -> Result<Node, String> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            nodes.push(self.quantified(atom)?);
+        }
+        Ok(if nodes.len() == 1 {
+            nodes.pop().unwrap()
+        } else {
+            Node::Concat(nodes)
+        })
+    }
+
+    fn atom(&mut self) -> Result<Node, String> {
+        let c = self.peek().expect("concat stops at the end");
+        match c {
+            '(' => self.group(),
+            '[' => self.class(),
+            '.' => {
+                self.position += 1;
+                Ok(Node::Class(printable().collect()))
+            }
+            '\\' => self.escape(),
+            '*' | '+' | '?' | '{' => Err(format!("nothing to repeat at position {}", self.at())),
+            '^' | '$' => Err(self.unsupported(&c.to_string(), "anchor")),
+            c => {
+                self.position += 1;
+                Ok(Node::Literal(c))
+            }
+        }
+    }
+
+    fn group(&mut self) -> Result<Node, String> {
+        let start = self.at();
+        self.position += 1;
+        if self.eat('?') {
+            let rest: String = self.chars[self.position..].iter().take(2).collect();
+            if rest.starts_with(':') {
+                self.position += 1;
+            } else if rest.starts_with('<') && !rest.starts_with("<=") && !rest.starts_with("<!")
+                || rest.starts_with("P<")
+            {
+                // A named group generates like any other.
+                while let Some(c) = self.peek() {
+                    self.position += 1;
+                    if c == '>' {
+                        break;
+                    }
+                }
+            } else {
+                let what = match rest.as_str() {
+                    r if r.starts_with('=') || r.starts_with('!') => "lookahead",
+                    "<=" | "<!" => "lookbehind",
+                    _ => "group flags",
+                };
+                self.position -= 1;
+                return Err(format!(
+                    "unsupported {} at position {}",
+                    what,
+                    self.at() - 1
+                ));
+            }
+        }
+        let inner = self.alternation()?;
+        if !self.eat(')') {
+            return Err(format!("missing ')' for the '(' at position {}", start));
+        }
+        Ok(inner)
+    }
+
+    fn escape(&mut self) -> Result<Node, String> {
+        let Some(&letter) = self.chars.get(self.position + 1) else {
+            return Err(format!("pattern ends with '\\' at position {}", self.at()));
+        };
+        if let Some(what) = unsupported_escape(letter) {
+            return Err(self.unsupported(&format!("\\{}", letter), what));
+        }
+        let node = match letter {
+            'n' => Node::Literal('\n'),
+            't' => Node::Literal('\t'),
+            c if c.is_ascii_alphanumeric() => match shorthand_class(c) {
+                Some(chars) => Node::Class(chars),
+                None => {
+                    return Err(format!(
+                        "unknown escape '\\{}' at position {}",
+                        c,
+                        self.at()
+                    ));
+                }
+            },
+            c => Node::Literal(c),
+        };
+        self.position += 2;
+        Ok(node)
+    }
+
+    fn class(&mut self) -> Result<Node, String> {
+        let start = self.at();
+        self.position += 1;
+        let negated = self.eat('^');
+        let mut chars = Vec::new();
+        let mut first = true;
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(format!("missing ']' for the '[' at position {}", start));
+            };
+            if c == ']' && !first {
+                self.position += 1;
+                break;
+            }
+            first = false;
+
+            if c == '[' && self.chars.get(self.position + 1) == Some(&':') {
+                chars.extend(self.posix_class()?);
+                continue;
+            }
+
+            let low = if c == '\\' {
+                match self.escape()? {
+                    Node::Literal(c) => c,
+                    Node::Class(class) => {
+                        chars.extend(class);
+                        continue;
+                    }
+                    _ => unreachable!("escapes are literals or classes"),
+                }
+            } else {
+                self.position += 1;
+                c
+            };
+
+            let is_range = self.peek() == Some('-')
+                && self
+                    .chars
+                    .get(self.position + 1)
+                    .is_some_and(|&next| next != ']');
+            if !is_range {
+                chars.push(low);
+                continue;
+            }
+            self.position += 1;
+            let high = if self.peek() == Some('\\') {
+                match self.escape()? {
+                    Node::Literal(c) => c,
+                    _ => return Err(format!("invalid range end at position {}", self.at() - 2)),
+                }
+            } else {
+                let high = self.peek().unwrap();
+                self.position += 1;
+                high
+            };
+            if high < low {
+                return Err(format!(
+                    "range '{}-{}' is out of order at position {}",
+                    low,
+                    high,
+                    self.at() - 3
+                ));
+            }
+            chars.extend(low..=high);
+        }
+
+        if negated {
+            chars = printable().filter(|c| !chars.contains(c)).collect();
+        }
+        chars.sort_unstable();
+        chars.dedup();
+        if chars.is_empty() {
+            return Err(format!("class at position {} matches nothing", start));
+        }
+        Ok(Node::Class(chars))
+    }
+
+    /// `[:name:]` inside a class.
+    fn posix_class(&mut self) -> Result<Vec<char>, String> {
+        let start = self.at();
+        let rest = &self.chars[self.position + 2..];
+        let Some(length) = rest.windows(2).position(|pair| pair == [':', ']']) else {
+            return Err(format!("missing ':]' for the '[:' at position {}", start));
+        };
+        let name: String = rest[..length].iter().collect();
+        let chars = posix_class(&name)
+            .ok_or_else(|| format!("unknown POSIX class '[:{}:]' at position {}", name, start))?;
+        self.position += length + 4;
+        Ok(chars)
+    }
+
+    fn quantified(&mut self, atom: Node) -> Result<Node, String> {
+        let start = self.at();
+        let (min, max) = match self.peek() {
+            Some('?') => (0, 1),
+            Some('*') => (0, UNBOUNDED_EXTRA),
+            Some('+') => (1, 1 + UNBOUNDED_EXTRA),
+            Some('{') => return self.braces(atom),
+            _ => return Ok(atom),
+        };
+        self.position += 1;
+        self.finish_repeat(atom, min, max, start)
+    }
+
+    /// `{n}`, `{n,}` or `{n,m}`.
+    fn braces(&mut self, atom: Node) -> Result<Node, String> {
+        let start = self.at();
+        let Some(length) = self.chars[self.position..].iter().position(|&c| c == '}') else {
+            return Err(format!("missing '}}' for the '{{' at position {}", start));
+        };
+        let inner: String = self.chars[self.position + 1..self.position + length]
+            .iter()
+            .collect();
+        let invalid = || format!("invalid repetition '{{{}}}' at position {}", inner, start);
+        let number = |s: &str| s.trim().parse::<u32>().map_err(|_| invalid());
+        let (min, max) = match inner.split_once(',') {
+            None => {
+                let n = number(&inner)?;
+                (n, n)
+            }
+            Some((min, "")) => {
+                let min = number(min)?;
+                (min, min.saturating_add(UNBOUNDED_EXTRA))
+            }
+            Some((min, max)) => (number(min)?, number(max)?),
+        };
+        if min > max {
+            return Err(format!(
+                "repetition '{{{}}}' has min above max at position {}",
+                inner, start
+            ));
+        }
+        if max > MAX_REPEAT {
+            return Err(format!(
+                "repetition '{{{}}}' at position {} is above {}",
+                inner, start, MAX_REPEAT
+            ));
+        }
+        self.position += length + 1;
+        self.finish_repeat(atom, min, max, start)
+    }
+
+    /// Skips a lazy or possessive marker, which matches the same strings,
+    /// and rejects a second quantifier.
+    fn finish_repeat(
+        &mut self,
+        atom: Node,
+        min: u32,
+        max: u32,
+        start: usize,
+    ) -> Result<Node, String> {
+        if !self.eat('?') {
+            self.eat('+');
+        }
+        if matches!(self.peek(), Some('?' | '*' | '+' | '{')) {
+            return Err(format!(
+                "quantifier at position {} follows the one at position {}",
+                self.at(),
+                start
+            ));
+        }
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn samples(pattern: &str, count: usize) -> Vec<String> {
+        let pattern = Pattern::parse(pattern).unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        (0..count).map(|_| pattern.sample(&mut rng)).collect()
+    }
+
+    #[test]
+    fn test_sample_matches_pattern() {
+        for value in samples(r"^ORD-[0-9]{6}-[A-Z]{2}$", 50) {
+            assert_eq!(value.len(), 13, "{}", value);
+            assert!(value.starts_with("ORD-"));
+            assert!(value[4..10].chars().all(|c| c.is_ascii_digit()));
+            assert_eq!(&value[10..11], "-");
+            assert!(value[11..].chars().all(|c| c.is_ascii_uppercase()));
+        }
+        for value in samples(r"(?:SW|NW)\d{1,2} \d[A-HJ-NP-Z]{2}", 50) {
+            assert!(value.starts_with("SW") || value.starts_with("NW"));
+            assert!(!value.contains('I') && !value.contains('O'));
+        }
+        for value in samples(r"[[:upper:][:digit:]_]{3}[^[:alnum:]]", 50) {
+            let (head, tail) = value.split_at(3);
+            assert!(
+                head.chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+            );
+            assert!(!tail.chars().next().unwrap().is_ascii_alphanumeric());
+        }
+        for value in samples(r"[^0-9a-z]x?\.\w+", 50) {
+            assert!(!value.chars().next().unwrap().is_ascii_digit());
+            assert!(value.contains('.'));
+        }
+    }
+
+    #[test]
+    fn test_cardinality_and_enumeration() {
+        let pattern = Pattern::parse("[ab]{1,2}|c").unwrap();
+        assert_eq!(pattern.cardinality(), 7);
+        let values: Vec<String> = (0..7).map(|n| pattern.nth_value(n).unwrap()).collect();
+        assert_eq!(values, vec!["a", "b", "aa", "ab", "ba", "bb", "c"]);
+        assert_eq!(pattern.nth_value(7), None);
+
+        assert_eq!(Pattern::parse(r"SKU-\d{4}").unwrap().cardinality(), 10_000);
+        assert_eq!(
+            Pattern::parse(r"SKU-\d{4}").unwrap().nth_value(42).unwrap(),
+            "SKU-0042"
+        );
+        assert_eq!(Pattern::parse(".{100}").unwrap().cardinality(), u64::MAX);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |pattern: &str| Pattern::parse(pattern).unwrap_err();
+        assert_eq!(error(""), "pattern must not be empty");
+        assert_eq!(error("a(?=b)"), "unsupported lookahead at position 2");
+        assert_eq!(error("(?<!a)b"), "unsupported lookbehind at position 1");
+        assert_eq!(error("(?i)abc"), "unsupported group flags at position 1");
+        assert_eq!(
+            error(r"(a)\1"),
+            r"unsupported backreference '\1' at position 4"
+        );
+        assert_eq!(
+            error(r"\bword"),
+            r"unsupported word boundary '\b' at position 1"
+        );
+        assert_eq!(error("a^b"), "unsupported anchor '^' at position 2");
+        assert_eq!(error(r"\q"), r"unknown escape '\q' at position 1");
+        assert_eq!(error("*a"), "nothing to repeat at position 1");
+        assert_eq!(
+            error("a**"),
+            "quantifier at position 3 follows the one at position 2"
+        );
+        assert_eq!(
+            error("a{3,1}"),
+            "repetition '{3,1}' has min above max at position 2"
+        );
+        assert_eq!(
+            error("a{2000}"),
+            "repetition '{2000}' at position 2 is above 1000"
+        );
+        assert_eq!(
+            error("((x{1000}){1000}){1000}"),
+            "pattern generates values of up to 1000000000 characters, above 10000"
+        );
+        assert_eq!(
+            error("(a|b{600}){20}"),
+            "pattern generates values of up to 12000 characters, above 10000"
+        );
+        assert_eq!(error("a{x}"), "invalid repetition '{x}' at position 2");
+        assert_eq!(
+            error("[[:alpah:]]"),
+            "unknown POSIX class '[:alpah:]' at position 2"
+        );
+        assert_eq!(
+            error("[[:alpha]"),
+            "missing ':]' for the '[:' at position 2"
+        );
+        assert_eq!(error("[z-a]"), "range 'z-a' is out of order at position 2");
+        assert_eq!(error("[abc"), "missing ']' for the '[' at position 1");
+        assert_eq!(error("(ab"), "missing ')' for the '(' at position 1");
+        assert_eq!(error("ab)"), "unmatched ')' at position 3");
+    }
+}
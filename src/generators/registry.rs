@@ -0,0 +1,353 @@
+use super::{GeneratorError, builtin};
+use crate::schema::ColumnSpec;
+use crate::value::ValueKind;
+use rand::RngCore;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+/// A named column type: what it accepts and how to build a value generator
+/// for a column. Implement this and add it to a `Registry` to make a new type
+/// available to datasets and schema files.
+pub trait ColumnType: Send + Sync {
+    /// The name used as `type` in column specs, in lowercase.
+    fn name(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    /// Parameters accepted besides `null_rate` and `unique`.
+    fn params(&self) -> &'static [ParamSpec] {
+        &[]
+    }
+
+    /// Whether an "auto" column with this lowercased header gets this type.
+    fn detects(&self, _header: &str) -> bool {
+        false
+    }
+
+    /// Builds the generator for one column, validating its parameters.
+    fn build(&self, column: &ColumnSpec) -> Result<Box<dyn ColumnGenerator>, GeneratorError>;
+}
+
+/// Produces the values of one column. `index` is the 1-based row number.
+///
+/// A column whose values follow other columns of the same row names them in
+/// `depends_on` and reads them in `generate_in_row`; those columns are always
+/// generated first.
+///
+/// The other methods serve `unique` columns once random retries keep
+/// colliding: a column with a finite value space enumerates it, any other
+/// column makes a repeated value distinct by suffixing it.
+pub trait ColumnGenerator: Send + Sync {
+    /// A value on its own, ignoring the rest of the row.
+    fn generate(&self, index: usize, rng: &mut dyn RngCore) -> String;
+
+    /// The columns of the same row this column's values depend on.
+    fn depends_on(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// A value given the columns generated so far, which include every
+    /// column in `depends_on`.
+    fn generate_in_row(&self, index: usize, _row: &RowValues, rng: &mut dyn RngCore) -> String {
+        self.generate(index, rng)
+    }
+
+    /// Like `generate_in_row`, but `None` for a value that is null rather
+    /// than empty, as a computed column's result can be.
+    fn generate_in_row_or_null(
+        &self,
+        index: usize,
+        row: &RowValues,
+        rng: &mut dyn RngCore,
+    ) -> Option<String> {
+        Some(self.generate_in_row(index, row, rng))
+    }
+
+    /// How many distinct values the column can produce, when that is a hard
+    /// limit. `None` when suffixing can always make a value distinct.
+    fn cardinality(&self) -> Option<u64> {
+        None
+    }
+
+    /// The `n`th of the `cardinality()` distinct values.
+    fn nth_value(&self, _n: u64) -> Option<String> {
+        None
+    }
+
+    fn with_suffix(&self, value: &str, suffix: u64) -> String {
+        format!("{}_{}", value, suffix)
+    }
+
+    /// Whether values are distinct by construction, as sequential ids are,
+    /// so a `unique` column need not remember the values it handed out.
+    fn distinct_by_construction(&self) -> bool {
+        false
+    }
+
+    /// Whether the column never repeats a value even when it is not marked
+    /// `unique`, as random integer ids do.
+    fn always_unique(&self) -> bool {
+        false
+    }
+
+    /// What kind of value the generated text stands for, so outputs can
+    /// write it natively. `None` when it varies by row, as for computed
+    /// columns; each value is then read for whatever it looks like.
+    fn kind(&self) -> Option<ValueKind> {
+        Some(ValueKind::String)
+    }
+}
+
+impl<F> ColumnGenerator for F
+where
+    F: Fn(usize, &mut dyn RngCore) -> String + Send + Sync,
+{
+    fn generate(&self, index: usize, rng: &mut dyn RngCore) -> String {
+        self(index, rng)
+    }
+}
+
+/// The values of one row generated so far, looked up by column name.
+pub struct RowValues<'a> {
+    headers: &'a [String],
+    values: &'a [Option<String>],
+}
+
+impl<'a> RowValues<'a> {
+    /// `values` holds a cell per header, `None` until it is generated.
+    pub fn new(headers: &'a [String], values: &'a [Option<String>]) -> Self {
+        Self { headers, values }
+    }
+
+    /// The value of `column`, or `None` when it is null, not generated yet
+    /// or not a column of this row.
+    pub fn get(&self, column: &str) -> Option<&'a str> {
+        let position = self.headers.iter().position(|h| h == column)?;
+        self.values[position]
+            .as_deref()
+            .filter(|value| !value.is_empty())
+    }
+}
+
+/// One parameter of a column type, as listed by `GET /api/types`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ParamSpec {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub kind: ParamKind,
+    pub required: bool,
+    pub description: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamKind {
+    Integer,
+    Number,
+    /// A `YYYY-MM-DD` string.
+    Date,
+    String,
+    StringList,
+    NumberList,
+    /// The name of another column of the same table.
+    Column,
+}
+
+/// The column types known to the generators, in detection order.
+pub struct Registry {
+    types: Vec<Box<dyn ColumnType>>,
+}
+
+/// The type given to "auto" columns no other type detects.
+const FALLBACK_TYPE: &str = "text";
+
+impl Registry {
+    pub fn new() -> Self {
+        Self { types: Vec::new() }
+    }
+
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        builtin::register_all(&mut registry);
+        registry
+    }
+
+    /// Adds a column type. A type with the same name is replaced in place, so
+    /// built-ins can be overridden without changing detection order.
+    pub fn register(&mut self, column_type: impl ColumnType + 'static) {
+        let column_type: Box<dyn ColumnType> = Box::new(column_type);
+        match self
+            .types
+            .iter_mut()
+            .find(|t| t.name() == column_type.name())
+        {
+            Some(existing) => *existing = column_type,
+            None => self.types.push(column_type),
+        }
+    }
+
+    /// Looks up a type by name, ignoring case and surrounding whitespace.
+    pub fn get(&self, name: &str) -> Option<&dyn ColumnType> {
+        let name = name.trim().to_lowercase();
+        self.types
+            .iter()
+            .find(|t| t.name() == name)
+            .map(|t| t.as_ref())
+    }
+
+    pub fn types(&self) -> impl Iterator<Item = &dyn ColumnType> {
+        self.types.iter().map(|t| t.as_ref())
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.types.iter().map(|t| t.name()).collect()
+    }
+
+    /// The type of an "auto" column, from its header name: the first type
+    /// that detects it, or "text".
+    // TODO: ML-based detection(Ollama)
+    pub fn detect(&self, header: &str) -> Option<&dyn ColumnType> {
+        let header = header.to_lowercase();
+        self.types()
+            .find(|t| t.detects(&header))
+            .or_else(|| self.get(FALLBACK_TYPE))
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// The process-wide registry, holding the built-in types.
+pub fn registry() -> &'static Registry {
+    REGISTRY.get_or_init(Registry::with_builtins)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Constant;
+
+    impl ColumnType for Constant {
+        fn name(&self) -> &'static str {
+            "constant"
+        }
+
+        fn description(&self) -> &'static str {
+            "Always 'x'"
+        }
+
+        fn detects(&self, header: &str) -> bool {
+            header.contains("const")
+        }
+
+        fn build(&self, _: &ColumnSpec) -> Result<Box<dyn ColumnGenerator>, GeneratorError> {
+            Ok(Box::new(|_: usize, _: &mut dyn RngCore| "x".to_string()))
+        }
+    }
+
+    struct ReplacementText;
+
+    impl ColumnType for ReplacementText {
+        fn name(&self) -> &'static str {
+            "text"
+        }
+
+        fn description(&self) -> &'static str {
+            "Replaced"
+        }
+
+        fn build(&self, _: &ColumnSpec) -> Result<Box<dyn ColumnGenerator>, GeneratorError> {
+            Ok(Box::new(|_: usize, _: &mut dyn RngCore| "y".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_builtins_registered_in_order() {
+        assert_eq!(
+            registry().names(),
+            vec![
+                "uuid",
+                "boolean",
+                "id",
+                "url",
+                "user_agent",
+                "percentage",
+                "domain",
+                "username",
+                "company",
+                "name",
+                "email",
+                "password_hash",
+                "age",
+                "city",
+                "state",
+                "ipv6",
+                "ipv4",
+                "mac_address",
+                "street",
+                "postcode",
+                "country",
+                "currency_code",
+                "phone",
+                "timestamp",
+                "time",
+                "date",
+                "latitude",
+                "longitude",
+                "money",
+                "integer",
+                "decimal",
+                "iban",
+                "credit_card",
+                "isbn",
+                "job_title",
+                "color",
+                "text",
+                "enum",
+                "dictionary",
+                "regex",
+                "computed"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_ignores_case() {
+        assert_eq!(registry().get(" Money ").unwrap().name(), "money");
+        assert!(registry().get("unknown").is_none());
+    }
+
+    #[test]
+    fn test_register_custom_type() {
+        let mut registry = Registry::with_builtins();
+        registry.register(Constant);
+
+        assert_eq!(
+            registry.get("constant").unwrap().description(),
+            "Always 'x'"
+        );
+        assert_eq!(registry.detect("my_const").unwrap().name(), "constant");
+        assert_eq!(registry.names().last(), Some(&"constant"));
+    }
+
+    #[test]
+    fn test_register_replaces_same_name() {
+        let mut registry = Registry::with_builtins();
+        let count = registry.names().len();
+        registry.register(ReplacementText);
+
+        assert_eq!(registry.names().len(), count);
+        assert_eq!(registry.get("text").unwrap().description(), "Replaced");
+    }
+
+    #[test]
+    fn test_detect_without_fallback() {
+        assert!(Registry::new().detect("anything").is_none());
+    }
+}
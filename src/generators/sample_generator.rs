@@ -0,0 +1,590 @@
+use super::builtin::generate_text_with_length;
+use super::inference::has_leading_zero;
+use super::smart_generator::is_auto_type;
+use super::{DataGenerator, GeneratorError, SmartGenerator};
+use crate::schema::ColumnSpec;
+use crate::value::{Value, ValueKind};
+use chrono::NaiveDate;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+
+pub(crate) const DATE_FORMATS: &[&str] =
+    &["%Y-%m-%d", "%Y/%m/%d", "%d/%m/%Y", "%m/%d/%Y", "%d.%m.%Y"];
+
+// Columns with at most this many distinct values, each repeated on average,
+// are treated as categories and reproduced with their observed frequencies.
+pub(super) const MAX_CATEGORIES: usize = 20;
+
+/// Generates rows that follow the value distributions observed in a set of
+/// sample rows, column by column.
+pub struct SampleGenerator {
+    smart: SmartGenerator,
+    profiles: Vec<ColumnProfile>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnProfile {
+    pub null_ratio: f64,
+    pub distribution: Distribution,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Distribution {
+    /// No usable sample values, or an explicit column type was configured:
+    /// values come from the `SmartGenerator` instead.
+    Delegate,
+    Categorical {
+        values: Vec<String>,
+        counts: Vec<usize>,
+    },
+    Integer {
+        min: i64,
+        max: i64,
+        mean: f64,
+        std_dev: f64,
+    },
+    Decimal {
+        min: f64,
+        max: f64,
+        mean: f64,
+        std_dev: f64,
+        precision: usize,
+    },
+    Date {
+        min: NaiveDate,
+        max: NaiveDate,
+        format: &'static str,
+    },
+    Text {
+        min_len: usize,
+        max_len: usize,
+    },
+}
+
+impl SampleGenerator {
+    /// Columns with an explicit type are generated from their spec; only
+    /// "auto" columns are learned from the sample. Unique columns never are,
+    /// since sampled categories and blanks would repeat, and neither are
+    /// columns detected as ids whose sample values are distinct integers, so
+    /// ids stay sequential. A column's `null_rate` replaces the share of
+    /// blanks seen in the sample.
+    pub fn from_sample(
+        columns: &[ColumnSpec],
+        rows: &[Vec<String>],
+    ) -> Result<Self, GeneratorError> {
+        let smart = SmartGenerator::from_columns(columns)?;
+        let profiles = columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let values: Vec<&str> = rows
+                    .iter()
+                    .map(|row| row.get(index).map(String::as_str).unwrap_or(""))
+                    .collect();
+                let mut profile = ColumnProfile::from_values(&values);
+                if let Some(rate) = column.null_rate {
+                    profile.null_ratio = rate;
+                }
+
+                if column.unique {
+                    ColumnProfile {
+                        null_ratio: column.null_rate.unwrap_or(0.0),
+                        distribution: Distribution::Delegate,
+                    }
+                } else if is_auto_type(&column.type_name)
+                    && !(smart.is_id_column(index) && looks_like_ids(&values))
+                {
+                    profile
+                } else {
+                    ColumnProfile {
+                        distribution: Distribution::Delegate,
+                        ..profile
+                    }
+                }
+            })
+            .collect();
+
+        Ok(Self { smart, profiles })
+    }
+}
+
+impl DataGenerator for SampleGenerator {
+    fn headers(&self) -> Vec<String> {
+        self.smart.headers()
+    }
+
+    fn check_row_count(&self, row_count: usize) -> Result<(), GeneratorError> {
+        self.smart.check_row_count(row_count)
+    }
+
+    /// Columns are filled in the `SmartGenerator`'s order, so delegated
+    /// columns can read the cells they depend on.
+    fn generate_row(&self, index: usize, rng: &mut impl Rng) -> Vec<Value> {
+        let mut cells: Vec<Option<String>> = vec![None; self.profiles.len()];
+        let mut values = vec![Value::Null; self.profiles.len()];
+        for &column in self.smart.generation_order() {
+            let value = self.generate_value(column, index, &cells, rng);
+            cells[column] = Some(value.text().into_owned());
+            values[column] = value;
+        }
+        values
+    }
+}
+
+impl SampleGenerator {
+    fn generate_value(
+        &self,
+        column: usize,
+        index: usize,
+        cells: &[Option<String>],
+        rng: &mut impl Rng,
+    ) -> Value {
+        let profile = &self.profiles[column];
+        if profile.null_ratio > 0.0 && rng.random_bool(profile.null_ratio) {
+            return Value::Null;
+        }
+
+        let delegate = |rng: &mut _| self.smart.generate_cell(column, index, cells, rng);
+        match &profile.distribution {
+            Distribution::Delegate => delegate(rng),
+            // Sampled categories are read for what they look like, so
+            // ratings stay numbers and flags booleans.
+            Distribution::Categorical { values, counts } => {
+                Value::infer(values[weighted_index(counts, rng)].clone())
+            }
+            Distribution::Integer {
+                min,
+                max,
+                mean,
+                std_dev,
+            } => {
+                let value = sample_normal(*mean, *std_dev, rng).round();
+                Value::Int((value as i64).clamp(*min, *max))
+            }
+            Distribution::Decimal {
+                min,
+                max,
+                mean,
+                std_dev,
+                precision,
+            } => {
+                let value = sample_normal(*mean, *std_dev, rng).clamp(*min, *max);
+                ValueKind::Decimal.read(format!("{:.*}", precision, value))
+            }
+            Distribution::Date { min, max, format } => {
+                let span = (*max - *min).num_days();
+                let date = *min + chrono::Duration::days(rng.random_range(0..=span));
+                Value::Date(date.format(format).to_string())
+            }
+            Distribution::Text { min_len, max_len } => {
+                if self.smart.is_text_column(column) {
+                    Value::String(generate_text_with_length(rng, *min_len, *max_len))
+                } else {
+                    delegate(rng)
+                }
+            }
+        }
+    }
+}
+
+impl ColumnProfile {
+    pub fn from_values(values: &[&str]) -> Self {
+        let present: Vec<&str> = values
+            .iter()
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .collect();
+
+        let null_ratio = if values.is_empty() {
+            0.0
+        } else {
+            (values.len() - present.len()) as f64 / values.len() as f64
+        };
+
+        ColumnProfile {
+            null_ratio,
+            distribution: Distribution::from_values(&present),
+        }
+    }
+}
+
+impl Distribution {
+    fn from_values(values: &[&str]) -> Self {
+        if values.is_empty() {
+            return Distribution::Delegate;
+        }
+
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for value in values {
+            *counts.entry(value).or_insert(0) += 1;
+        }
+
+        // Numbers with leading zeros are codes such as zip codes: reading
+        // them as numbers would drop the zeros, so they are reproduced as
+        // observed.
+        let codes = values
+            .iter()
+            .any(|v| v.parse::<f64>().is_ok() && has_leading_zero(v));
+        if codes || (counts.len() <= MAX_CATEGORIES && counts.len() * 2 <= values.len()) {
+            let mut categories: Vec<(&str, usize)> = counts.into_iter().collect();
+            categories.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+            return Distribution::Categorical {
+                values: categories.iter().map(|(v, _)| v.to_string()).collect(),
+                counts: categories.iter().map(|(_, c)| *c).collect(),
+            };
+        }
+
+        if let Some(ints) = parse_all(values, |v| v.parse::<i64>().ok()) {
+            let floats: Vec<f64> = ints.iter().map(|&v| v as f64).collect();
+            let (mean, std_dev) = mean_and_std_dev(&floats);
+            return Distribution::Integer {
+                min: *ints.iter().min().unwrap(),
+                max: *ints.iter().max().unwrap(),
+                mean,
+                std_dev,
+            };
+        }
+
+        if let Some(floats) = parse_all(values, |v| v.parse::<f64>().ok().filter(|f| f.is_finite()))
+        {
+            let (mean, std_dev) = mean_and_std_dev(&floats);
+            let precision = values
+                .iter()
+                .map(|v| v.split_once('.').map(|(_, frac)| frac.len()).unwrap_or(0))
+                .max()
+                .unwrap_or(0);
+            return Distribution::Decimal {
+                min: floats.iter().cloned().fold(f64::INFINITY, f64::min),
+                max: floats.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+                mean,
+                std_dev,
+                precision,
+            };
+        }
+
+        for format in DATE_FORMATS {
+            if let Some(dates) = parse_all(values, |v| NaiveDate::parse_from_str(v, format).ok()) {
+                return Distribution::Date {
+                    min: *dates.iter().min().unwrap(),
+                    max: *dates.iter().max().unwrap(),
+                    format,
+                };
+            }
+        }
+
+        let lengths = values.iter().map(|v| v.chars().count());
+        Distribution::Text {
+            min_len: lengths.clone().min().unwrap(),
+            max_len: lengths.max().unwrap(),
+        }
+    }
+}
+
+/// Whether the non-blank values are integers that never repeat.
+fn looks_like_ids(values: &[&str]) -> bool {
+    let present: Vec<&str> = values
+        .iter()
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .collect();
+    present
+        .iter()
+        .all(|v| v.parse::<i64>().is_ok_and(|n| n.to_string() == *v))
+        && present.iter().collect::<HashSet<_>>().len() == present.len()
+}
+
+fn parse_all<T>(values: &[&str], parse: impl Fn(&str) -> Option<T>) -> Option<Vec<T>> {
+    values.iter().map(|v| parse(v)).collect()
+}
+
+fn mean_and_std_dev(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    (mean, variance.sqrt())
+}
+
+fn weighted_index(counts: &[usize], rng: &mut impl Rng) -> usize {
+    let total: usize = counts.iter().sum();
+    let mut target = rng.random_range(0..total);
+    for (idx, count) in counts.iter().enumerate() {
+        if target < *count {
+            return idx;
+        }
+        target -= count;
+    }
+    counts.len() - 1
+}
+
+// Box-Muller transform; keeps us off an extra rand_distr dependency.
+fn sample_normal(mean: f64, std_dev: f64, rng: &mut impl Rng) -> f64 {
+    if std_dev == 0.0 {
+        return mean;
+    }
+    let u1: f64 = 1.0 - rng.random::<f64>();
+    let u2: f64 = rng.random();
+    let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+    mean + z * std_dev
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::texts;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn rows(data: &[&[&str]]) -> Vec<Vec<String>> {
+        data.iter()
+            .map(|row| row.iter().map(|v| v.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_profile_categorical() {
+        let profile = ColumnProfile::from_values(&["active", "active", "active", "deleted"]);
+        assert_eq!(profile.null_ratio, 0.0);
+        assert_eq!(
+            profile.distribution,
+            Distribution::Categorical {
+                values: vec!["active".to_string(), "deleted".to_string()],
+                counts: vec![3, 1],
+            }
+        );
+    }
+
+    #[test]
+    fn test_profile_integer() {
+        let profile = ColumnProfile::from_values(&["10", "20", "30", "40"]);
+        match profile.distribution {
+            Distribution::Integer { min, max, mean, .. } => {
+                assert_eq!(min, 10);
+                assert_eq!(max, 40);
+                assert_eq!(mean, 25.0);
+            }
+            other => panic!("expected integer profile, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_profile_decimal_precision() {
+        let profile = ColumnProfile::from_values(&["1.5", "2.25", "3.125"]);
+        match profile.distribution {
+            Distribution::Decimal {
+                min,
+                max,
+                precision,
+                ..
+            } => {
+                assert_eq!(min, 1.5);
+                assert_eq!(max, 3.125);
+                assert_eq!(precision, 3);
+            }
+            other => panic!("expected decimal profile, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_profile_date() {
+        let profile = ColumnProfile::from_values(&["2021-03-01", "2021-01-15", "2022-07-30"]);
+        assert_eq!(
+            profile.distribution,
+            Distribution::Date {
+                min: NaiveDate::from_ymd_opt(2021, 1, 15).unwrap(),
+                max: NaiveDate::from_ymd_opt(2022, 7, 30).unwrap(),
+                format: "%Y-%m-%d",
+            }
+        );
+    }
+
+    #[test]
+    fn test_profile_text_lengths_and_nulls() {
+        let profile = ColumnProfile::from_values(&["abc", "", "abcdef", " "]);
+        assert_eq!(profile.null_ratio, 0.5);
+        assert_eq!(
+            profile.distribution,
+            Distribution::Text {
+                min_len: 3,
+                max_len: 6
+            }
+        );
+    }
+
+    #[test]
+    fn test_profile_all_empty() {
+        let profile = ColumnProfile::from_values(&["", ""]);
+        assert_eq!(profile.null_ratio, 1.0);
+        assert_eq!(profile.distribution, Distribution::Delegate);
+    }
+
+    #[test]
+    fn test_sample_generator_stays_within_observed_ranges() {
+        let headers = vec![
+            "status".to_string(),
+            "quantity".to_string(),
+            "price".to_string(),
+            "shipped".to_string(),
+            "notes".to_string(),
+        ];
+        let sample = rows(&[
+            &["open", "1", "9.99", "2023-01-01", "fragile"],
+            &["open", "5", "19.50", "2023-02-10", "leave at door"],
+            &["closed", "3", "4.25", "2023-03-05", "gift"],
+            &["open", "8", "12.00", "2023-01-20", "call first"],
+        ]);
+
+        let generator = SampleGenerator::from_sample(
+            &ColumnSpec::from_column_types(&headers, &HashMap::new()),
+            &sample,
+        )
+        .unwrap();
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for i in 1..=200 {
+            let row = texts(&generator.generate_row(i, &mut rng));
+            assert!(row[0] == "open" || row[0] == "closed");
+            let quantity: i64 = row[1].parse().unwrap();
+            assert!((1..=8).contains(&quantity));
+            let price: f64 = row[2].parse().unwrap();
+            assert!((4.25..=19.5).contains(&price));
+            assert_eq!(row[2].split('.').nth(1).unwrap().len(), 2);
+            let shipped = NaiveDate::parse_from_str(&row[3], "%Y-%m-%d").unwrap();
+            assert!(shipped >= NaiveDate::from_ymd_opt(2023, 1, 1).unwrap());
+            assert!(shipped <= NaiveDate::from_ymd_opt(2023, 3, 5).unwrap());
+            assert!((4..=13).contains(&row[4].len()));
+        }
+    }
+
+    #[test]
+    fn test_sample_generator_reproduces_null_ratio() {
+        let headers = vec!["middle_name".to_string()];
+        let sample = rows(&[&["Ann"], &[""], &["Lee"], &[""]]);
+
+        let generator = SampleGenerator::from_sample(
+            &ColumnSpec::from_column_types(&headers, &HashMap::new()),
+            &sample,
+        )
+        .unwrap();
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let blanks = (1..=1000)
+            .filter(|&i| texts(&generator.generate_row(i, &mut rng))[0].is_empty())
+            .count();
+        assert!((400..=600).contains(&blanks));
+    }
+
+    #[test]
+    fn test_sample_generator_null_rate_overrides_sample() {
+        let sample = rows(&[&["Ann", "1"], &["", "2"], &["Lee", "3"], &["", "4"]]);
+        let mut nickname = ColumnSpec::new("nickname", "auto");
+        nickname.null_rate = Some(0.0);
+        let mut code = ColumnSpec::new("code", "auto");
+        code.null_rate = Some(1.0);
+
+        let generator = SampleGenerator::from_sample(&[nickname, code], &sample).unwrap();
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for i in 1..=200 {
+            let row = texts(&generator.generate_row(i, &mut rng));
+            assert!(!row[0].is_empty());
+            assert!(row[1].is_empty());
+        }
+    }
+
+    #[test]
+    fn test_sample_generator_rules_read_learned_columns() {
+        let sample = rows(&[&["6", "3"], &["10", "5"], &["16", "8"], &["26", "13"]]);
+        let mut total = ColumnSpec::new("total", "computed");
+        total.expression = Some("qty * 2".to_string());
+
+        let generator =
+            SampleGenerator::from_sample(&[total, ColumnSpec::new("qty", "auto")], &sample)
+                .unwrap();
+        let mut rng = StdRng::seed_from_u64(3);
+        for i in 1..=50 {
+            let row = texts(&generator.generate_row(i, &mut rng));
+            let qty: i64 = row[1].parse().unwrap();
+            assert_eq!(row[0], (qty * 2).to_string());
+        }
+    }
+
+    #[test]
+    fn test_sample_generator_explicit_type_wins() {
+        let headers = vec!["contact".to_string()];
+        let sample = rows(&[&["x"], &["x"], &["x"]]);
+        let mut column_types = HashMap::new();
+        column_types.insert("contact".to_string(), "email".to_string());
+
+        let generator = SampleGenerator::from_sample(
+            &ColumnSpec::from_column_types(&headers, &column_types),
+            &sample,
+        )
+        .unwrap();
+        assert_eq!(generator.profiles[0].distribution, Distribution::Delegate);
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let row = texts(&generator.generate_row(1, &mut rng));
+        assert!(row[0].contains('@'));
+    }
+
+    #[test]
+    fn test_sample_generator_keeps_detected_ids_sequential() {
+        let headers = vec!["customer_id".to_string(), "tier".to_string()];
+        let sample = rows(&[&["56", "1"], &["12", "2"], &["98", "2"], &["31", "3"]]);
+        let generator = SampleGenerator::from_sample(
+            &ColumnSpec::from_column_types(&headers, &HashMap::new()),
+            &sample,
+        )
+        .unwrap();
+        assert_eq!(generator.profiles[0].distribution, Distribution::Delegate);
+
+        let mut rng = StdRng::seed_from_u64(5);
+        let ids: Vec<String> = (1..=100)
+            .map(|i| texts(&generator.generate_row(i, &mut rng))[0].clone())
+            .collect();
+        let expected: Vec<String> = (1..=100).map(|i| i.to_string()).collect();
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn test_sample_generator_keeps_leading_zeros() {
+        let profile = ColumnProfile::from_values(&["01234", "90210", "00501", "10001"]);
+        assert!(matches!(
+            profile.distribution,
+            Distribution::Categorical { ref values, .. } if values.len() == 4
+        ));
+
+        let headers = vec!["zip".to_string()];
+        let sample = rows(&[&["01234"], &["02134"], &["00501"], &["02139"], &["03101"]]);
+        let generator = SampleGenerator::from_sample(
+            &ColumnSpec::from_column_types(&headers, &HashMap::new()),
+            &sample,
+        )
+        .unwrap();
+        let mut rng = StdRng::seed_from_u64(11);
+        for i in 1..=50 {
+            let row = generator.generate_row(i, &mut rng);
+            assert!(
+                matches!(&row[0], Value::String(zip) if zip.len() == 5 && zip.starts_with('0'))
+            );
+        }
+    }
+
+    #[test]
+    fn test_sample_generator_deterministic_with_seed() {
+        let headers = vec!["score".to_string()];
+        let sample = rows(&[&["1.5"], &["2.5"], &["9.75"]]);
+        let generator = SampleGenerator::from_sample(
+            &ColumnSpec::from_column_types(&headers, &HashMap::new()),
+            &sample,
+        )
+        .unwrap();
+
+        let mut rng1 = StdRng::seed_from_u64(99);
+        let mut rng2 = StdRng::seed_from_u64(99);
+        assert_eq!(
+            texts(&generator.generate_row(1, &mut rng1)),
+            texts(&generator.generate_row(1, &mut rng2))
+        );
+    }
+}
@@ -1,490 +1,349 @@
-use super::DataGenerator;
-use rand::Rng;
+use super::registry::{ColumnGenerator, Registry, RowValues, registry};
+use super::{DataGenerator, GeneratorError};
+use crate::schema::ColumnSpec;
+use crate::value::{Value, ValueKind};
+use rand::{Rng, RngCore};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// Random draws tried for a unique column before falling back to
+/// enumerating or suffixing values.
+const UNIQUE_ATTEMPTS: usize = 20;
+
+/// Most rows a unique column that remembers its values may fill, keeping
+/// its seen-set, and a stream's memory, bounded. Sequential ids are unique
+/// by construction and have no limit.
+pub const MAX_UNIQUE_ROWS: usize = 1_000_000;
 
 pub struct SmartGenerator {
     headers: Vec<String>,
+    columns: Vec<ResolvedColumn>,
+    /// Column positions in generation order: every column after the columns
+    /// it depends on, otherwise in header order.
+    order: Vec<usize>,
 }
 
-impl SmartGenerator {
-    pub fn new(headers: Vec<String>) -> Self {
-        Self { headers }
-    }
-
-    // TODO: ML-based detection(Ollama)
-    fn detect_data_type(&self, header: &str) -> DataType {
-        let header_lower = header.to_lowercase();
-
-        if header_lower.contains("id") || header_lower == "id" {
-            return DataType::Id;
-        }
-
-        if header_lower.contains("name") {
-            return DataType::Name;
-        }
+/// A column's registered type name and the generator built from its spec.
+pub(super) struct ResolvedColumn {
+    type_name: &'static str,
+    generator: Box<dyn ColumnGenerator>,
+    null_rate: f64,
+    kind: Option<ValueKind>,
+    /// Values handed out so far, for `unique` columns.
+    seen: Option<Mutex<HashSet<String>>>,
+}
 
-        if header_lower.contains("email") || header_lower.contains("mail") {
-            return DataType::Email;
+impl ResolvedColumn {
+    /// A value, or `None` for a null. Nulls never count as repeats of a
+    /// unique column.
+    fn generate_nullable(
+        &self,
+        index: usize,
+        row: &RowValues,
+        rng: &mut dyn RngCore,
+    ) -> Option<String> {
+        if self.null_rate > 0.0 && rng.random_bool(self.null_rate) {
+            return None;
         }
+        self.generate(index, row, rng)
+    }
 
-        if header_lower.contains("age") {
-            return DataType::Age;
+    /// The typed cell of a generated value.
+    fn read(&self, text: String) -> Value {
+        match self.kind {
+            Some(kind) => kind.read(text),
+            None => Value::infer(text),
         }
+    }
 
-        if header_lower.contains("city") {
-            return DataType::City;
+    /// A value regardless of `null_rate`, or `None` when the generator
+    /// itself yields a null.
+    fn generate(&self, index: usize, row: &RowValues, rng: &mut dyn RngCore) -> Option<String> {
+        match &self.seen {
+            Some(seen) => Some(self.generate_unique(seen, index, row, rng)),
+            None => self.generator.generate_in_row_or_null(index, row, rng),
         }
+    }
 
-        if header_lower.contains("country") {
-            return DataType::Country;
-        }
+    /// Enumerated and suffixed fallbacks of a unique column do not look at
+    /// the row, so they may break a rule tying the column to another.
+    fn generate_unique(
+        &self,
+        seen: &Mutex<HashSet<String>>,
+        index: usize,
+        row: &RowValues,
+        rng: &mut dyn RngCore,
+    ) -> String {
+        let mut seen = seen.lock().unwrap();
 
-        if header_lower.contains("phone") || header_lower.contains("tel") {
-            return DataType::Phone;
+        let mut value = String::new();
+        for _ in 0..UNIQUE_ATTEMPTS {
+            value = self.generator.generate_in_row(index, row, rng);
+            if seen.insert(value.clone()) {
+                return value;
+            }
         }
 
-        if header_lower.contains("date") {
-            return DataType::Date;
+        // Walk the value space from a random point, so the remaining values
+        // are not handed out in order.
+        if let Some(cardinality) = self.generator.cardinality() {
+            let start = rng.random_range(0..cardinality);
+            for offset in 0..cardinality {
+                let n = start.wrapping_add(offset) % cardinality;
+                let Some(candidate) = self.generator.nth_value(n) else {
+                    break;
+                };
+                if seen.insert(candidate.clone()) {
+                    return candidate;
+                }
+            }
         }
 
-        if header_lower.contains("price")
-            || header_lower.contains("cost")
-            || header_lower.contains("amount")
-            || header_lower.contains("salary")
-        {
-            return DataType::Money;
+        for suffix in 2.. {
+            let candidate = self.generator.with_suffix(&value, suffix);
+            if seen.insert(candidate.clone()) {
+                return candidate;
+            }
         }
-
-        DataType::Text
-    }
-
-    fn generate_value(&self, data_type: &DataType, index: usize, rng: &mut impl Rng) -> String {
-        match data_type {
-            DataType::Id => index.to_string(),
-            DataType::Name => generate_name(rng),
-            DataType::Email => generate_email(rng),
-            DataType::Age => rng.random_range(18..=80).to_string(),
-            DataType::City => generate_city(rng),
-            DataType::Country => generate_country(rng),
-            DataType::Phone => generate_phone(rng),
-            DataType::Date => generate_date(rng),
-            DataType::Money => format!("{:.2}", rng.random_range(10.0..=10000.0)),
-            DataType::Text => generate_random_text(rng),
-        }
-    }
-}
-
-impl DataGenerator for SmartGenerator {
-    fn headers(&self) -> Vec<String> {
-        self.headers.clone()
+        unreachable!("suffixes are distinct")
     }
-
-    fn generate_row(&self, index: usize, rng: &mut impl Rng) -> Vec<String> {
-        self.headers
-            .iter()
-            .map(|header| {
-                let data_type = self.detect_data_type(header);
-                self.generate_value(&data_type, index, rng)
-            })
-            .collect()
-    }
-}
-
-#[derive(Debug, Clone)]
-enum DataType {
-    Id,
-    Name,
-    Email,
-    Age,
-    City,
-    Country,
-    Phone,
-    Date,
-    Money,
-    Text, // Fallback for unknown types
-}
-
-fn generate_name(rng: &mut impl Rng) -> String {
-    const FIRST_NAMES: &[&str] = &[
-        "James",
-        "Mary",
-        "John",
-        "Patricia",
-        "Robert",
-        "Jennifer",
-        "Michael",
-        "Linda",
-        "William",
-        "Elizabeth",
-        "David",
-        "Barbara",
-        "Richard",
-        "Susan",
-        "Joseph",
-        "Jessica",
-        "Thomas",
-        "Sarah",
-        "Charles",
-        "Karen",
-        "Emma",
-        "Oliver",
-        "Sophia",
-        "Liam",
-    ];
-    const LAST_NAMES: &[&str] = &[
-        "Smith",
-        "Johnson",
-        "Williams",
-        "Brown",
-        "Jones",
-        "Garcia",
-        "Miller",
-        "Davis",
-        "Rodriguez",
-        "Martinez",
-        "Hernandez",
-        "Lopez",
-        "Gonzalez",
-        "Wilson",
-        "Anderson",
-        "Thomas",
-        "Taylor",
-        "Moore",
-        "Jackson",
-        "Martin",
-        "Lee",
-        "Thompson",
-        "White",
-        "Harris",
-    ];
-
-    let first = FIRST_NAMES[rng.random_range(0..FIRST_NAMES.len())];
-    let last = LAST_NAMES[rng.random_range(0..LAST_NAMES.len())];
-    format!("{} {}", first, last)
 }
 
-fn generate_email(rng: &mut impl Rng) -> String {
-    const DOMAINS: &[&str] = &[
-        "gmail.com",
-        "yahoo.com",
-        "outlook.com",
-        "example.com",
-        "test.com",
-    ];
-
-    let username: String = (0..8)
-        .map(|_| {
-            const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
-            CHARSET[rng.random_range(0..CHARSET.len())] as char
-        })
-        .collect();
-
-    let domain = DOMAINS[rng.random_range(0..DOMAINS.len())];
-    format!("{}@{}", username, domain)
-}
-
-fn generate_city(rng: &mut impl Rng) -> String {
-    const CITIES: &[&str] = &[
-        "New York",
-        "Los Angeles",
-        "Chicago",
-        "Houston",
-        "Phoenix",
-        "Philadelphia",
-        "San Antonio",
-        "San Diego",
-        "Dallas",
-        "San Jose",
-        "Austin",
-        "Jacksonville",
-        "London",
-        "Paris",
-        "Tokyo",
-        "Berlin",
-        "Madrid",
-        "Rome",
-        "Sydney",
-        "Toronto",
-    ];
-
-    CITIES[rng.random_range(0..CITIES.len())].to_string()
-}
-
-fn generate_country(rng: &mut impl Rng) -> String {
-    const COUNTRIES: &[&str] = &[
-        "United States",
-        "Canada",
-        "United Kingdom",
-        "Germany",
-        "France",
-        "Japan",
-        "Australia",
-        "Spain",
-        "Italy",
-        "Brazil",
-        "Mexico",
-        "Netherlands",
-    ];
-
-    COUNTRIES[rng.random_range(0..COUNTRIES.len())].to_string()
-}
-
-fn generate_phone(rng: &mut impl Rng) -> String {
-    format!(
-        "+1-{:03}-{:03}-{:04}",
-        rng.random_range(200..=999),
-        rng.random_range(200..=999),
-        rng.random_range(1000..=9999)
-    )
-}
-
-fn generate_date(rng: &mut impl Rng) -> String {
-    let year = rng.random_range(2020..=2025);
-    let month = rng.random_range(1..=12);
-    let day = rng.random_range(1..=28); // Simple approach to avoid invalid dates
-    format!("{:04}-{:02}-{:02}", year, month, day)
-}
-
-fn generate_random_text(rng: &mut impl Rng) -> String {
-    const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
-    let length = rng.random_range(5..=12);
-
-    (0..length)
-        .map(|_| CHARSET[rng.random_range(0..CHARSET.len())] as char)
-        .collect()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use rand::rngs::StdRng;
-    use rand::SeedableRng;
-
-    #[test]
-    fn test_detect_data_type_id() {
-        let generator = SmartGenerator::new(vec![]);
-        assert!(matches!(generator.detect_data_type("id"), DataType::Id));
-        assert!(matches!(generator.detect_data_type("ID"), DataType::Id));
-        assert!(matches!(generator.detect_data_type("user_id"), DataType::Id));
-        assert!(matches!(generator.detect_data_type("product_id"), DataType::Id));
-    }
-
-    #[test]
-    fn test_detect_data_type_name() {
-        let generator = SmartGenerator::new(vec![]);
-        assert!(matches!(generator.detect_data_type("name"), DataType::Name));
-        assert!(matches!(generator.detect_data_type("Name"), DataType::Name));
-        assert!(matches!(generator.detect_data_type("first_name"), DataType::Name));
-        assert!(matches!(generator.detect_data_type("last_name"), DataType::Name));
-        assert!(matches!(generator.detect_data_type("username"), DataType::Name));
+impl SmartGenerator {
+    pub fn new(headers: Vec<String>) -> Self {
+        Self::with_column_types(headers, &HashMap::new())
+            .expect("header detection never yields an unknown type")
     }
 
-    #[test]
-    fn test_detect_data_type_email() {
-        let generator = SmartGenerator::new(vec![]);
-        assert!(matches!(generator.detect_data_type("email"), DataType::Email));
-        assert!(matches!(generator.detect_data_type("Email"), DataType::Email));
-        assert!(matches!(generator.detect_data_type("mail"), DataType::Email));
-        assert!(matches!(generator.detect_data_type("user_email"), DataType::Email));
+    /// Builds a generator where `column_types` (header -> type name) takes
+    /// precedence over header detection. Columns missing from the map, or
+    /// mapped to "auto", are detected from their header name.
+    pub fn with_column_types(
+        headers: Vec<String>,
+        column_types: &HashMap<String, String>,
+    ) -> Result<Self, GeneratorError> {
+        Self::from_columns(&ColumnSpec::from_column_types(&headers, column_types))
     }
 
-    #[test]
-    fn test_detect_data_type_age() {
-        let generator = SmartGenerator::new(vec![]);
-        assert!(matches!(generator.detect_data_type("age"), DataType::Age));
-        assert!(matches!(generator.detect_data_type("Age"), DataType::Age));
-        assert!(matches!(generator.detect_data_type("user_age"), DataType::Age));
+    /// Builds a generator from full column specs, e.g. from a schema file,
+    /// resolving their types through the process-wide registry.
+    pub fn from_columns(columns: &[ColumnSpec]) -> Result<Self, GeneratorError> {
+        Self::from_columns_in(registry(), columns)
     }
 
-    #[test]
-    fn test_detect_data_type_city() {
-        let generator = SmartGenerator::new(vec![]);
-        assert!(matches!(generator.detect_data_type("city"), DataType::City));
-        assert!(matches!(generator.detect_data_type("City"), DataType::City));
-        assert!(matches!(generator.detect_data_type("home_city"), DataType::City));
-    }
+    pub fn from_columns_in(
+        registry: &Registry,
+        columns: &[ColumnSpec],
+    ) -> Result<Self, GeneratorError> {
+        let resolved: Vec<ResolvedColumn> = columns
+            .iter()
+            .map(|column| build_column(registry, column))
+            .collect::<Result<_, GeneratorError>>()?;
+        let headers: Vec<String> = columns.iter().map(|c| c.name.clone()).collect();
+        let order = generation_order(&headers, &resolved)?;
 
-    #[test]
-    fn test_detect_data_type_country() {
-        let generator = SmartGenerator::new(vec![]);
-        assert!(matches!(
-            generator.detect_data_type("country"),
-            DataType::Country
-        ));
-        assert!(matches!(
-            generator.detect_data_type("Country"),
-            DataType::Country
-        ));
-        assert!(matches!(
-            generator.detect_data_type("home_country"),
-            DataType::Country
-        ));
+        Ok(Self {
+            headers,
+            columns: resolved,
+            order,
+        })
     }
 
-    #[test]
-    fn test_detect_data_type_phone() {
-        let generator = SmartGenerator::new(vec![]);
-        assert!(matches!(generator.detect_data_type("phone"), DataType::Phone));
-        assert!(matches!(generator.detect_data_type("Phone"), DataType::Phone));
-        assert!(matches!(generator.detect_data_type("tel"), DataType::Phone));
-        assert!(matches!(
-            generator.detect_data_type("telephone"),
-            DataType::Phone
-        ));
+    /// Column positions in the order their cells must be generated, so that
+    /// a row passed to `generate_cell` holds every column the cell reads.
+    pub fn generation_order(&self) -> &[usize] {
+        &self.order
     }
 
-    #[test]
-    fn test_detect_data_type_date() {
-        let generator = SmartGenerator::new(vec![]);
-        assert!(matches!(generator.detect_data_type("date"), DataType::Date));
-        assert!(matches!(generator.detect_data_type("Date"), DataType::Date));
-        assert!(matches!(
-            generator.detect_data_type("birth_date"),
-            DataType::Date
-        ));
-        assert!(matches!(
-            generator.detect_data_type("created_date"),
-            DataType::Date
-        ));
+    /// Generates a single typed cell for the column at `column`, as
+    /// `generate_row` would when it does not draw a null; only a computed
+    /// result can still be null. `row` holds the cells generated so far, in
+    /// header order.
+    pub fn generate_cell(
+        &self,
+        column: usize,
+        index: usize,
+        row: &[Option<String>],
+        rng: &mut impl Rng,
+    ) -> Value {
+        let row = RowValues::new(&self.headers, row);
+        let resolved = &self.columns[column];
+        resolved
+            .generate(index, &row, rng)
+            .map_or(Value::Null, |text| resolved.read(text))
     }
 
-    #[test]
-    fn test_detect_data_type_money() {
-        let generator = SmartGenerator::new(vec![]);
-        assert!(matches!(generator.detect_data_type("price"), DataType::Money));
-        assert!(matches!(generator.detect_data_type("cost"), DataType::Money));
-        assert!(matches!(
-            generator.detect_data_type("amount"),
-            DataType::Money
-        ));
-        assert!(matches!(
-            generator.detect_data_type("salary"),
-            DataType::Money
-        ));
-        assert!(matches!(
-            generator.detect_data_type("total_price"),
-            DataType::Money
-        ));
+    pub fn is_text_column(&self, column: usize) -> bool {
+        self.columns[column].type_name == "text"
     }
 
-    #[test]
-    fn test_detect_data_type_text_fallback() {
-        let generator = SmartGenerator::new(vec![]);
-        assert!(matches!(
-            generator.detect_data_type("description"),
-            DataType::Text
-        ));
-        assert!(matches!(
-            generator.detect_data_type("random"),
-            DataType::Text
-        ));
-        assert!(matches!(generator.detect_data_type("xyz"), DataType::Text));
+    pub fn is_id_column(&self, column: usize) -> bool {
+        self.columns[column].type_name == "id"
     }
+}
 
-    #[test]
-    fn test_generate_name() {
-        let mut rng = StdRng::seed_from_u64(42);
-        let name = generate_name(&mut rng);
-        assert!(name.contains(' '));
-        assert!(name.len() > 3);
-        let parts: Vec<&str> = name.split(' ').collect();
-        assert_eq!(parts.len(), 2);
+impl DataGenerator for SmartGenerator {
+    fn headers(&self) -> Vec<String> {
+        self.headers.clone()
     }
 
-    #[test]
-    fn test_generate_email() {
-        let mut rng = StdRng::seed_from_u64(42);
-        let email = generate_email(&mut rng);
-        assert!(email.contains('@'));
-        assert!(email.contains('.'));
-        let parts: Vec<&str> = email.split('@').collect();
-        assert_eq!(parts.len(), 2);
-        assert_eq!(parts[0].len(), 8);
+    /// Columns read the text of the cells before them, where nulls are
+    /// empty.
+    fn generate_row(&self, index: usize, rng: &mut impl Rng) -> Vec<Value> {
+        let mut cells: Vec<Option<String>> = vec![None; self.columns.len()];
+        let mut values = vec![Value::Null; self.columns.len()];
+        for &column in &self.order {
+            let row = RowValues::new(&self.headers, &cells);
+            let resolved = &self.columns[column];
+            let text = resolved.generate_nullable(index, &row, rng);
+            if let Some(text) = &text {
+                values[column] = resolved.read(text.clone());
+            }
+            cells[column] = Some(text.unwrap_or_default());
+        }
+        values
     }
 
-    #[test]
-    fn test_generate_city() {
-        let mut rng = StdRng::seed_from_u64(42);
-        let city = generate_city(&mut rng);
-        assert!(!city.is_empty());
-        assert!(city.chars().next().unwrap().is_uppercase());
+    fn check_row_count(&self, row_count: usize) -> Result<(), GeneratorError> {
+        for (header, column) in self.headers.iter().zip(&self.columns) {
+            if column.seen.is_none() {
+                continue;
+            }
+            if row_count > MAX_UNIQUE_ROWS {
+                return Err(GeneratorError::TooManyUniqueRows {
+                    column: header.clone(),
+                    limit: MAX_UNIQUE_ROWS,
+                    requested: row_count,
+                });
+            }
+            if let Some(available) = column.generator.cardinality()
+                && row_count as u64 > available
+            {
+                return Err(GeneratorError::NotEnoughUniqueValues {
+                    column: header.clone(),
+                    available,
+                    requested: row_count,
+                });
+            }
+        }
+        Ok(())
     }
+}
 
-    #[test]
-    fn test_generate_country() {
-        let mut rng = StdRng::seed_from_u64(42);
-        let country = generate_country(&mut rng);
-        assert!(!country.is_empty());
-        assert!(country.chars().next().unwrap().is_uppercase());
-    }
+/// Resolves a column's type, detecting it for "auto" columns, and builds its
+/// generator.
+pub(super) fn build_column(
+    registry: &Registry,
+    column: &ColumnSpec,
+) -> Result<ResolvedColumn, GeneratorError> {
+    let column_type = if is_auto_type(&column.type_name) {
+        registry.detect(&column.name)
+    } else {
+        registry.get(&column.type_name)
+    };
+    let column_type = column_type.ok_or_else(|| GeneratorError::UnknownDataType {
+        column: column.name.clone(),
+        type_name: column.type_name.clone(),
+    })?;
 
-    #[test]
-    fn test_generate_phone() {
-        let mut rng = StdRng::seed_from_u64(42);
-        let phone = generate_phone(&mut rng);
-        assert!(phone.starts_with("+1-"));
-        assert!(phone.matches('-').count() == 3);
-        let parts: Vec<&str> = phone.split('-').collect();
-        assert_eq!(parts.len(), 4);
-        assert_eq!(parts[1].len(), 3);
-        assert_eq!(parts[2].len(), 3);
-        assert_eq!(parts[3].len(), 4);
-    }
+    let generator = column_type.build(column)?;
+    let unique =
+        (column.unique || generator.always_unique()) && !generator.distinct_by_construction();
+    let kind = generator.kind();
+    Ok(ResolvedColumn {
+        type_name: column_type.name(),
+        generator,
+        null_rate: column.null_rate.unwrap_or(0.0),
+        kind,
+        seen: unique.then(|| Mutex::new(HashSet::new())),
+    })
+}
 
-    #[test]
-    fn test_generate_date() {
-        let mut rng = StdRng::seed_from_u64(42);
-        let date = generate_date(&mut rng);
-        assert!(date.matches('-').count() == 2);
-        let parts: Vec<&str> = date.split('-').collect();
-        assert_eq!(parts.len(), 3);
-        assert_eq!(parts[0].len(), 4);
-        assert_eq!(parts[1].len(), 2);
-        assert_eq!(parts[2].len(), 2);
-        let year: i32 = parts[0].parse().unwrap();
-        assert!(year >= 2020 && year <= 2025);
-        let month: i32 = parts[1].parse().unwrap();
-        assert!(month >= 1 && month <= 12);
-        let day: i32 = parts[2].parse().unwrap();
-        assert!(day >= 1 && day <= 28);
-    }
+/// Orders columns so each comes after the columns it depends on, keeping
+/// header order otherwise. Fails on references to missing columns and on
+/// cycles.
+fn generation_order(
+    headers: &[String],
+    columns: &[ResolvedColumn],
+) -> Result<Vec<usize>, GeneratorError> {
+    let invalid = |position: usize, message: String| GeneratorError::InvalidParameter {
+        column: headers[position].clone(),
+        message,
+    };
 
-    #[test]
-    fn test_generate_random_text() {
-        let mut rng = StdRng::seed_from_u64(42);
-        let text = generate_random_text(&mut rng);
-        assert!(text.len() >= 5 && text.len() <= 12);
-        assert!(text.chars().all(|c| c.is_alphanumeric()));
+    let mut dependencies = Vec::with_capacity(columns.len());
+    for (position, column) in columns.iter().enumerate() {
+        let mut positions = Vec::new();
+        for name in column.generator.depends_on() {
+            match headers.iter().position(|header| *header == name) {
+                Some(other) if other == position => {
+                    return Err(invalid(
+                        position,
+                        "a column cannot refer to itself".to_string(),
+                    ));
+                }
+                Some(other) => positions.push(other),
+                None => return Err(invalid(position, format!("no column named '{}'", name))),
+            }
+        }
+        dependencies.push(positions);
     }
 
-    #[test]
-    fn test_generate_value_id() {
-        let generator = SmartGenerator::new(vec![]);
-        let mut rng = StdRng::seed_from_u64(42);
-        let value = generator.generate_value(&DataType::Id, 5, &mut rng);
-        assert_eq!(value, "5");
+    let mut order = Vec::with_capacity(columns.len());
+    let mut done = vec![false; columns.len()];
+    while order.len() < columns.len() {
+        let ready = (0..columns.len())
+            .find(|&position| !done[position] && dependencies[position].iter().all(|&d| done[d]));
+        let Some(position) = ready else {
+            // Drop the columns that merely wait on a cycle, leaving the ones
+            // on it.
+            let mut waiting: Vec<usize> = (0..columns.len()).filter(|&p| !done[p]).collect();
+            loop {
+                let before = waiting.len();
+                let blocking: Vec<usize> = waiting
+                    .iter()
+                    .filter(|&&p| {
+                        waiting
+                            .iter()
+                            .any(|&other| dependencies[other].contains(&p))
+                    })
+                    .copied()
+                    .collect();
+                waiting = blocking;
+                if waiting.len() == before {
+                    break;
+                }
+            }
+            let names: Vec<&str> = waiting.iter().map(|&p| headers[p].as_str()).collect();
+            return Err(invalid(
+                waiting[0],
+                format!(
+                    "columns refer to each other in a cycle: {}",
+                    names.join(", ")
+                ),
+            ));
+        };
+        done[position] = true;
+        order.push(position);
     }
+    Ok(order)
+}
 
-    #[test]
-    fn test_generate_value_age() {
-        let generator = SmartGenerator::new(vec![]);
-        let mut rng = StdRng::seed_from_u64(42);
-        let value = generator.generate_value(&DataType::Age, 0, &mut rng);
-        let age: i32 = value.parse().unwrap();
-        assert!(age >= 18 && age <= 80);
-    }
+/// "auto" in a column type map means "detect from the header name".
+pub fn is_auto_type(type_name: &str) -> bool {
+    type_name.trim().eq_ignore_ascii_case("auto")
+}
 
-    #[test]
-    fn test_generate_value_money() {
-        let generator = SmartGenerator::new(vec![]);
-        let mut rng = StdRng::seed_from_u64(42);
-        let value = generator.generate_value(&DataType::Money, 0, &mut rng);
-        assert!(value.contains('.'));
-        let parts: Vec<&str> = value.split('.').collect();
-        assert_eq!(parts.len(), 2);
-        assert_eq!(parts[1].len(), 2);
-        let amount: f64 = value.parse().unwrap();
-        assert!(amount >= 10.0 && amount <= 10000.0);
-    }
+#[cfg(test)]
+#[allow(clippy::manual_range_contains)]
+mod tests {
+    use super::*;
+    use crate::generators::registry::ColumnType;
+    use crate::schema::Bound;
+    use crate::value::{Value, texts};
+    use rand::RngCore;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
 
     #[test]
     fn test_smart_generator_new() {
@@ -510,7 +369,7 @@ mod tests {
         ];
         let generator = SmartGenerator::new(headers.clone());
         let mut rng = StdRng::seed_from_u64(42);
-        let row = generator.generate_row(1, &mut rng);
+        let row = texts(&generator.generate_row(1, &mut rng));
         assert_eq!(row.len(), 4);
         assert_eq!(row[0], "1");
         assert!(row[1].contains(' '));
@@ -519,13 +378,48 @@ mod tests {
         assert!(age >= 18 && age <= 80);
     }
 
+    #[test]
+    fn test_generate_row_typed_values() {
+        let mut code = ColumnSpec::new("code", "id");
+        code.prefix = Some("C-".to_string());
+        let mut nickname = ColumnSpec::new("nickname", "text");
+        nickname.null_rate = Some(1.0);
+        let mut total = ColumnSpec::new("total", "computed");
+        total.expression = Some("price * 2".to_string());
+        let mut label = ColumnSpec::new("label", "computed");
+        label.expression = Some("code + '!'".to_string());
+        let columns = vec![
+            ColumnSpec::new("id", "auto"),
+            code,
+            ColumnSpec::new("age", "age"),
+            ColumnSpec::new("price", "money"),
+            ColumnSpec::new("joined", "date"),
+            ColumnSpec::new("is_active", "auto"),
+            nickname,
+            total,
+            label,
+        ];
+        let generator = SmartGenerator::from_columns(&columns).unwrap();
+        let row = generator.generate_row(3, &mut StdRng::seed_from_u64(5));
+
+        assert_eq!(row[0], Value::Int(3));
+        assert_eq!(row[1], Value::String("C-3".to_string()));
+        assert!(matches!(row[2], Value::Int(18..=80)));
+        assert!(matches!(&row[3], Value::Decimal(price) if price.contains('.')));
+        assert!(matches!(row[4], Value::Date(_)));
+        assert!(matches!(row[5], Value::Bool(_)));
+        assert_eq!(row[6], Value::Null);
+        assert!(matches!(row[7], Value::Decimal(_)));
+        assert_eq!(row[8], Value::String("C-3!".to_string()));
+    }
+
     #[test]
     fn test_smart_generator_generate_multiple_rows() {
         let headers = vec!["id".to_string(), "name".to_string()];
         let generator = SmartGenerator::new(headers);
         let mut rng = StdRng::seed_from_u64(42);
-        let row1 = generator.generate_row(1, &mut rng);
-        let row2 = generator.generate_row(2, &mut rng);
+        let row1 = texts(&generator.generate_row(1, &mut rng));
+        let row2 = texts(&generator.generate_row(2, &mut rng));
         assert_eq!(row1[0], "1");
         assert_eq!(row2[0], "2");
         assert_ne!(row1[1], row2[1]);
@@ -547,7 +441,7 @@ mod tests {
         ];
         let generator = SmartGenerator::new(headers);
         let mut rng = StdRng::seed_from_u64(42);
-        let row = generator.generate_row(10, &mut rng);
+        let row = texts(&generator.generate_row(10, &mut rng));
         assert_eq!(row.len(), 10);
         assert_eq!(row[0], "10");
         assert!(row[1].contains(' '));
@@ -568,8 +462,8 @@ mod tests {
         let generator = SmartGenerator::new(headers);
         let mut rng1 = StdRng::seed_from_u64(123);
         let mut rng2 = StdRng::seed_from_u64(123);
-        let row1 = generator.generate_row(1, &mut rng1);
-        let row2 = generator.generate_row(1, &mut rng2);
+        let row1 = texts(&generator.generate_row(1, &mut rng1));
+        let row2 = texts(&generator.generate_row(1, &mut rng2));
         assert_eq!(row1, row2);
     }
 
@@ -579,8 +473,580 @@ mod tests {
         let generator = SmartGenerator::new(headers);
         let mut rng1 = StdRng::seed_from_u64(123);
         let mut rng2 = StdRng::seed_from_u64(456);
-        let row1 = generator.generate_row(1, &mut rng1);
-        let row2 = generator.generate_row(1, &mut rng2);
+        let row1 = texts(&generator.generate_row(1, &mut rng1));
+        let row2 = texts(&generator.generate_row(1, &mut rng2));
         assert_ne!(row1, row2);
     }
+
+    #[test]
+    fn test_with_column_types_overrides_detection() {
+        let headers = vec!["contact".to_string(), "user_id".to_string()];
+        let mut column_types = HashMap::new();
+        column_types.insert("contact".to_string(), "email".to_string());
+        column_types.insert("user_id".to_string(), "Text".to_string());
+
+        let generator = SmartGenerator::with_column_types(headers, &column_types).unwrap();
+        let mut rng = StdRng::seed_from_u64(42);
+        let row = texts(&generator.generate_row(7, &mut rng));
+        assert!(row[0].contains('@'));
+        assert_ne!(row[1], "7");
+    }
+
+    #[test]
+    fn test_with_column_types_falls_back_to_detection() {
+        let headers = vec!["id".to_string(), "email".to_string()];
+        let mut column_types = HashMap::new();
+        column_types.insert("email".to_string(), "auto".to_string());
+
+        let generator = SmartGenerator::with_column_types(headers, &column_types).unwrap();
+        let mut rng = StdRng::seed_from_u64(42);
+        let row = texts(&generator.generate_row(3, &mut rng));
+        assert_eq!(row[0], "3");
+        assert!(row[1].contains('@'));
+    }
+
+    #[test]
+    fn test_with_column_types_unknown_type() {
+        let headers = vec!["contact".to_string()];
+        let mut column_types = HashMap::new();
+        column_types.insert("contact".to_string(), "telepathy".to_string());
+
+        let result = SmartGenerator::with_column_types(headers, &column_types);
+        match result {
+            Err(GeneratorError::UnknownDataType { column, type_name }) => {
+                assert_eq!(column, "contact");
+                assert_eq!(type_name, "telepathy");
+            }
+            _ => panic!("expected UnknownDataType error"),
+        }
+    }
+
+    #[test]
+    fn test_from_columns_enum() {
+        let mut plan = ColumnSpec::new("plan", "enum");
+        plan.values = Some(vec!["free".to_string(), "pro".to_string()]);
+        let columns = vec![ColumnSpec::new("user_id", "auto"), plan];
+
+        let generator = SmartGenerator::from_columns(&columns).unwrap();
+        assert_eq!(generator.headers(), vec!["user_id", "plan"]);
+
+        let mut rng = StdRng::seed_from_u64(5);
+        for i in 1..=50 {
+            let row = texts(&generator.generate_row(i, &mut rng));
+            assert_eq!(row[0], i.to_string());
+            assert!(row[1] == "free" || row[1] == "pro");
+        }
+    }
+
+    #[test]
+    fn test_weighted_enum_distribution() {
+        let headers = vec!["status".to_string()];
+        let mut column_types = HashMap::new();
+        column_types.insert(
+            "status".to_string(),
+            "enum: active=80, suspended=15, deleted=5".to_string(),
+        );
+        let generator = SmartGenerator::with_column_types(headers, &column_types).unwrap();
+
+        for seed in [1, 2, 3] {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let rows = 20_000;
+            let mut counts: HashMap<String, usize> = HashMap::new();
+            for i in 1..=rows {
+                *counts
+                    .entry(texts(&generator.generate_row(i, &mut rng)).remove(0))
+                    .or_default() += 1;
+            }
+            assert_eq!(counts.len(), 3);
+            for (value, expected) in [("active", 0.80), ("suspended", 0.15), ("deleted", 0.05)] {
+                let share = counts[value] as f64 / rows as f64;
+                assert!(
+                    (share - expected).abs() < 0.01,
+                    "seed {}: {} at {}",
+                    seed,
+                    value,
+                    share
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_columns_dictionary() {
+        use crate::dictionary::DictionaryEntry;
+
+        let entry = |value: &str, weight: f64| DictionaryEntry {
+            value: value.to_string(),
+            weight: Some(weight),
+        };
+        let mut dept = ColumnSpec::new("dept", "dictionary");
+        dept.dictionary = Some("departments".to_string());
+        let missing = SmartGenerator::from_columns(std::slice::from_ref(&dept));
+        assert!(missing.is_err());
+
+        dept.dictionary_entries =
+            Some(vec![entry("ENG", 9.0), entry("OPS", 1.0), entry("FIN", 0.0)].into());
+        let generator = SmartGenerator::from_columns(&[dept]).unwrap();
+        let mut rng = StdRng::seed_from_u64(5);
+        let rows: Vec<String> = (1..=1000)
+            .map(|i| texts(&generator.generate_row(i, &mut rng)).remove(0))
+            .collect();
+        let eng = rows.iter().filter(|v| *v == "ENG").count();
+        assert!(rows.iter().all(|v| v == "ENG" || v == "OPS"));
+        assert!((850..=950).contains(&eng), "{}", eng);
+    }
+
+    #[test]
+    fn test_from_columns_enum_without_values() {
+        let result = SmartGenerator::from_columns(&[ColumnSpec::new("plan", "enum")]);
+        match result {
+            Err(GeneratorError::InvalidParameter { column, .. }) => assert_eq!(column, "plan"),
+            _ => panic!("expected InvalidParameter error"),
+        }
+    }
+
+    #[test]
+    fn test_from_columns_applies_parameters() {
+        let mut age = ColumnSpec::new("age", "age");
+        age.min = Some(Bound::Number(65.0));
+        age.max = Some(Bound::Number(67.0));
+        let mut joined = ColumnSpec::new("joined", "date");
+        joined.min = Some(Bound::Text("2019-02-01".to_string()));
+        joined.max = Some(Bound::Text("2019-02-28".to_string()));
+        joined.format = Some("%m/%d/%Y".to_string());
+        let mut price = ColumnSpec::new("price", "money");
+        price.precision = Some(0);
+        price.currency = Some("$".to_string());
+        let mut phone = ColumnSpec::new("phone", "phone");
+        phone.format = Some("+33 # ## ## ## ##".to_string());
+        let mut code = ColumnSpec::new("code", "text");
+        code.min_length = Some(3);
+        code.max_length = Some(3);
+
+        let generator = SmartGenerator::from_columns(&[age, joined, price, phone, code]).unwrap();
+        let mut rng = StdRng::seed_from_u64(8);
+        for i in 0..50 {
+            let row = texts(&generator.generate_row(i, &mut rng));
+            let age: i32 = row[0].parse().unwrap();
+            assert!((65..=67).contains(&age));
+            assert!(
+                row[1].starts_with("02/") && row[1].ends_with("/2019"),
+                "{}",
+                row[1]
+            );
+            assert!(
+                row[2].starts_with('$') && !row[2].contains('.'),
+                "{}",
+                row[2]
+            );
+            assert!(
+                row[3].starts_with("+33 ") && row[3].len() == 17,
+                "{}",
+                row[3]
+            );
+            assert_eq!(row[4].len(), 3);
+        }
+    }
+
+    #[test]
+    fn test_from_columns_invalid_parameters() {
+        let mut age = ColumnSpec::new("age", "age");
+        age.max = Some(Bound::Number(10.0));
+        match SmartGenerator::from_columns(&[age]) {
+            Err(GeneratorError::InvalidParameter { column, message }) => {
+                assert_eq!(column, "age");
+                assert_eq!(message, "min (18, the default) is greater than max (10)");
+            }
+            _ => panic!("expected InvalidParameter error"),
+        }
+    }
+
+    struct Sequence;
+
+    impl ColumnType for Sequence {
+        fn name(&self) -> &'static str {
+            "sequence"
+        }
+
+        fn description(&self) -> &'static str {
+            "A prefixed row number"
+        }
+
+        fn detects(&self, header: &str) -> bool {
+            header.starts_with("seq")
+        }
+
+        fn build(&self, column: &ColumnSpec) -> Result<Box<dyn ColumnGenerator>, GeneratorError> {
+            let prefix = column.name.to_uppercase();
+            Ok(Box::new(move |index: usize, _: &mut dyn RngCore| {
+                format!("{}-{}", prefix, index)
+            }))
+        }
+    }
+
+    #[test]
+    fn test_from_columns_in_custom_registry() {
+        let mut registry = Registry::with_builtins();
+        registry.register(Sequence);
+        let columns = vec![
+            ColumnSpec::new("seq", "auto"),
+            ColumnSpec::new("code", "Sequence"),
+            ColumnSpec::new("email", "auto"),
+        ];
+
+        let generator = SmartGenerator::from_columns_in(&registry, &columns).unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        let row = texts(&generator.generate_row(4, &mut rng));
+        assert_eq!(row[0], "SEQ-4");
+        assert_eq!(row[1], "CODE-4");
+        assert!(row[2].contains('@'));
+
+        // The process-wide registry is unaffected.
+        assert!(SmartGenerator::from_columns(&columns[1..2]).is_err());
+    }
+
+    fn unique(name: &str, type_name: &str) -> ColumnSpec {
+        ColumnSpec {
+            unique: true,
+            ..ColumnSpec::new(name, type_name)
+        }
+    }
+
+    fn column_values(generator: &SmartGenerator, rows: usize, seed: u64) -> Vec<Vec<String>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let rows: Vec<Vec<String>> = (1..=rows)
+            .map(|i| texts(&generator.generate_row(i, &mut rng)))
+            .collect();
+        (0..generator.headers().len())
+            .map(|column| rows.iter().map(|row| row[column].clone()).collect())
+            .collect()
+    }
+
+    fn distinct(values: &[String]) -> usize {
+        values.iter().collect::<HashSet<_>>().len()
+    }
+
+    #[test]
+    fn test_unique_columns_exhaust_small_value_spaces() {
+        let mut tier = unique("tier", "enum");
+        tier.values = Some(vec!["a".to_string(), "b".to_string(), "c".to_string()]);
+        let mut age = unique("age", "age");
+        age.min = Some(Bound::Number(18.0));
+        age.max = Some(Bound::Number(20.0));
+        let mut code = unique("code", "text");
+        code.min_length = Some(1);
+        code.max_length = Some(1);
+
+        let columns = [tier, age, code];
+        SmartGenerator::from_columns(&columns)
+            .unwrap()
+            .check_row_count(3)
+            .unwrap();
+        for seed in 0..20 {
+            // Seen values live in the generator, so every run needs a fresh one.
+            let generator = SmartGenerator::from_columns(&columns).unwrap();
+            for values in column_values(&generator, 3, seed) {
+                assert_eq!(distinct(&values), 3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_id_strategies() {
+        let customer = ColumnSpec {
+            start: Some(42),
+            prefix: Some("CUST-".to_string()),
+            width: Some(6),
+            ..ColumnSpec::new("customer_id", "id")
+        };
+        let ticket = ColumnSpec {
+            strategy: Some("random".to_string()),
+            max: Some(Bound::Number(10.0)),
+            ..ColumnSpec::new("ticket", "id")
+        };
+        let order = ColumnSpec {
+            strategy: Some("ulid".to_string()),
+            prefix: Some("ord_".to_string()),
+            ..ColumnSpec::new("order_id", "id")
+        };
+        let columns = [customer, ticket, order];
+
+        let generator = SmartGenerator::from_columns(&columns).unwrap();
+        let values = column_values(&generator, 10, 7);
+        assert_eq!(values[0][0], "CUST-000042");
+        assert_eq!(values[0][9], "CUST-000051");
+        // Random ids never repeat, without the column being `unique`.
+        assert_eq!(distinct(&values[1]), 10);
+        assert!(values[2].iter().all(|id| id.starts_with("ord_01HK")));
+        assert!(generator.check_row_count(11).is_err());
+
+        let again = SmartGenerator::from_columns(&columns).unwrap();
+        assert_eq!(column_values(&again, 10, 7), values);
+    }
+
+    #[test]
+    fn test_unique_columns_suffix_unbounded_types() {
+        let generator = SmartGenerator::from_columns(&[
+            unique("name", "name"),
+            unique("email", "email"),
+            unique("city", "city"),
+        ])
+        .unwrap();
+        generator.check_row_count(2_000).unwrap();
+
+        let columns = column_values(&generator, 2_000, 11);
+        for values in &columns {
+            assert_eq!(distinct(values), 2_000);
+        }
+        assert!(columns[1].iter().all(|email| {
+            let (user, domain) = email.split_once('@').unwrap();
+            !user.is_empty() && domain.contains('.')
+        }));
+    }
+
+    #[test]
+    fn test_unique_rows_are_reproducible() {
+        let columns = [unique("email", "email"), unique("age", "age")];
+        let first = SmartGenerator::from_columns(&columns).unwrap();
+        let second = SmartGenerator::from_columns(&columns).unwrap();
+        assert_eq!(column_values(&first, 63, 5), column_values(&second, 63, 5));
+    }
+
+    #[test]
+    fn test_null_rate() {
+        let mut never = ColumnSpec::new("a", "name");
+        never.null_rate = Some(0.0);
+        let mut half = ColumnSpec::new("b", "name");
+        half.null_rate = Some(0.5);
+        let mut always = ColumnSpec::new("c", "name");
+        always.null_rate = Some(1.0);
+        let mut city = unique("city", "city");
+        city.null_rate = Some(0.9);
+
+        let generator = SmartGenerator::from_columns(&[never, half, always, city]).unwrap();
+        let columns = column_values(&generator, 1_000, 3);
+
+        let nulls = |values: &[String]| values.iter().filter(|v| v.is_empty()).count();
+        assert_eq!(nulls(&columns[0]), 0);
+        assert!((400..=600).contains(&nulls(&columns[1])));
+        assert_eq!(nulls(&columns[2]), 1_000);
+        // Nulls may repeat in a unique column; values may not.
+        let values: Vec<&String> = columns[3].iter().filter(|v| !v.is_empty()).collect();
+        assert!((50..=150).contains(&values.len()));
+        assert_eq!(values.iter().collect::<HashSet<_>>().len(), values.len());
+    }
+
+    #[test]
+    fn test_unique_row_count_above_cardinality() {
+        let mut tier = unique("tier", "enum");
+        tier.values = Some(vec!["a".to_string(), "b".to_string(), "a".to_string()]);
+        let generator =
+            SmartGenerator::from_columns(&[unique("id", "id"), unique("age", "age"), tier])
+                .unwrap();
+
+        let err = generator.check_row_count(3).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unique column 'tier' has only 2 possible values, but 3 rows were requested"
+        );
+        assert!(generator.check_row_count(2).is_ok());
+        assert!(
+            SmartGenerator::from_columns(&[ColumnSpec::new("age", "age")])
+                .unwrap()
+                .check_row_count(1_000)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_unique_row_count_above_memory_limit() {
+        let generator =
+            SmartGenerator::from_columns(&[unique("id", "id"), unique("email", "email")]).unwrap();
+        assert!(generator.columns[0].seen.is_none());
+        assert_eq!(
+            generator
+                .check_row_count(MAX_UNIQUE_ROWS + 1)
+                .unwrap_err()
+                .to_string(),
+            "Unique column 'email' can fill at most 1000000 rows, but 1000001 rows were requested"
+        );
+        assert!(generator.check_row_count(MAX_UNIQUE_ROWS).is_ok());
+
+        let sequential = SmartGenerator::from_columns(&[unique("id", "id")]).unwrap();
+        assert!(sequential.check_row_count(10_000_000).is_ok());
+    }
+
+    #[test]
+    fn test_cross_column_rules() {
+        // Dependent columns come first, so generation must reorder them.
+        let mut email = ColumnSpec::new("email", "email");
+        email.from = Some("name".to_string());
+        let mut city = ColumnSpec::new("city", "city");
+        city.within = Some("country".to_string());
+        let mut ends = ColumnSpec::new("ends", "date");
+        ends.min_column = Some("starts".to_string());
+        let mut total = ColumnSpec::new("total", "computed");
+        total.expression = Some("price * quantity".to_string());
+        total.currency = Some("$".to_string());
+        let mut price = ColumnSpec::new("price", "money");
+        price.currency = Some("$".to_string());
+        let mut quantity = ColumnSpec::new("quantity", "age");
+        quantity.min = Some(Bound::Number(1.0));
+        quantity.max = Some(Bound::Number(5.0));
+        let columns = [
+            email,
+            city,
+            ends,
+            total,
+            ColumnSpec::new("name", "name"),
+            ColumnSpec::new("country", "country"),
+            ColumnSpec::new("starts", "date"),
+            price,
+            quantity,
+        ];
+
+        let generator = SmartGenerator::from_columns(&columns).unwrap();
+        let mut rng = StdRng::seed_from_u64(4);
+        for i in 1..=200 {
+            let row = texts(&generator.generate_row(i, &mut rng));
+            let (user, _) = row[0].split_once('@').unwrap();
+            assert_eq!(user, row[4].to_lowercase().replace(' ', "."));
+            assert!(
+                super::super::builtin::cities_of(&row[5])
+                    .unwrap()
+                    .contains(&row[1].as_str()),
+                "{} is not in {}",
+                row[1],
+                row[5]
+            );
+            assert!(row[2] >= row[6], "{} before {}", row[2], row[6]);
+            let price: f64 = row[7][1..].parse().unwrap();
+            let quantity: f64 = row[8].parse().unwrap();
+            assert_eq!(row[3], format!("${:.2}", price * quantity));
+        }
+    }
+
+    #[test]
+    fn test_cross_column_rules_with_null_sources() {
+        let mut name = ColumnSpec::new("name", "name");
+        name.null_rate = Some(1.0);
+        let mut email = ColumnSpec::new("email", "email");
+        email.from = Some("name".to_string());
+        let mut quantity = ColumnSpec::new("quantity", "age");
+        quantity.null_rate = Some(1.0);
+        let mut total = ColumnSpec::new("total", "computed");
+        total.expression = Some("quantity * 2".to_string());
+
+        let generator = SmartGenerator::from_columns(&[name, email, quantity, total]).unwrap();
+        let row = texts(&generator.generate_row(1, &mut StdRng::seed_from_u64(1)));
+        assert!(row[1].contains('@'));
+        assert_eq!(row[3], "");
+    }
+
+    #[test]
+    fn test_cross_column_reference_errors() {
+        let message = |columns: &[ColumnSpec]| match SmartGenerator::from_columns(columns) {
+            Err(GeneratorError::InvalidParameter { column, message }) => {
+                format!("{}: {}", column, message)
+            }
+            _ => panic!("expected InvalidParameter error"),
+        };
+        let computed = |name: &str, expression: &str| ColumnSpec {
+            expression: Some(expression.to_string()),
+            ..ColumnSpec::new(name, "computed")
+        };
+
+        assert_eq!(
+            message(&[computed("total", "price * 2")]),
+            "total: no column named 'price'"
+        );
+        assert_eq!(
+            message(&[computed("total", "total + 1")]),
+            "total: a column cannot refer to itself"
+        );
+        assert_eq!(
+            message(&[
+                computed("report", "a"),
+                computed("a", "b"),
+                computed("b", "a"),
+            ]),
+            "a: columns refer to each other in a cycle: a, b"
+        );
+    }
+
+    #[test]
+    fn test_computed_expressions() {
+        let computed = |name: &str, expression: &str| ColumnSpec {
+            expression: Some(expression.to_string()),
+            ..ColumnSpec::new(name, "computed")
+        };
+        let mut due = computed("due", "ordered + 30");
+        due.format = Some("%d.%m.%Y".to_string());
+        let columns = [
+            computed("order", "format('ORD-{:04}', row())"),
+            computed("full_name", "first + ' ' + upper(last)"),
+            ColumnSpec::new("first", "name"),
+            ColumnSpec::new("last", "name"),
+            ColumnSpec::new("ordered", "date"),
+            due,
+            computed("band", "if(length(first) > 12, 'long', 'short')"),
+        ];
+
+        let generator = SmartGenerator::from_columns(&columns).unwrap();
+        let mut rng = StdRng::seed_from_u64(9);
+        for i in 1..=20 {
+            let row = texts(&generator.generate_row(i, &mut rng));
+            assert_eq!(row[0], format!("ORD-{:04}", i));
+            assert_eq!(row[1], format!("{} {}", row[2], row[3].to_uppercase()));
+            let ordered = chrono::NaiveDate::parse_from_str(&row[4], "%Y-%m-%d").unwrap();
+            assert_eq!(
+                row[5],
+                (ordered + chrono::Duration::days(30))
+                    .format("%d.%m.%Y")
+                    .to_string()
+            );
+            let band = if row[2].chars().count() > 12 {
+                "long"
+            } else {
+                "short"
+            };
+            assert_eq!(row[6], band);
+        }
+    }
+
+    #[test]
+    fn test_computed_null_results_stay_null() {
+        use crate::output::{NullValue, OutputFormat, render};
+        use crate::value::Table;
+
+        let computed = |name: &str, expression: &str| ColumnSpec {
+            expression: Some(expression.to_string()),
+            ..ColumnSpec::new(name, "computed")
+        };
+        let columns = [computed("ratio", "1 / 0"), computed("blank", "'' + ''")];
+        let generator = SmartGenerator::from_columns(&columns).unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        let row = generator.generate_row(1, &mut rng);
+        assert_eq!(row, vec![Value::Null, Value::String(String::new())]);
+        assert_eq!(
+            generator.generate_cell(0, 1, &[None, None], &mut rng),
+            Value::Null
+        );
+
+        let table = Table {
+            headers: generator.headers(),
+            rows: vec![row],
+        };
+        let json = render(&table, OutputFormat::Ndjson, NullValue::JsonNull, "t").unwrap();
+        assert_eq!(
+            String::from_utf8(json).unwrap(),
+            "{\"ratio\":null,\"blank\":\"\"}\n"
+        );
+        let sql = render(&table, OutputFormat::Sql, NullValue::Null, "t").unwrap();
+        assert!(
+            String::from_utf8(sql)
+                .unwrap()
+                .ends_with("VALUES (NULL, '');\n")
+        );
+    }
 }
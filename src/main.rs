@@ -1,8 +1,17 @@
+mod anonymize;
 mod api;
+mod cli;
 mod csv_parser;
 mod db;
+mod dictionary;
+mod fidelity;
 mod generators;
 mod multipart;
+mod output;
+mod profile;
+mod project;
+mod schema;
+mod value;
 
 use actix_cors::Cors;
 use actix_web::{App, HttpServer, web};
@@ -20,6 +29,10 @@ use tokio::signal;
 async fn main() -> Result<(), Box<dyn Error>> {
     dotenv().ok();
     env_logger::init();
+    info!(
+        "Registered column types: {}",
+        generators::registry().names().join(", ")
+    );
 
     let database_url = dotenvy::var("DATABASE_URL").expect("DATABASE_URL must be set");
     info!("Using database: {}", database_url);
@@ -54,9 +67,21 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 .help("Port to run the web server on")
                 .default_value("8080"),
         )
+        .subcommand(cli::generate_command())
+        .subcommand(cli::profile_command())
         .get_matches();
 
-    if matches.get_flag("serve") || matches.get_one::<String>("FILE").is_none() {
+    if let Some(generate_matches) = matches.subcommand_matches("generate") {
+        if let Err(e) = cli::run_generate(generate_matches, &pool).await {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    } else if let Some(profile_matches) = matches.subcommand_matches("profile") {
+        if let Err(e) = cli::run_profile(profile_matches, &pool).await {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    } else if matches.get_flag("serve") || matches.get_one::<String>("FILE").is_none() {
         let port = matches
             .get_one::<String>("port")
             .map(|s| s.as_str())
@@ -106,7 +131,10 @@ async fn run_server(port: &str, pool: SqlitePool) -> std::io::Result<()> {
             .service(
                 web::scope("/api")
                     .route("/health", web::get().to(api::handlers::health_check))
+                    .route("/types", web::get().to(api::handlers::list_types))
+                    .route("/locales", web::get().to(api::handlers::list_locales))
                     .route("/upload", web::post().to(api::handlers::upload_csv))
+                    .route("/anonymize", web::post().to(api::handlers::anonymize))
                     .route(
                         "/extract-headers",
                         web::post().to(api::handlers::extract_headers),
@@ -115,6 +143,10 @@ async fn run_server(port: &str, pool: SqlitePool) -> std::io::Result<()> {
                         "/generate",
                         web::post().to(api::handlers::generate_placeholder),
                     )
+                    .route(
+                        "/generate/stream",
+                        web::post().to(api::handlers::generate_stream),
+                    )
                     .route("/datasets", web::get().to(api::handlers::datasets::list))
                     .route("/datasets", web::post().to(api::handlers::datasets::save))
                     .route(
@@ -133,9 +165,67 @@ async fn run_server(port: &str, pool: SqlitePool) -> std::io::Result<()> {
                         "/datasets/{id}/generate",
                         web::post().to(api::handlers::datasets::generate_from_dataset),
                     )
+                    .route(
+                        "/datasets/{id}/generate/stream",
+                        web::post().to(api::handlers::datasets::generate_from_dataset_stream),
+                    )
+                    .route(
+                        "/datasets/{id}/fidelity",
+                        web::post().to(api::handlers::datasets::fidelity),
+                    )
+                    .route(
+                        "/datasets/{id}/profile",
+                        web::get().to(api::handlers::datasets::profile),
+                    )
                     .route(
                         "/datasets/{id}/duplicate",
                         web::post().to(api::handlers::datasets::duplicate),
+                    )
+                    .route("/projects", web::get().to(api::handlers::projects::list))
+                    .route("/projects", web::post().to(api::handlers::projects::create))
+                    .route(
+                        "/projects/{id}",
+                        web::get().to(api::handlers::projects::get_one),
+                    )
+                    .route(
+                        "/projects/{id}",
+                        web::delete().to(api::handlers::projects::delete),
+                    )
+                    .route(
+                        "/projects/{id}/generate",
+                        web::post().to(api::handlers::projects::generate),
+                    )
+                    .route(
+                        "/dictionaries",
+                        web::get().to(api::handlers::dictionaries::list),
+                    )
+                    .route(
+                        "/dictionaries",
+                        web::post().to(api::handlers::dictionaries::create),
+                    )
+                    .route(
+                        "/dictionaries/upload",
+                        web::post().to(api::handlers::dictionaries::upload),
+                    )
+                    .route(
+                        "/dictionaries/{id}",
+                        web::get().to(api::handlers::dictionaries::get_one),
+                    )
+                    .route(
+                        "/dictionaries/{id}",
+                        web::put().to(api::handlers::dictionaries::update),
+                    )
+                    .route(
+                        "/dictionaries/{id}",
+                        web::delete().to(api::handlers::dictionaries::delete),
+                    )
+                    .route(
+                        "/schemas/validate",
+                        web::post().to(api::handlers::schemas::validate),
+                    )
+                    .route(
+                        "/schemas/import",
+                        web::post().to(api::handlers::schemas::import),
                     ),
             )
     })
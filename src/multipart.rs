@@ -4,6 +4,7 @@ use actix_web::http::StatusCode;
 use actix_web::{HttpResponse, ResponseError};
 use futures_util::StreamExt;
 use log::{debug, error, warn};
+use std::collections::HashMap;
 
 #[derive(Debug)]
 pub enum MultipartError {
@@ -68,6 +69,43 @@ pub async fn parse_multipart(mut payload: Multipart) -> Result<Vec<u8>, Multipar
     Ok(csv_bytes)
 }
 
+/// The fields of a multipart form by name, e.g. an uploaded `file` next to
+/// plain text fields. Unnamed fields are skipped.
+pub async fn parse_form(
+    mut payload: Multipart,
+) -> Result<HashMap<String, Vec<u8>>, MultipartError> {
+    let mut fields = HashMap::new();
+
+    while let Some(item) = payload.next().await {
+        let mut field = match item {
+            Ok(field) => field,
+            Err(e) => {
+                error!("Error reading multipart field: {}", e);
+                return Err(MultipartError::ReadError(e.to_string()));
+            }
+        };
+        let Some(name) = field.name().map(str::to_string) else {
+            continue;
+        };
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.next().await {
+            match chunk {
+                Ok(data) => bytes.extend_from_slice(&data),
+                Err(e) => {
+                    error!("Error reading chunk: {}", e);
+                    return Err(MultipartError::ReadError(e.to_string()));
+                }
+            }
+        }
+        fields.insert(name, bytes);
+    }
+
+    debug!("Received multipart form with {} fields", fields.len());
+
+    Ok(fields)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
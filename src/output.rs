@@ -0,0 +1,802 @@
+use crate::generators::DataGenerator;
+use crate::value::{Table, Value};
+use log::debug;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+/// Most rows one streamed download or CLI run may ask for.
+pub const MAX_STREAM_ROWS: usize = 10_000_000;
+
+// Rows are generated and encoded this many at a time, so memory use stays
+// flat no matter how many rows are requested.
+const ROWS_PER_CHUNK: usize = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Csv,
+    Tsv,
+    /// A single JSON array of objects keyed by header, in header order.
+    Json,
+    Ndjson,
+    /// One `INSERT` statement per row, into a table named after the dataset.
+    Sql,
+    Markdown,
+}
+
+impl OutputFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            OutputFormat::Csv => "text/csv; charset=utf-8",
+            OutputFormat::Tsv => "text/tab-separated-values; charset=utf-8",
+            OutputFormat::Json => "application/json",
+            OutputFormat::Ndjson => "application/x-ndjson",
+            OutputFormat::Sql => "application/sql; charset=utf-8",
+            OutputFormat::Markdown => "text/markdown; charset=utf-8",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Csv => "csv",
+            OutputFormat::Tsv => "tsv",
+            OutputFormat::Json => "json",
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::Sql => "sql",
+            OutputFormat::Markdown => "md",
+        }
+    }
+
+    /// Whether several tables fit in one file of this format, as for a
+    /// project bundle.
+    pub fn holds_many_tables(self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Json | OutputFormat::Sql | OutputFormat::Markdown
+        )
+    }
+
+    fn delimiter(self) -> u8 {
+        match self {
+            OutputFormat::Tsv => b'\t',
+            _ => b',',
+        }
+    }
+}
+
+/// How null cells are written. Empty strings are never nulls.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NullValue {
+    /// An empty string in every format, e.g. `""` in JSON and `''` in SQL.
+    #[default]
+    #[serde(rename = "empty")]
+    Empty,
+    /// `NULL` in CSV, TSV and Markdown.
+    #[serde(rename = "NULL")]
+    Null,
+    /// `\N`, as read by PostgreSQL `COPY` and MySQL `LOAD DATA`, in CSV, TSV
+    /// and Markdown.
+    #[serde(rename = "\\N")]
+    BackslashN,
+    /// An empty cell in CSV, TSV and Markdown.
+    #[serde(rename = "null")]
+    JsonNull,
+}
+
+impl NullValue {
+    /// A cell as written in CSV, TSV and Markdown.
+    fn text(self, value: &Value) -> Cow<'_, str> {
+        if !value.is_null() {
+            return value.text();
+        }
+        Cow::Borrowed(match self {
+            NullValue::Empty | NullValue::JsonNull => "",
+            NullValue::Null => "NULL",
+            NullValue::BackslashN => "\\N",
+        })
+    }
+
+    /// Whether JSON and SQL get a real `null`/`NULL` rather than an empty
+    /// string. Only `Empty` keeps the string.
+    fn is_native(self) -> bool {
+        self != NullValue::Empty
+    }
+}
+
+impl std::str::FromStr for NullValue {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::Value::String(s.to_string())).map_err(|_| {
+            format!(
+                "Unknown null value '{}' (expected empty, NULL, \\N or null)",
+                s
+            )
+        })
+    }
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::Value::String(s.to_ascii_lowercase()))
+            .map_err(|_| format!("Unknown output format '{}'", s))
+    }
+}
+
+/// Lowercases a dataset name into `[a-z0-9_]`, e.g. "Customer Schema" becomes
+/// `customer_schema`. Used for download filenames and SQL table names.
+fn slug(name: &str) -> String {
+    let mut stem = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            stem.push(c.to_ascii_lowercase());
+        } else if !stem.is_empty() && !stem.ends_with('_') {
+            stem.push('_');
+        }
+    }
+    let stem = stem.trim_end_matches('_');
+    if stem.is_empty() {
+        "data".to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+/// Builds a download filename like `customer_schema.csv` from a dataset name.
+pub fn attachment_filename(name: &str, format: OutputFormat) -> String {
+    format!("{}.{}", slug(name), format.extension())
+}
+
+/// Renders a whole table in the given format.
+pub fn render(
+    data: &Table,
+    format: OutputFormat,
+    null_as: NullValue,
+    name: &str,
+) -> io::Result<Vec<u8>> {
+    let mut encoder = RowEncoder::new(format, data.headers.clone(), name).null_as(null_as);
+    encoder.begin()?;
+    for row in &data.rows {
+        encoder.write_row(row)?;
+    }
+    encoder.finish()?;
+    encoder.take()
+}
+
+/// Renders several named tables as one file: a JSON object keyed by table
+/// name, one SQL script inserting into each table in turn, or a Markdown
+/// section per table. Other formats hold a single table.
+pub fn render_bundle<'a>(
+    tables: impl IntoIterator<Item = (&'a str, &'a Table)>,
+    format: OutputFormat,
+    null_as: NullValue,
+) -> io::Result<Vec<u8>> {
+    if !format.holds_many_tables() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{:?} output holds a single table", format),
+        ));
+    }
+
+    let mut bundle = Vec::new();
+    if format == OutputFormat::Json {
+        bundle.push(b'{');
+    }
+    for (position, (name, data)) in tables.into_iter().enumerate() {
+        let body = render(data, format, null_as, name)?;
+        match format {
+            OutputFormat::Json => {
+                if position > 0 {
+                    bundle.push(b',');
+                }
+                write!(bundle, "\n{}: ", serde_json::to_string(name)?)?;
+                bundle.extend_from_slice(body.trim_ascii_end());
+            }
+            OutputFormat::Markdown => {
+                if position > 0 {
+                    bundle.push(b'\n');
+                }
+                writeln!(bundle, "## {}\n", name)?;
+                bundle.extend_from_slice(&body);
+            }
+            _ => {
+                if position > 0 {
+                    bundle.push(b'\n');
+                }
+                bundle.extend_from_slice(&body);
+            }
+        }
+    }
+    if format == OutputFormat::Json {
+        bundle.extend_from_slice(b"\n}\n");
+    }
+    Ok(bundle)
+}
+
+/// Encodes rows one at a time. Encoded bytes accumulate until drained with
+/// `take`, so callers can hand them off after every chunk of rows.
+pub struct RowEncoder {
+    format: OutputFormat,
+    null_as: NullValue,
+    headers: Vec<String>,
+    /// Object keys of JSON rows, in header order.
+    keys: Vec<String>,
+    table: String,
+    csv: csv::Writer<Vec<u8>>,
+    buffer: Vec<u8>,
+    rows_written: usize,
+}
+
+impl RowEncoder {
+    /// `name` is the dataset name; SQL output inserts into its slug.
+    pub fn new(format: OutputFormat, headers: Vec<String>, name: &str) -> Self {
+        Self {
+            format,
+            null_as: NullValue::default(),
+            keys: json_keys(&headers),
+            headers,
+            table: slug(name),
+            csv: csv_writer(format),
+            buffer: Vec::new(),
+            rows_written: 0,
+        }
+    }
+
+    pub fn null_as(mut self, null_as: NullValue) -> Self {
+        self.null_as = null_as;
+        self
+    }
+
+    /// Writes whatever precedes the first row (header line, opening bracket).
+    pub fn begin(&mut self) -> io::Result<()> {
+        match self.format {
+            OutputFormat::Csv | OutputFormat::Tsv => self.csv.write_record(&self.headers)?,
+            OutputFormat::Json => self.buffer.push(b'['),
+            OutputFormat::Ndjson | OutputFormat::Sql => {}
+            OutputFormat::Markdown => {
+                let header = markdown_line(&self.headers);
+                let divider = markdown_line(&vec!["---".to_string(); self.headers.len()]);
+                writeln!(self.buffer, "{}\n{}", header, divider)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn write_row(&mut self, row: &[Value]) -> io::Result<()> {
+        match self.format {
+            OutputFormat::Csv | OutputFormat::Tsv => {
+                let null_as = self.null_as;
+                self.csv
+                    .write_record(row.iter().map(|v| null_as.text(v).into_owned()))?
+            }
+            OutputFormat::Json | OutputFormat::Ndjson => {
+                if self.format == OutputFormat::Json {
+                    let separator: &[u8] = if self.rows_written == 0 {
+                        b"\n"
+                    } else {
+                        b",\n"
+                    };
+                    self.buffer.extend_from_slice(separator);
+                }
+                self.buffer.push(b'{');
+                for (position, (key, value)) in self.keys.iter().zip(row).enumerate() {
+                    if position > 0 {
+                        self.buffer.push(b',');
+                    }
+                    serde_json::to_writer(&mut self.buffer, key)?;
+                    self.buffer.push(b':');
+                    let value = self.json_value(value);
+                    serde_json::to_writer(&mut self.buffer, &value)?;
+                }
+                self.buffer.push(b'}');
+                if self.format == OutputFormat::Ndjson {
+                    self.buffer.push(b'\n');
+                }
+            }
+            OutputFormat::Sql => {
+                let columns: Vec<String> =
+                    self.headers.iter().map(|h| quote_identifier(h)).collect();
+                let values: Vec<String> = row.iter().map(|v| self.sql_literal(v)).collect();
+                writeln!(
+                    self.buffer,
+                    "INSERT INTO {} ({}) VALUES ({});",
+                    quote_identifier(&self.table),
+                    columns.join(", "),
+                    values.join(", ")
+                )?;
+            }
+            OutputFormat::Markdown => {
+                let cells: Vec<String> = row
+                    .iter()
+                    .map(|v| self.null_as.text(v).into_owned())
+                    .collect();
+                writeln!(self.buffer, "{}", markdown_line(&cells))?
+            }
+        }
+        self.rows_written += 1;
+        Ok(())
+    }
+
+    /// Numbers and booleans as themselves, dates as strings.
+    fn json_value(&self, value: &Value) -> serde_json::Value {
+        if value.is_null() && !self.null_as.is_native() {
+            return serde_json::Value::String(String::new());
+        }
+        serde_json::to_value(value).expect("cells serialize to JSON")
+    }
+
+    /// Numbers bare, booleans as `TRUE`/`FALSE`, everything else quoted.
+    fn sql_literal(&self, value: &Value) -> String {
+        match value {
+            Value::Null if self.null_as.is_native() => "NULL".to_string(),
+            Value::Bool(true) => "TRUE".to_string(),
+            Value::Bool(false) => "FALSE".to_string(),
+            value if value.is_number() && !matches!(value, Value::Float(f) if !f.is_finite()) => {
+                value.text().into_owned()
+            }
+            value => quote_literal(&value.text()),
+        }
+    }
+
+    /// Writes whatever follows the last row (the closing bracket for JSON).
+    pub fn finish(&mut self) -> io::Result<()> {
+        if self.format == OutputFormat::Json {
+            self.buffer.extend_from_slice(b"\n]\n");
+        }
+        Ok(())
+    }
+
+    /// Drains the bytes encoded since the previous call.
+    pub fn take(&mut self) -> io::Result<Vec<u8>> {
+        let csv = std::mem::replace(&mut self.csv, csv_writer(self.format));
+        let mut bytes = csv.into_inner().map_err(|e| e.into_error())?;
+        bytes.append(&mut self.buffer);
+        Ok(bytes)
+    }
+}
+
+/// Headers as JSON object keys. A repeated header gets the first free
+/// `_2`, `_3`, ... suffix, so no cell is lost to a duplicate key.
+fn json_keys(headers: &[String]) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::with_capacity(headers.len());
+    for header in headers {
+        let mut key = header.clone();
+        let mut suffix = 2;
+        while keys.contains(&key) || (key != *header && headers.contains(&key)) {
+            key = format!("{}_{}", header, suffix);
+            suffix += 1;
+        }
+        keys.push(key);
+    }
+    keys
+}
+
+fn csv_writer(format: OutputFormat) -> csv::Writer<Vec<u8>> {
+    csv::WriterBuilder::new()
+        .delimiter(format.delimiter())
+        .from_writer(Vec::new())
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn markdown_line(cells: &[String]) -> String {
+    let cells: Vec<String> = cells
+        .iter()
+        .map(|cell| cell.replace('|', "\\|").replace(['\r', '\n'], " "))
+        .collect();
+    format!("| {} |", cells.join(" | "))
+}
+
+/// Yields encoded rows `ROWS_PER_CHUNK` at a time; the first chunk carries the
+/// header and the last one any closing bytes.
+pub struct RowChunks<G> {
+    generator: G,
+    rng: StdRng,
+    encoder: RowEncoder,
+    next_index: usize,
+    row_count: usize,
+    started: bool,
+    finished: bool,
+}
+
+impl<G: DataGenerator> RowChunks<G> {
+    pub fn new(
+        generator: G,
+        row_count: usize,
+        seed: u64,
+        format: OutputFormat,
+        null_as: NullValue,
+        name: &str,
+    ) -> Self {
+        let encoder = RowEncoder::new(format, generator.headers(), name).null_as(null_as);
+        Self {
+            generator,
+            rng: StdRng::seed_from_u64(seed),
+            encoder,
+            next_index: 1,
+            row_count,
+            started: false,
+            finished: false,
+        }
+    }
+
+    fn next_chunk(&mut self) -> io::Result<Vec<u8>> {
+        if !self.started {
+            self.encoder.begin()?;
+            self.started = true;
+        }
+
+        let end = (self.next_index + ROWS_PER_CHUNK).min(self.row_count + 1);
+        for index in self.next_index..end {
+            let row = self.generator.generate_row(index, &mut self.rng);
+            self.encoder.write_row(&row)?;
+        }
+        debug!("Encoded rows {}..{}", self.next_index, end);
+        self.next_index = end;
+
+        if self.next_index > self.row_count {
+            self.encoder.finish()?;
+            self.finished = true;
+        }
+
+        self.encoder.take()
+    }
+}
+
+impl<G: DataGenerator> Iterator for RowChunks<G> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let chunk = self.next_chunk();
+        if chunk.is_err() {
+            self.finished = true;
+        }
+        Some(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::SmartGenerator;
+
+    fn encode(format: OutputFormat, headers: &[&str], rows: &[&[&str]]) -> String {
+        let mut encoder = RowEncoder::new(
+            format,
+            headers.iter().map(|h| h.to_string()).collect(),
+            "Test Table",
+        );
+        encoder.begin().unwrap();
+        for row in rows {
+            let row: Vec<Value> = row.iter().map(|&v| Value::from(v)).collect();
+            encoder.write_row(&row).unwrap();
+        }
+        encoder.finish().unwrap();
+        String::from_utf8(encoder.take().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_encode_csv() {
+        let output = encode(
+            OutputFormat::Csv,
+            &["id", "name"],
+            &[&["1", "Alice"], &["2", "Bob"]],
+        );
+        assert_eq!(output, "id,name\n1,Alice\n2,Bob\n");
+    }
+
+    #[test]
+    fn test_encode_csv_quotes_special_characters() {
+        let output = encode(
+            OutputFormat::Csv,
+            &["id", "address"],
+            &[&["1", "123 Main St, Apt 4"], &["2", "He said \"hi\""]],
+        );
+        assert_eq!(
+            output,
+            "id,address\n1,\"123 Main St, Apt 4\"\n2,\"He said \"\"hi\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn test_encode_ndjson() {
+        let output = encode(
+            OutputFormat::Ndjson,
+            &["id", "name"],
+            &[&["1", "Alice"], &["2", "Bob"]],
+        );
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["id"], "1");
+        assert_eq!(first["name"], "Alice");
+    }
+
+    #[test]
+    fn test_encode_tsv() {
+        let output = encode(
+            OutputFormat::Tsv,
+            &["id", "address"],
+            &[&["1", "123 Main St, Apt 4"]],
+        );
+        assert_eq!(output, "id\taddress\n1\t123 Main St, Apt 4\n");
+    }
+
+    #[test]
+    fn test_encode_json_array() {
+        let output = encode(
+            OutputFormat::Json,
+            &["id", "name"],
+            &[&["1", "Alice"], &["2", "Bob"]],
+        );
+        let rows: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(rows.as_array().unwrap().len(), 2);
+        assert_eq!(rows[1]["name"], "Bob");
+
+        let empty: serde_json::Value =
+            serde_json::from_str(&encode(OutputFormat::Json, &["id"], &[])).unwrap();
+        assert_eq!(empty, serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_encode_json_keeps_header_order() {
+        let output = encode(OutputFormat::Ndjson, &["z", "a", "m"], &[&["1", "2", "3"]]);
+        assert_eq!(output, "{\"z\":\"1\",\"a\":\"2\",\"m\":\"3\"}\n");
+
+        let output = encode(
+            OutputFormat::Json,
+            &["name", "name", "name_2"],
+            &[&["a", "b", "c"]],
+        );
+        assert_eq!(
+            output,
+            "[\n{\"name\":\"a\",\"name_3\":\"b\",\"name_2\":\"c\"}\n]\n"
+        );
+    }
+
+    #[test]
+    fn test_encode_sql() {
+        let output = encode(OutputFormat::Sql, &["id", "name"], &[&["1", "O'Brien"]]);
+        assert_eq!(
+            output,
+            "INSERT INTO \"test_table\" (\"id\", \"name\") VALUES ('1', 'O''Brien');\n"
+        );
+    }
+
+    #[test]
+    fn test_encode_markdown() {
+        let output = encode(OutputFormat::Markdown, &["id", "note"], &[&["1", "a|b"]]);
+        assert_eq!(output, "| id | note |\n| --- | --- |\n| 1 | a\\|b |\n");
+    }
+
+    fn encode_nulls(format: OutputFormat, null_as: NullValue) -> String {
+        let mut encoder = RowEncoder::new(format, vec!["id".to_string(), "note".to_string()], "t")
+            .null_as(null_as);
+        encoder.write_row(&[Value::from("1"), Value::Null]).unwrap();
+        String::from_utf8(encoder.take().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_encode_nulls() {
+        use NullValue::*;
+
+        assert_eq!(encode_nulls(OutputFormat::Csv, Empty), "1,\n");
+        assert_eq!(encode_nulls(OutputFormat::Csv, Null), "1,NULL\n");
+        assert_eq!(encode_nulls(OutputFormat::Tsv, BackslashN), "1\t\\N\n");
+        assert_eq!(encode_nulls(OutputFormat::Csv, JsonNull), "1,\n");
+        assert_eq!(encode_nulls(OutputFormat::Markdown, Null), "| 1 | NULL |\n");
+
+        assert_eq!(
+            encode_nulls(OutputFormat::Ndjson, Empty),
+            "{\"id\":\"1\",\"note\":\"\"}\n"
+        );
+        for null_as in [Null, BackslashN, JsonNull] {
+            assert_eq!(
+                encode_nulls(OutputFormat::Ndjson, null_as),
+                "{\"id\":\"1\",\"note\":null}\n"
+            );
+            assert_eq!(
+                encode_nulls(OutputFormat::Sql, null_as),
+                "INSERT INTO \"t\" (\"id\", \"note\") VALUES ('1', NULL);\n"
+            );
+        }
+        assert_eq!(
+            encode_nulls(OutputFormat::Sql, Empty),
+            "INSERT INTO \"t\" (\"id\", \"note\") VALUES ('1', '');\n"
+        );
+    }
+
+    fn encode_typed(format: OutputFormat) -> String {
+        let headers = ["id", "price", "active", "joined", "note", "code"];
+        let mut encoder =
+            RowEncoder::new(format, headers.iter().map(|h| h.to_string()).collect(), "t")
+                .null_as(NullValue::JsonNull);
+        encoder
+            .write_row(&[
+                Value::Int(7),
+                Value::Decimal("12.50".to_string()),
+                Value::Bool(true),
+                Value::Date("2024-03-01".to_string()),
+                Value::Null,
+                Value::from("007"),
+            ])
+            .unwrap();
+        String::from_utf8(encoder.take().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_encode_typed_values() {
+        assert_eq!(
+            encode_typed(OutputFormat::Csv),
+            "7,12.50,true,2024-03-01,,007\n"
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&encode_typed(OutputFormat::Ndjson)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "id": 7,
+                "price": "12.50",
+                "active": true,
+                "joined": "2024-03-01",
+                "note": null,
+                "code": "007"
+            })
+        );
+        assert!(
+            encode_typed(OutputFormat::Sql)
+                .ends_with("VALUES (7, 12.50, TRUE, '2024-03-01', NULL, '007');\n")
+        );
+    }
+
+    #[test]
+    fn test_null_value_parsing() {
+        assert_eq!("NULL".parse::<NullValue>(), Ok(NullValue::Null));
+        assert_eq!("\\N".parse::<NullValue>(), Ok(NullValue::BackslashN));
+        assert_eq!("null".parse::<NullValue>(), Ok(NullValue::JsonNull));
+        assert_eq!(
+            serde_json::from_str::<NullValue>("\"empty\"").unwrap(),
+            NullValue::Empty
+        );
+        assert!("none".parse::<NullValue>().is_err());
+    }
+
+    #[test]
+    fn test_render_csv_data() {
+        let data = Table {
+            headers: vec!["id".to_string()],
+            rows: vec![vec![Value::from("1")], vec![Value::from("2")]],
+        };
+        let output = render(&data, OutputFormat::Ndjson, NullValue::Empty, "data").unwrap();
+        assert_eq!(output, b"{\"id\":\"1\"}\n{\"id\":\"2\"}\n");
+    }
+
+    #[test]
+    fn test_render_bundle() {
+        let customers = Table {
+            headers: vec!["id".to_string()],
+            rows: vec![vec![Value::from("1")]],
+        };
+        let orders = Table {
+            headers: vec!["id".to_string(), "customer_id".to_string()],
+            rows: vec![vec![Value::from("1"), Value::from("1")]],
+        };
+        let tables = [("customers", &customers), ("orders", &orders)];
+
+        let json = render_bundle(tables, OutputFormat::Json, NullValue::Empty).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(json["customers"][0]["id"], "1");
+        assert_eq!(json["orders"][0]["customer_id"], "1");
+
+        let sql = render_bundle(tables, OutputFormat::Sql, NullValue::Empty).unwrap();
+        assert_eq!(
+            String::from_utf8(sql).unwrap(),
+            "INSERT INTO \"customers\" (\"id\") VALUES ('1');\n\n\
+             INSERT INTO \"orders\" (\"id\", \"customer_id\") VALUES ('1', '1');\n"
+        );
+
+        let markdown = render_bundle(tables, OutputFormat::Markdown, NullValue::Empty).unwrap();
+        assert!(
+            String::from_utf8(markdown)
+                .unwrap()
+                .starts_with("## customers\n\n| id |")
+        );
+
+        assert!(render_bundle(tables, OutputFormat::Csv, NullValue::Empty).is_err());
+    }
+
+    #[test]
+    fn test_take_drains_buffer() {
+        let mut encoder = RowEncoder::new(OutputFormat::Csv, vec!["id".to_string()], "data");
+        encoder.begin().unwrap();
+        assert_eq!(encoder.take().unwrap(), b"id\n");
+        encoder.write_row(&[Value::from("1")]).unwrap();
+        assert_eq!(encoder.take().unwrap(), b"1\n");
+        assert!(encoder.take().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_output_format_deserialization() {
+        let format: OutputFormat = serde_json::from_str("\"ndjson\"").unwrap();
+        assert_eq!(format, OutputFormat::Ndjson);
+        let format: OutputFormat = serde_json::from_str("\"markdown\"").unwrap();
+        assert_eq!(format, OutputFormat::Markdown);
+        assert!(serde_json::from_str::<OutputFormat>("\"xml\"").is_err());
+        assert_eq!("TSV".parse::<OutputFormat>(), Ok(OutputFormat::Tsv));
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn test_attachment_filename() {
+        assert_eq!(
+            attachment_filename("Customer Schema", OutputFormat::Csv),
+            "customer_schema.csv"
+        );
+        assert_eq!(
+            attachment_filename("  orders (2024)! ", OutputFormat::Ndjson),
+            "orders_2024.ndjson"
+        );
+        assert_eq!(attachment_filename("日本", OutputFormat::Csv), "data.csv");
+    }
+
+    #[test]
+    fn test_row_chunks_splits_rows() {
+        let generator = SmartGenerator::new(vec!["id".to_string()]);
+        let chunks: Vec<Vec<u8>> = RowChunks::new(
+            generator,
+            2_500,
+            1,
+            OutputFormat::Csv,
+            NullValue::Empty,
+            "data",
+        )
+        .map(|chunk| chunk.unwrap())
+        .collect();
+
+        assert_eq!(chunks.len(), 3);
+        let output: String = chunks
+            .iter()
+            .map(|chunk| std::str::from_utf8(chunk).unwrap())
+            .collect();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2_501);
+        assert_eq!(lines[0], "id");
+        assert_eq!(lines[1], "1");
+        assert_eq!(lines[2_500], "2500");
+    }
+
+    #[test]
+    fn test_row_chunks_exact_chunk_boundary() {
+        let generator = SmartGenerator::new(vec!["id".to_string()]);
+        let chunks: Vec<Vec<u8>> = RowChunks::new(
+            generator,
+            ROWS_PER_CHUNK,
+            1,
+            OutputFormat::Ndjson,
+            NullValue::Empty,
+            "data",
+        )
+        .map(|chunk| chunk.unwrap())
+        .collect();
+
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(
+            std::str::from_utf8(&chunks[0]).unwrap().lines().count(),
+            ROWS_PER_CHUNK
+        );
+    }
+}
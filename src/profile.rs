@@ -0,0 +1,257 @@
+//! Per-column statistics of a dataset's stored sample rows, to see what a
+//! sample holds without reading through its rows.
+use crate::csv_parser::CsvData;
+use crate::generators::{InferredKind, infer_columns};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Most frequent values listed for each column.
+pub const TOP_VALUES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DatasetProfile {
+    pub row_count: usize,
+    pub columns: Vec<ColumnStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColumnStats {
+    pub name: String,
+    /// Cells in the column, blanks included.
+    pub count: usize,
+    /// Distinct non-blank values.
+    pub distinct: usize,
+    /// Empty or whitespace-only cells.
+    pub blank: usize,
+    /// What the values hold, as inferred on upload.
+    pub kind: InferredKind,
+    /// The column type generating values like these.
+    pub detected_type: String,
+    /// Share of the non-blank values that fit `kind`, from 0 to 1.
+    pub confidence: f64,
+    /// Set when every non-blank value is a number.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numeric: Option<NumericStats>,
+    /// Set for columns of values other than numbers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lengths: Option<LengthStats>,
+    /// The most frequent non-blank values, most frequent first.
+    pub top_values: Vec<ValueCount>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NumericStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+/// Character counts of a column's non-blank values.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LengthStats {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    /// How many values have each length, shortest first.
+    pub histogram: Vec<LengthCount>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LengthCount {
+    pub length: usize,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValueCount {
+    pub value: String,
+    pub count: usize,
+}
+
+/// Profiles every column of `data`. Values are trimmed, as they are when
+/// learning from a sample; missing cells of short rows count as blanks.
+pub fn profile(data: &CsvData) -> DatasetProfile {
+    let inferred = infer_columns(&data.headers, &data.rows);
+    let columns = inferred
+        .into_iter()
+        .enumerate()
+        .map(|(index, inferred)| {
+            let values: Vec<&str> = data
+                .rows
+                .iter()
+                .map(|row| row.get(index).map_or("", |value| value.trim()))
+                .filter(|value| !value.is_empty())
+                .collect();
+            let numbers: Option<Vec<f64>> = values
+                .iter()
+                .map(|v| v.parse::<f64>().ok().filter(|n| n.is_finite()))
+                .collect();
+            let numeric = numbers.filter(|n| !n.is_empty()).map(|n| numeric_stats(&n));
+            let lengths = match numeric {
+                None if !values.is_empty() => Some(length_stats(&values)),
+                _ => None,
+            };
+
+            ColumnStats {
+                name: inferred.name,
+                count: data.rows.len(),
+                distinct: values.iter().collect::<HashSet<_>>().len(),
+                blank: data.rows.len() - values.len(),
+                kind: inferred.kind,
+                detected_type: inferred.column.type_name,
+                confidence: inferred.confidence,
+                numeric,
+                lengths,
+                top_values: top_values(&values),
+            }
+        })
+        .collect();
+
+    DatasetProfile {
+        row_count: data.rows.len(),
+        columns,
+    }
+}
+
+fn numeric_stats(numbers: &[f64]) -> NumericStats {
+    let n = numbers.len() as f64;
+    let mean = numbers.iter().sum::<f64>() / n;
+    let variance = numbers.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    NumericStats {
+        min: numbers.iter().copied().fold(f64::INFINITY, f64::min),
+        max: numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        mean,
+        std_dev: variance.sqrt(),
+    }
+}
+
+fn length_stats(values: &[&str]) -> LengthStats {
+    let mut counts: HashMap<usize, usize> = HashMap::new();
+    for value in values {
+        *counts.entry(value.chars().count()).or_insert(0) += 1;
+    }
+    let mut histogram: Vec<LengthCount> = counts
+        .into_iter()
+        .map(|(length, count)| LengthCount { length, count })
+        .collect();
+    histogram.sort_by_key(|bucket| bucket.length);
+
+    let total: usize = histogram.iter().map(|b| b.length * b.count).sum();
+    LengthStats {
+        min: histogram[0].length,
+        max: histogram[histogram.len() - 1].length,
+        mean: total as f64 / values.len() as f64,
+        histogram,
+    }
+}
+
+/// Ties are listed in value order, so profiles of the same rows match.
+fn top_values(values: &[&str]) -> Vec<ValueCount> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for value in values {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+    let mut top: Vec<(&str, usize)> = counts.into_iter().collect();
+    top.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    top.into_iter()
+        .take(TOP_VALUES)
+        .map(|(value, count)| ValueCount {
+            value: value.to_string(),
+            count,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(headers: &[&str], rows: &[&[&str]]) -> CsvData {
+        CsvData {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: rows
+                .iter()
+                .map(|row| row.iter().map(|v| v.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_profile_numeric_column() {
+        let data = data(&["age"], &[&["20"], &["30"], &[""], &["30"], &[" 40 "]]);
+        let profile = profile(&data);
+
+        assert_eq!(profile.row_count, 5);
+        let age = &profile.columns[0];
+        assert_eq!((age.count, age.distinct, age.blank), (5, 3, 1));
+        assert_eq!(age.kind, InferredKind::Integer);
+        assert_eq!(age.detected_type, "integer");
+        assert_eq!(
+            age.numeric,
+            Some(NumericStats {
+                min: 20.0,
+                max: 40.0,
+                mean: 30.0,
+                std_dev: 50.0_f64.sqrt(),
+            })
+        );
+        assert_eq!(age.lengths, None);
+        assert_eq!(
+            age.top_values[0],
+            ValueCount {
+                value: "30".to_string(),
+                count: 2
+            }
+        );
+        assert_eq!(age.top_values.len(), 3);
+    }
+
+    #[test]
+    fn test_profile_text_column() {
+        let data = data(
+            &["id", "status"],
+            &[&["1", "open"], &["2", "closed"], &["3", "open"], &["4"]],
+        );
+        let status = &profile(&data).columns[1];
+
+        assert_eq!((status.count, status.distinct, status.blank), (4, 2, 1));
+        assert_eq!(status.kind, InferredKind::Text);
+        assert_eq!(status.numeric, None);
+        assert_eq!(
+            status.lengths,
+            Some(LengthStats {
+                min: 4,
+                max: 6,
+                mean: 14.0 / 3.0,
+                histogram: vec![
+                    LengthCount {
+                        length: 4,
+                        count: 2
+                    },
+                    LengthCount {
+                        length: 6,
+                        count: 1
+                    },
+                ],
+            })
+        );
+        let top: Vec<(&str, usize)> = status
+            .top_values
+            .iter()
+            .map(|v| (v.value.as_str(), v.count))
+            .collect();
+        assert_eq!(top, vec![("open", 2), ("closed", 1)]);
+    }
+
+    #[test]
+    fn test_profile_without_rows() {
+        let profile = profile(&data(&["email"], &[]));
+        let email = &profile.columns[0];
+
+        assert_eq!((email.count, email.distinct, email.blank), (0, 0, 0));
+        assert_eq!(email.kind, InferredKind::Empty);
+        assert_eq!((email.numeric.clone(), email.lengths.clone()), (None, None));
+        assert!(email.top_values.is_empty());
+    }
+}
@@ -0,0 +1,642 @@
+use crate::generators::{DataGenerator, GeneratorError};
+use crate::value::{Table, Value};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Row count bounds of a project table, as for a single dataset.
+pub const MAX_TABLE_ROWS: usize = 1000;
+
+/// Upper bound of `children.max`, which keeps child tables at most
+/// `MAX_TABLE_ROWS * MAX_CHILDREN` rows.
+pub const MAX_CHILDREN: usize = 100;
+
+/// Draws of the parent's key column tried for an orphan key before falling
+/// back to suffixing.
+const ORPHAN_ATTEMPTS: usize = 20;
+
+/// Several saved datasets generated together as related tables:
+///
+/// ```json
+/// {
+///   "name": "shop",
+///   "tables": [
+///     {"name": "customers", "dataset_id": 1, "rows": 50},
+///     {"name": "orders", "dataset_id": 2}
+///   ],
+///   "foreign_keys": [
+///     {"column": "orders.customer_id", "references": "customers.id",
+///      "children": {"min": 1, "max": 5}, "orphan_rate": 0.05}
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectSpec {
+    pub name: String,
+    pub tables: Vec<ProjectTable>,
+    #[serde(default)]
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectTable {
+    /// The name foreign keys and outputs use for the table.
+    pub name: String,
+    pub dataset_id: i64,
+    /// Rows to generate, defaulting to the request's row count. Unused when a
+    /// foreign key sets `children` for this table.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rows: Option<usize>,
+}
+
+/// A child column whose values are keys of a parent table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ForeignKey {
+    /// The child column, e.g. `orders.customer_id`.
+    pub column: ColumnRef,
+    /// The parent key, e.g. `customers.id`.
+    pub references: ColumnRef,
+    /// Child rows per parent row. Sets the child table's row count.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub children: Option<ChildCount>,
+    /// Chance (0 to below 1) of a child row whose key matches no parent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orphan_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChildCount {
+    pub min: usize,
+    pub max: usize,
+}
+
+/// A `table.column` reference.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ColumnRef {
+    pub table: String,
+    pub column: String,
+}
+
+impl TryFrom<String> for ColumnRef {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.split_once('.') {
+            Some((table, column)) if !table.trim().is_empty() && !column.trim().is_empty() => {
+                Ok(Self {
+                    table: table.trim().to_string(),
+                    column: column.trim().to_string(),
+                })
+            }
+            _ => Err(format!("expected 'table.column', got '{}'", value)),
+        }
+    }
+}
+
+impl From<ColumnRef> for String {
+    fn from(value: ColumnRef) -> Self {
+        value.to_string()
+    }
+}
+
+impl std::fmt::Display for ColumnRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.table, self.column)
+    }
+}
+
+/// Every problem found in a project, reported together.
+#[derive(Debug)]
+pub struct ProjectErrors(pub Vec<String>);
+
+impl std::fmt::Display for ProjectErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.join("\n"))
+    }
+}
+
+impl std::error::Error for ProjectErrors {}
+
+/// One generated table of a project.
+#[derive(Debug, Serialize)]
+pub struct GeneratedTable {
+    pub name: String,
+    pub data: Table,
+}
+
+impl ProjectSpec {
+    /// Checks the spec against the headers of its datasets, keyed by dataset
+    /// id. A dataset missing from `headers` does not exist.
+    pub fn validate(&self, headers: &HashMap<i64, Vec<String>>) -> Result<(), ProjectErrors> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            errors.push("Project name must not be empty".to_string());
+        }
+        if self.tables.is_empty() {
+            errors.push("Project must have at least one table".to_string());
+        }
+
+        let mut seen = HashSet::new();
+        for (index, table) in self.tables.iter().enumerate() {
+            let at = format!("tables[{}] '{}'", index, table.name);
+            if table.name.trim().is_empty() {
+                errors.push(format!("tables[{}]: table name must not be empty", index));
+            } else if !seen.insert(table.name.as_str()) {
+                errors.push(format!("{}: duplicate table name", at));
+            }
+            if !headers.contains_key(&table.dataset_id) {
+                errors.push(format!("{}: dataset {} not found", at, table.dataset_id));
+            }
+            if let Some(rows) = table.rows
+                && !(1..=MAX_TABLE_ROWS).contains(&rows)
+            {
+                errors.push(format!(
+                    "{}: rows must be between 1 and {}, got {}",
+                    at, MAX_TABLE_ROWS, rows
+                ));
+            }
+        }
+
+        let mut keyed_columns = HashSet::new();
+        let mut counted_tables: HashMap<&str, usize> = HashMap::new();
+        for (index, key) in self.foreign_keys.iter().enumerate() {
+            let at = format!(
+                "foreign_keys[{}] '{} -> {}'",
+                index, key.column, key.references
+            );
+            for reference in [&key.column, &key.references] {
+                if let Some(problem) = self.column_problem(reference, headers) {
+                    errors.push(format!("{}: {}", at, problem));
+                }
+            }
+            if key.column.table == key.references.table {
+                errors.push(format!("{}: a table cannot reference itself", at));
+            }
+            if !keyed_columns.insert(&key.column) {
+                errors.push(format!("{}: column already has a foreign key", at));
+            }
+            if let Some(children) = key.children {
+                if children.min > children.max {
+                    errors.push(format!(
+                        "{}: children min ({}) is greater than max ({})",
+                        at, children.min, children.max
+                    ));
+                }
+                if children.max > MAX_CHILDREN {
+                    errors.push(format!(
+                        "{}: children max must be at most {}, got {}",
+                        at, MAX_CHILDREN, children.max
+                    ));
+                }
+                match counted_tables.get(key.column.table.as_str()) {
+                    Some(first) => errors.push(format!(
+                        "{}: table '{}' already gets its row count from foreign_keys[{}]",
+                        at, key.column.table, first
+                    )),
+                    None => {
+                        counted_tables.insert(key.column.table.as_str(), index);
+                    }
+                }
+            }
+            if let Some(rate) = key.orphan_rate
+                && !(0.0..1.0).contains(&rate)
+            {
+                errors.push(format!(
+                    "{}: orphan_rate must be at least 0 and below 1, got {}",
+                    at, rate
+                ));
+            }
+        }
+
+        if errors.is_empty()
+            && let Err(cycle) = self.table_order()
+        {
+            errors.push(format!(
+                "Foreign keys form a cycle between tables: {}",
+                cycle.join(", ")
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ProjectErrors(errors))
+        }
+    }
+
+    fn column_problem(
+        &self,
+        reference: &ColumnRef,
+        headers: &HashMap<i64, Vec<String>>,
+    ) -> Option<String> {
+        let Some(table) = self.table_index(&reference.table) else {
+            return Some(format!("no table '{}'", reference.table));
+        };
+        let columns = headers.get(&self.tables[table].dataset_id)?;
+        (!columns.contains(&reference.column)).then(|| {
+            format!(
+                "table '{}' has no column '{}'",
+                reference.table, reference.column
+            )
+        })
+    }
+
+    fn table_index(&self, name: &str) -> Option<usize> {
+        self.tables.iter().position(|t| t.name == name)
+    }
+
+    /// Table indexes with every parent before its children, otherwise in
+    /// definition order. Fails with the tables left on a cycle.
+    fn table_order(&self) -> Result<Vec<usize>, Vec<&str>> {
+        let parents: Vec<Vec<usize>> = self
+            .tables
+            .iter()
+            .map(|table| {
+                self.foreign_keys
+                    .iter()
+                    .filter(|key| key.column.table == table.name)
+                    .filter_map(|key| self.table_index(&key.references.table))
+                    .collect()
+            })
+            .collect();
+
+        let mut order = Vec::with_capacity(self.tables.len());
+        while order.len() < self.tables.len() {
+            let next = (0..self.tables.len()).find(|table| {
+                !order.contains(table) && parents[*table].iter().all(|p| order.contains(p))
+            });
+            match next {
+                Some(table) => order.push(table),
+                None => {
+                    return Err((0..self.tables.len())
+                        .filter(|table| !order.contains(table))
+                        .map(|table| self.tables[table].name.as_str())
+                        .collect());
+                }
+            }
+        }
+        Ok(order)
+    }
+}
+
+/// Generates every table of a validated project, parents first. `generators`
+/// line up with `spec.tables`; tables without `rows` get `default_rows`.
+pub fn generate<G: DataGenerator>(
+    spec: &ProjectSpec,
+    generators: &[G],
+    default_rows: usize,
+    rng: &mut impl Rng,
+) -> Result<Vec<GeneratedTable>, GeneratorError> {
+    let order = spec
+        .table_order()
+        .expect("validated projects have no foreign key cycles");
+    let mut generated: Vec<Option<Table>> = spec.tables.iter().map(|_| None).collect();
+    // The next row index of each table, so orphan keys drawn from a parent's
+    // generator continue after its rows.
+    let mut next_index: Vec<usize> = vec![0; spec.tables.len()];
+
+    for table in order {
+        let name = &spec.tables[table].name;
+        let keys: Vec<&ForeignKey> = spec
+            .foreign_keys
+            .iter()
+            .filter(|key| &key.column.table == name)
+            .collect();
+
+        // The keys of the foreign key that sets the row count, one per row
+        // (`None` for orphans).
+        let counted = keys.iter().find_map(|key| {
+            let children = key.children?;
+            let parent_keys = parent_keys(spec, &generated, key);
+            let mut slots: Vec<Value> = parent_keys
+                .iter()
+                .flat_map(|parent| {
+                    let count = rng.random_range(children.min..=children.max);
+                    std::iter::repeat_n(parent.clone(), count)
+                })
+                .collect();
+            slots.shuffle(rng);
+
+            let orphan_rate = key.orphan_rate.unwrap_or(0.0);
+            let mut rows = Vec::with_capacity(slots.len());
+            for slot in slots {
+                while orphan_rate > 0.0 && rng.random_bool(orphan_rate) {
+                    rows.push(None);
+                }
+                rows.push(Some(slot));
+            }
+            Some((*key, rows))
+        });
+
+        let row_count = match &counted {
+            Some((_, rows)) => rows.len(),
+            None => spec.tables[table].rows.unwrap_or(default_rows),
+        };
+        let generator = &generators[table];
+        generator.check_row_count(row_count)?;
+
+        let mut data = Table {
+            headers: generator.headers(),
+            rows: (1..=row_count)
+                .map(|i| generator.generate_row(i, rng))
+                .collect(),
+        };
+        next_index[table] = row_count;
+
+        for key in keys {
+            let column = column_index(&data, &key.column.column);
+            let parent = spec.table_index(&key.references.table).unwrap();
+            let parent_keys = parent_keys(spec, &generated, key);
+            let known: HashSet<String> = parent_keys.iter().map(Value::to_string).collect();
+            let orphan_rate = key.orphan_rate.unwrap_or(0.0);
+            let assigned = match &counted {
+                Some((counted_key, rows)) if std::ptr::eq(*counted_key, key) => Some(rows),
+                _ => None,
+            };
+
+            let parent_column =
+                column_index(generated[parent].as_ref().unwrap(), &key.references.column);
+
+            for (row_number, row) in data.rows.iter_mut().enumerate() {
+                // A null key (from the column's null_rate) stays null, and
+                // so does every key when the parent has none.
+                if row[column].is_null() || (assigned.is_none() && parent_keys.is_empty()) {
+                    row[column] = Value::Null;
+                    continue;
+                }
+                let value = match assigned {
+                    Some(rows) => rows[row_number].clone(),
+                    None if orphan_rate > 0.0 && rng.random_bool(orphan_rate) => None,
+                    None => Some(parent_keys[rng.random_range(0..parent_keys.len())].clone()),
+                };
+                row[column] = value.unwrap_or_else(|| {
+                    orphan_key(
+                        &generators[parent],
+                        &mut next_index[parent],
+                        parent_column,
+                        &known,
+                        rng,
+                    )
+                });
+            }
+        }
+
+        generated[table] = Some(data);
+    }
+
+    Ok(spec
+        .tables
+        .iter()
+        .zip(generated)
+        .map(|(table, data)| GeneratedTable {
+            name: table.name.clone(),
+            data: data.expect("every table is generated"),
+        })
+        .collect())
+}
+
+fn column_index(data: &Table, column: &str) -> usize {
+    data.headers
+        .iter()
+        .position(|header| header == column)
+        .expect("validated foreign keys name existing columns")
+}
+
+/// The distinct non-null values of a foreign key's parent column, in row
+/// order.
+fn parent_keys(spec: &ProjectSpec, generated: &[Option<Table>], key: &ForeignKey) -> Vec<Value> {
+    let parent = spec.table_index(&key.references.table).unwrap();
+    let data = generated[parent]
+        .as_ref()
+        .expect("parents are generated before their children");
+    let column = column_index(data, &key.references.column);
+    let mut seen = HashSet::new();
+    data.rows
+        .iter()
+        .map(|row| &row[column])
+        .filter(|value| !value.is_null() && seen.insert(value.to_string()))
+        .cloned()
+        .collect()
+}
+
+/// A key the parent's key column could have produced but did not. Drawn from
+/// the rows following the parent's last one, so e.g. ids keep counting up.
+fn orphan_key<G: DataGenerator>(
+    parent: &G,
+    next_index: &mut usize,
+    column: usize,
+    known: &HashSet<String>,
+    rng: &mut impl Rng,
+) -> Value {
+    let mut candidate = Value::Null;
+    for _ in 0..ORPHAN_ATTEMPTS {
+        *next_index += 1;
+        candidate = parent.generate_row(*next_index, rng).swap_remove(column);
+        if !candidate.is_null() && !known.contains(candidate.text().as_ref()) {
+            return candidate;
+        }
+    }
+    (2..)
+        .map(|suffix| format!("{}_{}", candidate, suffix))
+        .find(|value| !known.contains(value))
+        .map(Value::String)
+        .expect("suffixes are distinct")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::SmartGenerator;
+    use crate::schema::ColumnSpec;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn shop(foreign_key: serde_json::Value) -> ProjectSpec {
+        serde_json::from_value(serde_json::json!({
+            "name": "shop",
+            "tables": [
+                {"name": "orders", "dataset_id": 2},
+                {"name": "customers", "dataset_id": 1, "rows": 10}
+            ],
+            "foreign_keys": [foreign_key]
+        }))
+        .unwrap()
+    }
+
+    fn headers() -> HashMap<i64, Vec<String>> {
+        HashMap::from([
+            (1, vec!["id".to_string(), "email".to_string()]),
+            (2, vec!["id".to_string(), "customer_id".to_string()]),
+        ])
+    }
+
+    fn generators() -> Vec<SmartGenerator> {
+        vec![
+            SmartGenerator::from_columns(&[
+                ColumnSpec::new("id", "id"),
+                ColumnSpec::new("customer_id", "auto"),
+            ])
+            .unwrap(),
+            SmartGenerator::from_columns(&[
+                ColumnSpec::new("id", "id"),
+                ColumnSpec {
+                    unique: true,
+                    ..ColumnSpec::new("email", "email")
+                },
+            ])
+            .unwrap(),
+        ]
+    }
+
+    fn column(table: &GeneratedTable, name: &str) -> Vec<String> {
+        let index = table.data.headers.iter().position(|h| h == name).unwrap();
+        table
+            .data
+            .rows
+            .iter()
+            .map(|row| row[index].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_column_ref_parsing() {
+        let reference: ColumnRef = serde_json::from_str("\"orders.customer_id\"").unwrap();
+        assert_eq!(reference.table, "orders");
+        assert_eq!(reference.column, "customer_id");
+        assert_eq!(
+            serde_json::to_string(&reference).unwrap(),
+            "\"orders.customer_id\""
+        );
+
+        let err = serde_json::from_str::<ColumnRef>("\"orders\"").unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("expected 'table.column', got 'orders'")
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let spec = shop(serde_json::json!({
+            "column": "orders.customer_id",
+            "references": "customers.id",
+            "children": {"min": 1, "max": 3}
+        }));
+        assert!(spec.validate(&headers()).is_ok());
+
+        let spec: ProjectSpec = serde_json::from_value(serde_json::json!({
+            "name": "shop",
+            "tables": [
+                {"name": "orders", "dataset_id": 2, "rows": 0},
+                {"name": "orders", "dataset_id": 9}
+            ],
+            "foreign_keys": [
+                {"column": "orders.customer_id", "references": "customers.id"},
+                {"column": "orders.total", "references": "orders.id",
+                 "children": {"min": 4, "max": 2}, "orphan_rate": 1.0}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            spec.validate(&headers()).unwrap_err().0,
+            vec![
+                "tables[0] 'orders': rows must be between 1 and 1000, got 0",
+                "tables[1] 'orders': duplicate table name",
+                "tables[1] 'orders': dataset 9 not found",
+                "foreign_keys[0] 'orders.customer_id -> customers.id': no table 'customers'",
+                "foreign_keys[1] 'orders.total -> orders.id': table 'orders' has no column 'total'",
+                "foreign_keys[1] 'orders.total -> orders.id': a table cannot reference itself",
+                "foreign_keys[1] 'orders.total -> orders.id': children min (4) is greater than max (2)",
+                "foreign_keys[1] 'orders.total -> orders.id': orphan_rate must be at least 0 and below 1, got 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_cycles() {
+        let spec: ProjectSpec = serde_json::from_value(serde_json::json!({
+            "name": "loop",
+            "tables": [
+                {"name": "a", "dataset_id": 2},
+                {"name": "b", "dataset_id": 2},
+                {"name": "c", "dataset_id": 1}
+            ],
+            "foreign_keys": [
+                {"column": "a.customer_id", "references": "b.id"},
+                {"column": "b.customer_id", "references": "a.id"}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            spec.validate(&headers()).unwrap_err().0,
+            vec!["Foreign keys form a cycle between tables: a, b"]
+        );
+    }
+
+    #[test]
+    fn test_generate_fills_child_keys_from_parents() {
+        let spec = shop(serde_json::json!({
+            "column": "orders.customer_id",
+            "references": "customers.id",
+            "children": {"min": 1, "max": 3}
+        }));
+        let mut rng = StdRng::seed_from_u64(4);
+        let tables = generate(&spec, &generators(), 5, &mut rng).unwrap();
+
+        assert_eq!(tables[0].name, "orders");
+        assert_eq!(tables[1].name, "customers");
+        let customer_ids = column(&tables[1], "id");
+        assert_eq!(customer_ids.len(), 10);
+
+        let order_keys = column(&tables[0], "customer_id");
+        assert!((10..=30).contains(&order_keys.len()));
+        for customer in &customer_ids {
+            let orders = order_keys.iter().filter(|key| *key == customer).count();
+            assert!((1..=3).contains(&orders));
+        }
+    }
+
+    #[test]
+    fn test_generate_orphans_and_random_parents() {
+        let spec = shop(serde_json::json!({
+            "column": "orders.customer_id",
+            "references": "customers.email",
+            "orphan_rate": 0.5
+        }));
+        let mut rng = StdRng::seed_from_u64(8);
+        let tables = generate(&spec, &generators(), 200, &mut rng).unwrap();
+
+        let emails = column(&tables[1], "email");
+        let order_keys = column(&tables[0], "customer_id");
+        assert_eq!(order_keys.len(), 200);
+        let orphans = order_keys
+            .iter()
+            .filter(|key| !emails.contains(key))
+            .count();
+        assert!((60..=140).contains(&orphans));
+        assert!(order_keys.iter().all(|key| key.contains('@')));
+    }
+
+    #[test]
+    fn test_generate_is_reproducible() {
+        let spec = shop(serde_json::json!({
+            "column": "orders.customer_id",
+            "references": "customers.id",
+            "children": {"min": 0, "max": 4},
+            "orphan_rate": 0.2
+        }));
+        let run = |seed| {
+            let tables =
+                generate(&spec, &generators(), 5, &mut StdRng::seed_from_u64(seed)).unwrap();
+            tables.into_iter().map(|t| t.data.rows).collect::<Vec<_>>()
+        };
+        assert_eq!(run(3), run(3));
+    }
+}
@@ -0,0 +1,924 @@
+use crate::dictionary::DictionaryEntry;
+use crate::generators::{self, GeneratorError, registry};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// The only schema file version this build understands.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A declarative description of a dataset, loaded from a YAML, JSON or TOML
+/// file:
+///
+/// ```yaml
+/// version: 1
+/// name: customers
+/// columns:
+///   - name: id
+///     type: id
+///   - name: age
+///     type: age
+///     min: 21
+///     max: 65
+///   - name: tier
+///     type: enum
+///     values: [free, pro]
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schema {
+    pub version: u32,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Locale pack of every column that names none, e.g. "de_DE".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    pub columns: Vec<ColumnSpec>,
+}
+
+/// One column of a schema: its type plus optional generator parameters.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnSpec {
+    pub name: String,
+    /// A column type name, or "auto" (the default) to detect it from `name`.
+    #[serde(rename = "type", default = "auto_type")]
+    pub type_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<Bound>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<Bound>,
+    /// strftime format for date and computed columns, or a `#`-digit pattern
+    /// for phones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Digits after the decimal point of money columns.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precision: Option<u32>,
+    /// Symbol prefixed to money values, e.g. "$".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    /// Character count bounds for text columns.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    /// The allowed values of an `enum` column.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<String>>,
+    /// Relative frequency of each of `values`; equally likely without. A
+    /// value left without a weight (`null`) is rejected: weight all or none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<Vec<Option<f64>>>,
+    /// The name column an `email` column builds its addresses from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// The country column whose cities a `city` column picks from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub within: Option<String>,
+    /// Columns whose value in the same row bounds this column's value from
+    /// below or above, e.g. `min_column: start_date` on an end date.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_column: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_column: Option<String>,
+    /// The value of a `computed` column in terms of other columns of the row.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
+    /// How an `id` column numbers rows: "sequence" (the default), "random",
+    /// "uuid_v4", "uuid_v7", "ulid" or "snowflake".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<String>,
+    /// First id and increment of a sequence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<i64>,
+    /// Text put before each id, e.g. "CUST-".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// Digits a numeric id is zero-padded to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<usize>,
+    /// Regular expression the values of a `regex` column match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Name of the saved dictionary a `dictionary` column picks from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dictionary: Option<String>,
+    /// The entries of `dictionary`, loaded from the database before the
+    /// column is validated or generated. `None` when no such dictionary
+    /// exists.
+    #[serde(skip)]
+    pub dictionary_entries: Option<Arc<[DictionaryEntry]>>,
+    /// Locale pack of `name`, `city`, `street`, `postcode`, `phone` and
+    /// `date` columns, e.g. "fr_FR".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Fraction of cells (0 to 1) left empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub null_rate: Option<f64>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub unique: bool,
+}
+
+/// A `min`/`max` bound: a number for numeric columns, a `YYYY-MM-DD` string
+/// for date columns.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Bound {
+    Number(f64),
+    Text(String),
+}
+
+impl std::fmt::Display for Bound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Bound::Number(n) => write!(f, "{}", n),
+            Bound::Text(s) => write!(f, "'{}'", s),
+        }
+    }
+}
+
+fn auto_type() -> String {
+    "auto".to_string()
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+impl ColumnSpec {
+    pub fn new(name: impl Into<String>, type_name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            type_name: type_name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Specs for a plain header list and `column_types` map, as stored by
+    /// datasets that were not imported from a schema file.
+    pub fn from_column_types(
+        headers: &[String],
+        column_types: &HashMap<String, String>,
+    ) -> Vec<Self> {
+        headers
+            .iter()
+            .map(|header| match column_types.get(header) {
+                Some(type_name) => Self::from_type_entry(header, type_name),
+                None => Self::new(header.clone(), auto_type()),
+            })
+            .collect()
+    }
+
+    /// The spec of one `column_types` entry. Besides a type name, an entry
+    /// may list the values of an enum inline, each optionally weighted:
+    /// `"enum: active=80, suspended=15, deleted=5"`. When some values are
+    /// weighted, the others are left without a weight, which the enum type
+    /// rejects.
+    fn from_type_entry(header: &str, type_name: &str) -> Self {
+        let inline = type_name
+            .split_once(':')
+            .filter(|(name, _)| name.trim().eq_ignore_ascii_case("enum"));
+        let Some((_, list)) = inline else {
+            return Self::new(header, type_name);
+        };
+
+        let (values, weights): (Vec<String>, Vec<Option<f64>>) = list
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| match item.rsplit_once('=') {
+                Some((value, weight)) => match weight.trim().trim_end_matches('%').parse() {
+                    Ok(weight) => (value.trim().to_string(), Some(weight)),
+                    Err(_) => (item.to_string(), None),
+                },
+                None => (item.to_string(), None),
+            })
+            .unzip();
+        let weighted = weights.iter().any(Option::is_some);
+        Self {
+            values: Some(values),
+            weights: weighted.then_some(weights),
+            ..Self::new(header, "enum")
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl SchemaFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "yaml" | "yml" => Some(SchemaFormat::Yaml),
+            "json" => Some(SchemaFormat::Json),
+            "toml" => Some(SchemaFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// Maps a request `Content-Type` to a format, ignoring any parameters.
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let mime = content_type.split(';').next()?.trim().to_ascii_lowercase();
+        match mime.as_str() {
+            "application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => {
+                Some(SchemaFormat::Yaml)
+            }
+            "application/json" => Some(SchemaFormat::Json),
+            "application/toml" | "text/toml" | "text/x-toml" => Some(SchemaFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// A problem with a schema file or a dataset's column specs, pointing at the
+/// offending column when there is one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SchemaError {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    /// Position in `columns`, so unnamed columns can still be located.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    pub message: String,
+}
+
+impl SchemaError {
+    pub fn document(message: impl Into<String>) -> Self {
+        Self {
+            column: None,
+            index: None,
+            message: message.into(),
+        }
+    }
+
+    fn column(index: usize, column: &ColumnSpec, message: impl Into<String>) -> Self {
+        Self {
+            column: Some(column.name.clone()),
+            index: Some(index),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.index, self.column.as_deref()) {
+            (Some(index), Some(column)) if !column.is_empty() => {
+                write!(f, "columns[{}] '{}': {}", index, column, self.message)
+            }
+            (Some(index), _) => write!(f, "columns[{}]: {}", index, self.message),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Every problem found in a schema file, reported together.
+#[derive(Debug)]
+pub struct SchemaErrors(pub Vec<SchemaError>);
+
+impl std::fmt::Display for SchemaErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let messages: Vec<String> = self.0.iter().map(|e| e.to_string()).collect();
+        write!(f, "{}", messages.join("\n"))
+    }
+}
+
+impl std::error::Error for SchemaErrors {}
+
+impl Schema {
+    /// Parses and validates a schema file that names no dictionaries.
+    #[cfg(test)]
+    pub fn parse(text: &str, format: SchemaFormat) -> Result<Self, SchemaErrors> {
+        let schema = Self::read(text, format)?;
+        schema.validate()?;
+        Ok(schema)
+    }
+
+    /// Parses a schema file without validating it, for callers that must
+    /// resolve its dictionaries first.
+    pub fn read(text: &str, format: SchemaFormat) -> Result<Self, SchemaErrors> {
+        let parsed = match format {
+            SchemaFormat::Yaml => serde_yaml::from_str(text).map_err(|e| e.to_string()),
+            SchemaFormat::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+            SchemaFormat::Toml => toml::from_str(text).map_err(|e| e.to_string()),
+        };
+        let schema: Schema = parsed.map_err(|message| {
+            SchemaErrors(vec![SchemaError::document(format!(
+                "Invalid schema file: {}",
+                message.trim_end()
+            ))])
+        })?;
+        Ok(schema)
+    }
+
+    pub fn validate(&self) -> Result<(), SchemaErrors> {
+        let mut errors = Vec::new();
+
+        if self.version != SCHEMA_VERSION {
+            errors.push(SchemaError::document(format!(
+                "Unsupported schema version {} (expected {})",
+                self.version, SCHEMA_VERSION
+            )));
+        }
+        if self.name.trim().is_empty() {
+            errors.push(SchemaError::document("Schema name must not be empty"));
+        }
+        if let Some(locale) = &self.locale {
+            errors.extend(locale_error(locale));
+        }
+        if self.columns.is_empty() {
+            errors.push(SchemaError::document(
+                "Schema must define at least one column",
+            ));
+        }
+
+        errors.extend(column_errors(&self.columns));
+        if errors.is_empty() {
+            errors.extend(reference_errors(&self.columns, &self.columns));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(SchemaErrors(errors))
+        }
+    }
+
+    pub fn headers(&self) -> Vec<String> {
+        self.columns.iter().map(|c| c.name.clone()).collect()
+    }
+
+    /// The plain header -> type name map stored alongside every dataset.
+    pub fn column_types(&self) -> HashMap<String, String> {
+        column_type_map(&self.columns)
+    }
+}
+
+/// The problem with a dataset-wide locale code, if it names no bundled pack.
+pub fn locale_error(locale: &str) -> Option<SchemaError> {
+    (!generators::is_locale(locale)).then(|| {
+        SchemaError::document(format!(
+            "Unknown locale '{}' (expected one of: {})",
+            locale,
+            generators::locale_codes().join(", ")
+        ))
+    })
+}
+
+/// The header -> type name map of a list of column specs.
+pub fn column_type_map(columns: &[ColumnSpec]) -> HashMap<String, String> {
+    columns
+        .iter()
+        .map(|c| (c.name.clone(), c.type_name.clone()))
+        .collect()
+}
+
+/// Merges the column specs sent with a saved dataset into its header list.
+/// Every spec must name one of `headers`; headers without a spec fall back to
+/// their `column_types` entry. The result is in header order.
+pub fn dataset_columns(
+    headers: &[String],
+    column_types: Option<&HashMap<String, String>>,
+    columns: &[ColumnSpec],
+) -> Result<Vec<ColumnSpec>, SchemaErrors> {
+    let empty = HashMap::new();
+    let column_types = column_types.unwrap_or(&empty);
+    let plain_types: HashMap<String, String> = column_types
+        .iter()
+        .filter(|(name, _)| !columns.iter().any(|c| c.name == **name))
+        .map(|(name, type_name)| (name.clone(), type_name.clone()))
+        .collect();
+
+    let mut errors = column_errors(columns);
+    errors.extend(column_type_errors(headers, &plain_types));
+    for (index, column) in columns.iter().enumerate() {
+        if !column.name.trim().is_empty() && !headers.contains(&column.name) {
+            errors.push(SchemaError::column(
+                index,
+                column,
+                "no header with this name",
+            ));
+        }
+    }
+    if !errors.is_empty() {
+        return Err(SchemaErrors(errors));
+    }
+
+    let merged: Vec<ColumnSpec> = ColumnSpec::from_column_types(headers, column_types)
+        .into_iter()
+        .map(|derived| {
+            columns
+                .iter()
+                .find(|c| c.name == derived.name)
+                .cloned()
+                .unwrap_or(derived)
+        })
+        .collect();
+
+    let errors = reference_errors(&merged, columns);
+    if errors.is_empty() {
+        Ok(merged)
+    } else {
+        Err(SchemaErrors(errors))
+    }
+}
+
+/// Problems with the entries of a plain `column_types` map for `headers`,
+/// such as unknown type names or malformed inline enums. Entries for names
+/// that are not headers are never used, so they are not checked.
+pub fn column_type_errors(
+    headers: &[String],
+    column_types: &HashMap<String, String>,
+) -> Vec<SchemaError> {
+    ColumnSpec::from_column_types(headers, column_types)
+        .iter()
+        .filter(|column| column_types.contains_key(&column.name))
+        .flat_map(|column| {
+            column_problems(column).into_iter().map(|message| {
+                SchemaError::document(format!("column_types '{}': {}", column.name, message))
+            })
+        })
+        .collect()
+}
+
+/// Problems with columns referring to other columns (`from`, `within`,
+/// `min_column`, `max_column`, `expression`), once each column is valid on
+/// its own. Errors point into `specs`, the columns as they were given.
+fn reference_errors(columns: &[ColumnSpec], specs: &[ColumnSpec]) -> Vec<SchemaError> {
+    let Err(GeneratorError::InvalidParameter { column, message }) =
+        generators::check_columns(columns)
+    else {
+        return Vec::new();
+    };
+    match specs.iter().position(|c| c.name == column) {
+        Some(index) => vec![SchemaError::column(index, &specs[index], message)],
+        None => vec![SchemaError::document(format!(
+            "column '{}': {}",
+            column, message
+        ))],
+    }
+}
+
+/// Name and parameter problems of every column, in order.
+fn column_errors(columns: &[ColumnSpec]) -> Vec<SchemaError> {
+    let mut errors = Vec::new();
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for (index, column) in columns.iter().enumerate() {
+        if column.name.trim().is_empty() {
+            errors.push(SchemaError::column(
+                index,
+                column,
+                "column name must not be empty",
+            ));
+        } else if let Some(first) = seen.insert(column.name.as_str(), index) {
+            errors.push(SchemaError::column(
+                index,
+                column,
+                format!(
+                    "duplicate column name (first defined at columns[{}])",
+                    first
+                ),
+            ));
+        }
+
+        for message in column_problems(column) {
+            errors.push(SchemaError::column(index, column, message));
+        }
+    }
+    errors
+}
+
+fn column_problems(column: &ColumnSpec) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let allowed: Vec<&str> = if generators::is_auto_type(&column.type_name) {
+        Vec::new()
+    } else {
+        match registry().get(&column.type_name) {
+            Some(column_type) => column_type.params().iter().map(|p| p.name).collect(),
+            None => {
+                problems.push(format!(
+                    "unknown type '{}' (expected one of: auto, {})",
+                    column.type_name,
+                    registry().names().join(", ")
+                ));
+                return problems;
+            }
+        }
+    };
+
+    if let Some(rate) = column.null_rate
+        && !(0.0..=1.0).contains(&rate)
+    {
+        problems.push(format!("null_rate must be between 0 and 1, got {}", rate));
+    }
+
+    let given = [
+        ("min", column.min.is_some()),
+        ("max", column.max.is_some()),
+        ("format", column.format.is_some()),
+        ("precision", column.precision.is_some()),
+        ("currency", column.currency.is_some()),
+        ("min_length", column.min_length.is_some()),
+        ("max_length", column.max_length.is_some()),
+        ("values", column.values.is_some()),
+        ("weights", column.weights.is_some()),
+        ("from", column.from.is_some()),
+        ("within", column.within.is_some()),
+        ("min_column", column.min_column.is_some()),
+        ("max_column", column.max_column.is_some()),
+        ("expression", column.expression.is_some()),
+        ("pattern", column.pattern.is_some()),
+        ("strategy", column.strategy.is_some()),
+        ("start", column.start.is_some()),
+        ("step", column.step.is_some()),
+        ("prefix", column.prefix.is_some()),
+        ("width", column.width.is_some()),
+        ("locale", column.locale.is_some()),
+        ("dictionary", column.dictionary.is_some()),
+    ];
+    for (param, _) in given
+        .iter()
+        .filter(|(param, given)| *given && !allowed.contains(param))
+    {
+        problems.push(format!(
+            "'{}' is not supported for type '{}'",
+            param, column.type_name
+        ));
+    }
+
+    // The generator owns the type-specific rules, so a column that passes here
+    // is one it can build.
+    if problems.is_empty()
+        && let Err(GeneratorError::InvalidParameter { message, .. }) =
+            generators::check_column(column)
+    {
+        problems.push(message);
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YAML: &str = r#"
+version: 1
+name: customers
+columns:
+  - name: id
+    type: id
+  - name: age
+    type: age
+    min: 21
+    max: 65
+  - name: signup
+    type: date
+    min: "2021-01-01"
+    max: "2021-12-31"
+    format: "%d/%m/%Y"
+  - name: tier
+    type: enum
+    values: [free, pro]
+    null_rate: 0.1
+  - name: email
+    unique: true
+"#;
+
+    fn messages(result: Result<Schema, SchemaErrors>) -> Vec<String> {
+        result
+            .unwrap_err()
+            .0
+            .iter()
+            .map(|e| e.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_yaml() {
+        let schema = Schema::parse(YAML, SchemaFormat::Yaml).unwrap();
+        assert_eq!(schema.name, "customers");
+        assert_eq!(
+            schema.headers(),
+            vec!["id", "age", "signup", "tier", "email"]
+        );
+        assert_eq!(schema.columns[1].min, Some(Bound::Number(21.0)));
+        assert_eq!(
+            schema.columns[3].values,
+            Some(vec!["free".to_string(), "pro".to_string()])
+        );
+        assert_eq!(schema.columns[4].type_name, "auto");
+        assert!(schema.columns[4].unique);
+    }
+
+    #[test]
+    fn test_parse_json_and_toml_match_yaml() {
+        let json = r#"{
+            "version": 1,
+            "name": "people",
+            "columns": [
+                {"name": "id", "type": "id"},
+                {"name": "price", "type": "money", "min": 1, "max": 9.5}
+            ]
+        }"#;
+        let toml = r#"
+version = 1
+name = "people"
+
+[[columns]]
+name = "id"
+type = "id"
+
+[[columns]]
+name = "price"
+type = "money"
+min = 1
+max = 9.5
+"#;
+        let from_json = Schema::parse(json, SchemaFormat::Json).unwrap();
+        let from_toml = Schema::parse(toml, SchemaFormat::Toml).unwrap();
+        let from_yaml = Schema::parse(json, SchemaFormat::Yaml).unwrap();
+        assert_eq!(from_json, from_toml);
+        assert_eq!(from_json, from_yaml);
+    }
+
+    #[test]
+    fn test_parse_error_reports_location() {
+        let yaml = "version: 1\nname: x\ncolumns:\n  - name: id\n    tpye: id\n";
+        let errors = messages(Schema::parse(yaml, SchemaFormat::Yaml));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("unknown field `tpye`"), "{}", errors[0]);
+        assert!(errors[0].contains("line 5"), "{}", errors[0]);
+    }
+
+    #[test]
+    fn test_validate_points_at_offending_columns() {
+        let yaml = r#"
+version: 1
+name: broken
+columns:
+  - name: age
+    type: age
+    min: 70
+    max: 20
+  - name: when
+    type: date
+    min: "01/02/2020"
+  - name: email
+    type: email
+    values: [a]
+  - name: tier
+    type: enum
+  - name: score
+    type: number
+  - name: age
+    null_rate: 1.5
+"#;
+        let errors = messages(Schema::parse(yaml, SchemaFormat::Yaml));
+        assert_eq!(
+            errors,
+            vec![
+                "columns[0] 'age': min (70) is greater than max (20)",
+                "columns[1] 'when': min must be a date (YYYY-MM-DD), got '01/02/2020'",
+                "columns[2] 'email': 'values' is not supported for type 'email'",
+                "columns[3] 'tier': type 'enum' requires a 'values' list",
+                "columns[4] 'score': unknown type 'number' (expected one of: auto, uuid, \
+                 boolean, id, url, user_agent, percentage, domain, username, company, name, \
+                 email, password_hash, age, city, state, ipv6, ipv4, mac_address, street, \
+                 postcode, country, currency_code, phone, timestamp, time, date, latitude, \
+                 longitude, money, integer, decimal, iban, credit_card, isbn, job_title, \
+                 color, text, enum, dictionary, regex, computed)",
+                "columns[5] 'age': duplicate column name (first defined at columns[0])",
+                "columns[5] 'age': null_rate must be between 0 and 1, got 1.5",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_document_errors() {
+        let json = r#"{"version": 2, "name": " ", "locale": "en_GB", "columns": []}"#;
+        let errors = messages(Schema::parse(json, SchemaFormat::Json));
+        assert_eq!(
+            errors,
+            vec![
+                "Unsupported schema version 2 (expected 1)",
+                "Schema name must not be empty",
+                "Unknown locale 'en_GB' (expected one of: en_US, de_DE, fr_FR, ja_JP, pt_BR)",
+                "Schema must define at least one column",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_date_format() {
+        let mut schema = Schema::parse(YAML, SchemaFormat::Yaml).unwrap();
+        schema.columns[2].format = Some("%Y-%Q".to_string());
+        let errors = messages(schema.validate().map(|_| schema.clone()));
+        assert_eq!(
+            errors,
+            vec!["columns[2] 'signup': invalid date format '%Y-%Q'"]
+        );
+    }
+
+    #[test]
+    fn test_validate_type_parameters() {
+        let yaml = r#"
+version: 1
+name: params
+columns:
+  - name: price
+    type: money
+    min: 5
+    precision: 0
+    currency: "$"
+    min_length: 3
+  - name: note
+    type: text
+    min_length: 30
+  - name: phone
+    type: phone
+    format: "+44 ####"
+  - name: fax
+    type: phone
+    format: "none"
+"#;
+        let errors = messages(Schema::parse(yaml, SchemaFormat::Yaml));
+        assert_eq!(
+            errors,
+            vec![
+                "columns[0] 'price': 'min_length' is not supported for type 'money'",
+                "columns[1] 'note': min (30) is greater than max (12, the default)",
+                "columns[3] 'fax': phone format 'none' needs at least one '#' digit placeholder",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_column_references() {
+        let yaml = r#"
+version: 1
+name: orders
+columns:
+  - name: customer
+    type: name
+  - name: email
+    type: email
+    from: customer
+  - name: city
+    type: city
+    from: customer
+  - name: total
+    type: computed
+    expression: "price *"
+"#;
+        let errors = messages(Schema::parse(yaml, SchemaFormat::Yaml));
+        assert_eq!(
+            errors,
+            vec![
+                "columns[2] 'city': 'from' is not supported for type 'city'",
+                "columns[3] 'total': invalid expression 'price *': unexpected end of expression",
+            ]
+        );
+
+        let yaml = r#"
+version: 1
+name: orders
+columns:
+  - name: shipped
+    type: date
+    min_column: ordered
+  - name: delivered
+    type: date
+    min_column: shipped
+"#;
+        let errors = messages(Schema::parse(yaml, SchemaFormat::Yaml));
+        assert_eq!(
+            errors,
+            vec!["columns[0] 'shipped': no column named 'ordered'"]
+        );
+
+        let headers = vec!["ordered".to_string(), "shipped".to_string()];
+        let mut shipped = ColumnSpec::new("shipped", "date");
+        shipped.min_column = Some("ordered".to_string());
+        assert!(dataset_columns(&headers, None, &[shipped.clone()]).is_ok());
+        shipped.min_column = Some("paid".to_string());
+        let errors = dataset_columns(&headers, None, &[shipped])
+            .unwrap_err()
+            .to_string();
+        assert_eq!(errors, "columns[0] 'shipped': no column named 'paid'");
+    }
+
+    #[test]
+    fn test_dataset_columns_merges_specs_over_headers() {
+        let headers = vec!["id".to_string(), "age".to_string(), "city".to_string()];
+        let mut column_types = HashMap::new();
+        column_types.insert("city".to_string(), "text".to_string());
+        let mut age = ColumnSpec::new("age", "age");
+        age.max = Some(Bound::Number(30.0));
+
+        let columns = dataset_columns(&headers, Some(&column_types), &[age.clone()]).unwrap();
+        assert_eq!(
+            columns,
+            vec![
+                ColumnSpec::new("id", "auto"),
+                age,
+                ColumnSpec::new("city", "text"),
+            ]
+        );
+
+        let errors = dataset_columns(&headers, None, &[ColumnSpec::new("zip", "text")])
+            .unwrap_err()
+            .to_string();
+        assert_eq!(errors, "columns[0] 'zip': no header with this name");
+    }
+
+    #[test]
+    fn test_inline_enum_column_types() {
+        let headers = vec!["status".to_string(), "plan".to_string(), "note".to_string()];
+        let mut column_types = HashMap::new();
+        column_types.insert(
+            "status".to_string(),
+            "enum: active=80%, suspended=15%, deleted=5%".to_string(),
+        );
+        column_types.insert("plan".to_string(), "Enum:free, pro, a=b".to_string());
+        column_types.insert("note".to_string(), "text".to_string());
+
+        let columns = ColumnSpec::from_column_types(&headers, &column_types);
+        assert_eq!(columns[0].type_name, "enum");
+        assert_eq!(
+            columns[0].values,
+            Some(vec![
+                "active".to_string(),
+                "suspended".to_string(),
+                "deleted".to_string()
+            ])
+        );
+        assert_eq!(
+            columns[0].weights,
+            Some(vec![Some(80.0), Some(15.0), Some(5.0)])
+        );
+        assert_eq!(
+            columns[1].values,
+            Some(vec![
+                "free".to_string(),
+                "pro".to_string(),
+                "a=b".to_string()
+            ])
+        );
+        assert_eq!(columns[1].weights, None);
+        assert_eq!(columns[2], ColumnSpec::new("note", "text"));
+
+        let yaml = "version: 1\nname: x\ncolumns:\n  - name: status\n    type: enum\n    values: [a, b]\n    weights: [1]\n  - name: note\n    type: text\n    weights: [1]\n";
+        assert_eq!(
+            messages(Schema::parse(yaml, SchemaFormat::Yaml)),
+            vec![
+                "columns[0] 'status': 'weights' has 1 numbers but there are 2 values",
+                "columns[1] 'note': 'weights' is not supported for type 'text'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_column_spec_round_trips_compactly() {
+        let spec = ColumnSpec::new("id", "id");
+        assert_eq!(
+            serde_json::to_string(&spec).unwrap(),
+            r#"{"name":"id","type":"id"}"#
+        );
+        let back: ColumnSpec = serde_json::from_str(r#"{"name":"id","type":"id"}"#).unwrap();
+        assert_eq!(back, spec);
+    }
+
+    #[test]
+    fn test_format_detection() {
+        assert_eq!(
+            SchemaFormat::from_path(Path::new("schemas/users.YML")),
+            Some(SchemaFormat::Yaml)
+        );
+        assert_eq!(
+            SchemaFormat::from_path(Path::new("users.toml")),
+            Some(SchemaFormat::Toml)
+        );
+        assert_eq!(SchemaFormat::from_path(Path::new("users.csv")), None);
+        assert_eq!(
+            SchemaFormat::from_content_type("application/json; charset=utf-8"),
+            Some(SchemaFormat::Json)
+        );
+        assert_eq!(
+            SchemaFormat::from_content_type("text/yaml"),
+            Some(SchemaFormat::Yaml)
+        );
+        assert_eq!(SchemaFormat::from_content_type("text/plain"), None);
+    }
+}
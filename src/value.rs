@@ -0,0 +1,273 @@
+//! Typed cells of generated tables. Generators produce text; each column
+//! says what kind of value its text stands for, so JSON can write ages as
+//! numbers and nulls as `null` while CSV writes the same text as before.
+use serde::ser::{Serialize, Serializer};
+use std::borrow::Cow;
+use std::fmt;
+
+/// One cell of a generated row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    /// A fixed-point number kept as its digits, e.g. "12.50", so trailing
+    /// zeros survive in text formats.
+    Decimal(String),
+    String(String),
+    /// A date as its column formats it, e.g. "2024-03-01" or "01/03/2024".
+    Date(String),
+    /// A date and time as its column formats it.
+    DateTime(String),
+}
+
+/// What kind of value a column's text stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    Bool,
+    Int,
+    Decimal,
+    String,
+    Date,
+    DateTime,
+}
+
+impl ValueKind {
+    /// Reads generated text as this kind. Text that does not read back the
+    /// same way stays a string, such as an id with a prefix or padding, or
+    /// money with a currency sign.
+    pub fn read(self, text: String) -> Value {
+        match self {
+            ValueKind::Bool => match text.as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => Value::String(text),
+            },
+            ValueKind::Int => match text.parse::<i64>() {
+                Ok(number) if number.to_string() == text => Value::Int(number),
+                _ => Value::String(text),
+            },
+            ValueKind::Decimal if is_decimal(&text) => Value::Decimal(text),
+            ValueKind::Decimal => Value::String(text),
+            ValueKind::String => Value::String(text),
+            ValueKind::Date => Value::Date(text),
+            ValueKind::DateTime => Value::DateTime(text),
+        }
+    }
+}
+
+/// An optional minus, digits and at most one decimal point between digits.
+pub(crate) fn is_decimal(text: &str) -> bool {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, "0"));
+    !whole.is_empty()
+        && !fraction.is_empty()
+        && whole.bytes().all(|b| b.is_ascii_digit())
+        && fraction.bytes().all(|b| b.is_ascii_digit())
+}
+
+impl Value {
+    /// Reads text for whatever it looks like: `true`/`false`, a whole or
+    /// decimal number, a float in scientific notation, an ISO date or
+    /// timestamp, otherwise a string. For columns whose kind varies by row,
+    /// as computed columns' does.
+    pub fn infer(text: String) -> Value {
+        use chrono::{NaiveDate, NaiveDateTime};
+
+        if text == "true" || text == "false" {
+            return ValueKind::Bool.read(text);
+        }
+        if let Value::Int(number) = ValueKind::Int.read(text.clone()) {
+            return Value::Int(number);
+        }
+        if text.contains('.') && is_decimal(&text) {
+            return Value::Decimal(text);
+        }
+        if text.contains(['e', 'E'])
+            && let Ok(number) = text.parse::<f64>()
+            && number.is_finite()
+        {
+            return Value::Float(number);
+        }
+        if NaiveDate::parse_from_str(&text, "%Y-%m-%d").is_ok() {
+            return Value::Date(text);
+        }
+        if NaiveDateTime::parse_from_str(&text, "%Y-%m-%dT%H:%M:%S").is_ok()
+            || NaiveDateTime::parse_from_str(&text, "%Y-%m-%d %H:%M:%S").is_ok()
+        {
+            return Value::DateTime(text);
+        }
+        Value::String(text)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// The value as text formats write it; nulls are empty.
+    pub fn text(&self) -> Cow<'_, str> {
+        match self {
+            Value::Null => Cow::Borrowed(""),
+            Value::Bool(value) => Cow::Borrowed(if *value { "true" } else { "false" }),
+            Value::Int(number) => Cow::Owned(number.to_string()),
+            Value::Float(number) => Cow::Owned(number.to_string()),
+            Value::Decimal(text)
+            | Value::String(text)
+            | Value::Date(text)
+            | Value::DateTime(text) => Cow::Borrowed(text),
+        }
+    }
+
+    /// Whether SQL writes the value as a bare number.
+    pub fn is_number(&self) -> bool {
+        matches!(self, Value::Int(_) | Value::Float(_) | Value::Decimal(_))
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text())
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Self {
+        Value::String(text)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(text: &str) -> Self {
+        Value::String(text.to_string())
+    }
+}
+
+/// Native JSON: numbers, booleans and `null`, with dates as strings.
+/// Decimals are strings too, as a JSON number would round them through an
+/// `f64` and drop digits, so "12.50" stays "12.50".
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_none(),
+            Value::Bool(value) => serializer.serialize_bool(*value),
+            Value::Int(number) => serializer.serialize_i64(*number),
+            Value::Float(number) if number.is_finite() => serializer.serialize_f64(*number),
+            Value::Float(_) => serializer.serialize_none(),
+            Value::Decimal(text)
+            | Value::String(text)
+            | Value::Date(text)
+            | Value::DateTime(text) => serializer.serialize_str(text),
+        }
+    }
+}
+
+/// The text of every cell of a row, nulls as empty strings.
+pub fn texts(row: &[Value]) -> Vec<String> {
+    row.iter().map(|value| value.text().into_owned()).collect()
+}
+
+/// Generated rows of typed cells under their headers; the generated
+/// counterpart of a parsed `CsvData`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    /// The rows as text, as saved for sample data.
+    pub fn text_rows(&self) -> Vec<Vec<String>> {
+        self.rows.iter().map(|row| texts(row)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_kinds() {
+        assert_eq!(ValueKind::Int.read("42".into()), Value::Int(42));
+        assert_eq!(ValueKind::Int.read("-7".into()), Value::Int(-7));
+        assert_eq!(
+            ValueKind::Int.read("0042".into()),
+            Value::String("0042".into())
+        );
+        assert_eq!(
+            ValueKind::Int.read("INV-1".into()),
+            Value::String("INV-1".into())
+        );
+        assert_eq!(
+            ValueKind::Decimal.read("12.50".into()),
+            Value::Decimal("12.50".into())
+        );
+        assert_eq!(
+            ValueKind::Decimal.read("-3".into()),
+            Value::Decimal("-3".into())
+        );
+        for text in ["$12.50", "1.", ".5", "1e5", ""] {
+            assert_eq!(
+                ValueKind::Decimal.read(text.into()),
+                Value::String(text.into())
+            );
+        }
+        assert_eq!(ValueKind::Bool.read("true".into()), Value::Bool(true));
+        assert_eq!(
+            ValueKind::Bool.read("yes".into()),
+            Value::String("yes".into())
+        );
+        assert_eq!(
+            ValueKind::Date.read("01/03/2024".into()),
+            Value::Date("01/03/2024".into())
+        );
+    }
+
+    #[test]
+    fn test_infer() {
+        assert_eq!(Value::infer("12".into()), Value::Int(12));
+        assert_eq!(Value::infer("12.00".into()), Value::Decimal("12.00".into()));
+        assert_eq!(Value::infer("false".into()), Value::Bool(false));
+        assert_eq!(
+            Value::infer("2024-03-01".into()),
+            Value::Date("2024-03-01".into())
+        );
+        assert_eq!(
+            Value::infer("2024-03-01T08:30:00".into()),
+            Value::DateTime("2024-03-01T08:30:00".into())
+        );
+        assert_eq!(Value::infer("2.5e3".into()), Value::Float(2500.0));
+        assert_eq!(Value::infer("$5".into()), Value::String("$5".into()));
+        assert_eq!(Value::infer("e".into()), Value::String("e".into()));
+        assert_eq!(Value::infer("007".into()), Value::String("007".into()));
+    }
+
+    #[test]
+    fn test_text_and_json() {
+        let row = vec![
+            Value::Null,
+            Value::Bool(true),
+            Value::Int(30),
+            Value::Float(0.5),
+            Value::Decimal("9.90".into()),
+            Value::String("Anna".into()),
+            Value::Date("2024-03-01".into()),
+        ];
+        assert_eq!(
+            texts(&row),
+            vec!["", "true", "30", "0.5", "9.90", "Anna", "2024-03-01"]
+        );
+        assert_eq!(
+            serde_json::to_string(&row).unwrap(),
+            r#"[null,true,30,0.5,"9.90","Anna","2024-03-01"]"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Value::Decimal("12345678901234567.89".into())).unwrap(),
+            r#""12345678901234567.89""#
+        );
+        assert_eq!(
+            serde_json::to_string(&Value::Float(f64::NAN)).unwrap(),
+            "null"
+        );
+    }
+}